@@ -0,0 +1,266 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+//! `arbitrary::Arbitrary` implementations for property testing against
+//! realistic random manifests.
+//!
+//! The exports and imports maps are `serde_json` types, so they are
+//! generated as part of the `PackageJson` implementation and through
+//! `arbitrary_exports`/`arbitrary_imports`.
+
+use std::path::PathBuf;
+
+use arbitrary::Arbitrary;
+use arbitrary::Result;
+use arbitrary::Unstructured;
+use deno_semver::package::PackageReq;
+use deno_semver::StackString;
+use deno_semver::VersionReq;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::PackageJson;
+use crate::PackageJsonDepValue;
+use crate::PackageJsonDepWorkspaceReq;
+
+const MAX_DEPTH: usize = 3;
+const CONDITIONS: [&str; 9] = [
+  "import",
+  "require",
+  "node",
+  "deno",
+  "browser",
+  "types",
+  "types@>=5.0",
+  "module-sync",
+  "default",
+];
+
+impl<'a> Arbitrary<'a> for PackageJson {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    let mut obj = Map::new();
+    let name = arbitrary_package_name(u)?;
+    if u.arbitrary()? {
+      obj.insert("name".to_string(), name.clone().into());
+    }
+    if u.arbitrary()? {
+      let version = format!(
+        "{}.{}.{}",
+        u.int_in_range(0..=20u8)?,
+        u.int_in_range(0..=20u8)?,
+        u.int_in_range(0..=20u8)?
+      );
+      obj.insert("version".to_string(), version.into());
+    }
+    if u.arbitrary()? {
+      let typ = *u.choose(&["module", "commonjs", "other"])?;
+      obj.insert("type".to_string(), typ.into());
+    }
+    for field in ["main", "module", "types"] {
+      if u.arbitrary()? {
+        obj.insert(field.to_string(), arbitrary_relative_path(u)?.into());
+      }
+    }
+    if u.arbitrary()? {
+      obj.insert("exports".to_string(), arbitrary_exports_value(u)?);
+    }
+    if u.arbitrary()? {
+      obj.insert("imports".to_string(), Value::Object(arbitrary_imports(u)?));
+    }
+    for field in ["dependencies", "devDependencies", "optionalDependencies"] {
+      if u.arbitrary()? {
+        let mut deps = Map::new();
+        for _ in 0..u.int_in_range(0..=8u8)? {
+          let alias = arbitrary_package_name(u)?;
+          let value = PackageJsonDepValue::arbitrary(u)?;
+          deps.insert(alias, arbitrary_dep_text(u, value)?.into());
+        }
+        obj.insert(field.to_string(), Value::Object(deps));
+      }
+    }
+    if u.arbitrary()? {
+      let mut scripts = Map::new();
+      for _ in 0..u.int_in_range(0..=4u8)? {
+        let name = *u.choose(&["build", "test", "prebuild", "postinstall"])?;
+        scripts.insert(name.to_string(), format!("echo {}", name).into());
+      }
+      obj.insert("scripts".to_string(), Value::Object(scripts));
+    }
+    let path = PathBuf::from(format!("/node_modules/{}/package.json", name));
+    Ok(PackageJson::load_from_value(path, Value::Object(obj)))
+  }
+}
+
+impl<'a> Arbitrary<'a> for PackageJsonDepWorkspaceReq {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    Ok(match u.int_in_range(0..=2u8)? {
+      0 => Self::Tilde,
+      1 => Self::Caret,
+      _ => Self::VersionReq(arbitrary_version_req(u)?),
+    })
+  }
+}
+
+impl<'a> Arbitrary<'a> for PackageJsonDepValue {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    Ok(if u.ratio(1, 4)? {
+      Self::Workspace(PackageJsonDepWorkspaceReq::arbitrary(u)?)
+    } else {
+      Self::Req(PackageReq {
+        name: StackString::from_string(arbitrary_package_name(u)?),
+        version_req: arbitrary_version_req(u)?,
+      })
+    })
+  }
+}
+
+/// Generates a random "exports" map in its normalized form.
+pub fn arbitrary_exports(u: &mut Unstructured) -> Result<Map<String, Value>> {
+  let mut map = Map::new();
+  map.insert(".".to_string(), arbitrary_target(u, 0)?);
+  for _ in 0..u.int_in_range(0..=4u8)? {
+    map.insert(arbitrary_subpath(u, "./")?, arbitrary_target(u, 0)?);
+  }
+  Ok(map)
+}
+
+/// Generates a random "imports" map.
+pub fn arbitrary_imports(u: &mut Unstructured) -> Result<Map<String, Value>> {
+  let mut map = Map::new();
+  for _ in 0..u.int_in_range(1..=4u8)? {
+    map.insert(arbitrary_subpath(u, "#")?, arbitrary_target(u, 0)?);
+  }
+  Ok(map)
+}
+
+fn arbitrary_exports_value(u: &mut Unstructured) -> Result<Value> {
+  // also produce the sugar forms
+  Ok(match u.int_in_range(0..=2u8)? {
+    0 => arbitrary_relative_path(u)?.into(),
+    1 => arbitrary_conditions(u, 0)?,
+    _ => Value::Object(arbitrary_exports(u)?),
+  })
+}
+
+fn arbitrary_target(u: &mut Unstructured, depth: usize) -> Result<Value> {
+  let max = if depth >= MAX_DEPTH { 1 } else { 3 };
+  Ok(match u.int_in_range(0..=max)? {
+    0 => arbitrary_relative_path(u)?.into(),
+    1 => Value::Null,
+    2 => arbitrary_conditions(u, depth + 1)?,
+    _ => {
+      let mut items = Vec::new();
+      for _ in 0..u.int_in_range(1..=3u8)? {
+        items.push(arbitrary_target(u, depth + 1)?);
+      }
+      Value::Array(items)
+    }
+  })
+}
+
+fn arbitrary_conditions(u: &mut Unstructured, depth: usize) -> Result<Value> {
+  let mut map = Map::new();
+  for _ in 0..u.int_in_range(1..=4u8)? {
+    let condition = *u.choose(&CONDITIONS)?;
+    map.insert(condition.to_string(), arbitrary_target(u, depth)?);
+  }
+  Ok(Value::Object(map))
+}
+
+fn arbitrary_subpath(u: &mut Unstructured, prefix: &str) -> Result<String> {
+  let mut subpath = format!("{}{}", prefix, arbitrary_identifier(u)?);
+  if u.arbitrary()? {
+    subpath.push_str("/*");
+  }
+  Ok(subpath)
+}
+
+fn arbitrary_relative_path(u: &mut Unstructured) -> Result<String> {
+  let extension = *u.choose(&["js", "mjs", "cjs", "d.ts"])?;
+  Ok(format!("./{}.{}", arbitrary_identifier(u)?, extension))
+}
+
+fn arbitrary_package_name(u: &mut Unstructured) -> Result<String> {
+  let name = arbitrary_identifier(u)?;
+  Ok(if u.ratio(1, 3)? {
+    format!("@{}/{}", arbitrary_identifier(u)?, name)
+  } else {
+    name
+  })
+}
+
+fn arbitrary_identifier(u: &mut Unstructured) -> Result<String> {
+  const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789-";
+  let len = u.int_in_range(1..=12u8)?;
+  let mut text = String::with_capacity(len as usize);
+  // start with a letter so the name is valid
+  text.push(*u.choose(&CHARS[..26])? as char);
+  for _ in 1..len {
+    text.push(*u.choose(CHARS)? as char);
+  }
+  Ok(text)
+}
+
+fn arbitrary_version_req(u: &mut Unstructured) -> Result<VersionReq> {
+  let (major, minor, patch) = (
+    u.int_in_range(0..=20u8)?,
+    u.int_in_range(0..=20u8)?,
+    u.int_in_range(0..=20u8)?,
+  );
+  let text = match u.int_in_range(0..=5u8)? {
+    0 => "*".to_string(),
+    1 => format!("^{}.{}.{}", major, minor, patch),
+    2 => format!("~{}.{}", major, minor),
+    3 => format!("{}.x", major),
+    4 => format!(">={}.{}.{} <{}.0.0", major, minor, patch, major + 1),
+    _ => format!("{}.{}.{}", major, minor, patch),
+  };
+  Ok(VersionReq::parse_from_npm(&text).unwrap())
+}
+
+fn arbitrary_dep_text(
+  u: &mut Unstructured,
+  value: PackageJsonDepValue,
+) -> Result<String> {
+  Ok(match value {
+    PackageJsonDepValue::Req(req) => {
+      if u.arbitrary()? {
+        format!("npm:{}@{}", req.name, req.version_req)
+      } else {
+        req.version_req.to_string()
+      }
+    }
+    PackageJsonDepValue::Workspace(PackageJsonDepWorkspaceReq::Tilde) => {
+      "workspace:~".to_string()
+    }
+    PackageJsonDepValue::Workspace(PackageJsonDepWorkspaceReq::Caret) => {
+      "workspace:^".to_string()
+    }
+    PackageJsonDepValue::Workspace(PackageJsonDepWorkspaceReq::VersionReq(
+      req,
+    )) => format!("workspace:{}", req),
+    PackageJsonDepValue::Raw { value, .. } => value,
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn generates_package_jsons() {
+    let data = (0..4096u32)
+      .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+      .collect::<Vec<_>>();
+    let mut u = Unstructured::new(&data);
+    for _ in 0..16 {
+      let package_json = PackageJson::arbitrary(&mut u).unwrap();
+      for (_, value) in package_json
+        .resolve_local_package_json_deps()
+        .dependencies
+        .iter()
+      {
+        assert!(value.is_ok());
+      }
+    }
+  }
+}
@@ -0,0 +1,171 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use crate::PackageJson;
+
+/// The executables of a package after applying npm's rules to "bin".
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageJsonBinEntries {
+  /// Executable name to the target path relative to the package directory.
+  pub entries: IndexMap<String, String>,
+  /// Entries that were overwritten by a later entry with the same name
+  /// after sanitizing.
+  pub conflicts: Vec<PackageJsonBinConflict>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageJsonBinConflict {
+  /// The sanitized executable name.
+  pub name: String,
+  /// The target that was overwritten.
+  pub overwritten_path: String,
+}
+
+impl PackageJson {
+  /// Resolves the "bin" field to a map of executable names to relative
+  /// paths following npm-normalize-package-bin.
+  ///
+  /// A string "bin" is named after the unscoped package name, names are
+  /// reduced to their last path component, and targets are normalized so
+  /// they can't point outside the package directory.
+  pub fn resolve_bin_entries(
+    &self,
+    package_name: &str,
+  ) -> PackageJsonBinEntries {
+    let mut result = PackageJsonBinEntries::default();
+    let raw_entries: Vec<(&str, &str)> = match &self.bin {
+      Some(Value::String(path)) => vec![(package_name, path)],
+      Some(Value::Object(map)) => map
+        .iter()
+        .filter_map(|(name, path)| Some((name.as_str(), path.as_str()?)))
+        .collect(),
+      _ => return result,
+    };
+    for (name, path) in raw_entries {
+      let Some(name) = sanitize_bin_name(name) else {
+        continue;
+      };
+      let Some(path) = normalize_bin_path(path) else {
+        continue;
+      };
+      if let Some(overwritten_path) = result.entries.insert(name.clone(), path)
+      {
+        result.conflicts.push(PackageJsonBinConflict {
+          name,
+          overwritten_path,
+        });
+      }
+    }
+    result
+  }
+}
+
+/// Gets the base name of the bin like npm-normalize-package-bin, which
+/// also treats `\` and `:` as path separators.
+fn sanitize_bin_name(name: &str) -> Option<String> {
+  let separators = [':', '/', '\\'];
+  let name = name.trim_end_matches(separators);
+  let name = name.rsplit(separators).next().unwrap_or(name);
+  match name {
+    "" | "." | ".." => None,
+    name => Some(name.to_string()),
+  }
+}
+
+/// Normalizes the path as if it were joined to the package root.
+fn normalize_bin_path(path: &str) -> Option<String> {
+  let mut segments = Vec::new();
+  for segment in path.split(['/', '\\']) {
+    match segment {
+      "" | "." => {}
+      ".." => {
+        segments.pop();
+      }
+      segment => segments.push(segment),
+    }
+  }
+  if segments.is_empty() {
+    None
+  } else {
+    Some(segments.join("/"))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn resolve(bin: Value, package_name: &str) -> PackageJsonBinEntries {
+    PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({ "bin": bin }),
+    )
+    .resolve_bin_entries(package_name)
+  }
+
+  #[test]
+  fn string_bin() {
+    let result = resolve("./cli.js".into(), "@scope/pkg");
+    assert_eq!(
+      result.entries,
+      IndexMap::from([("pkg".to_string(), "cli.js".to_string())])
+    );
+    assert!(result.conflicts.is_empty());
+  }
+
+  #[test]
+  fn map_bin() {
+    let result = resolve(
+      serde_json::json!({
+        "a": "./bin/a.js",
+        "../../b": "../../../b.js",
+        "..": "./dotdot.js",
+        "c": "./",
+        "nested/a": "bin\\\\other-a.js",
+        "d": 5,
+      }),
+      "pkg",
+    );
+    assert_eq!(
+      result.entries,
+      IndexMap::from([
+        ("a".to_string(), "bin/other-a.js".to_string()),
+        ("b".to_string(), "b.js".to_string()),
+      ])
+    );
+    assert_eq!(
+      result.conflicts,
+      vec![PackageJsonBinConflict {
+        name: "a".to_string(),
+        overwritten_path: "bin/a.js".to_string(),
+      }]
+    );
+  }
+
+  #[test]
+  fn bin_name_separators() {
+    let result = resolve(
+      serde_json::json!({
+        "a:b": "./b.js",
+        "c/": "./c.js",
+        "d\\e:\\": "./e.js",
+        ":/": "./empty.js",
+      }),
+      "pkg",
+    );
+    assert_eq!(
+      result.entries,
+      IndexMap::from([
+        ("b".to_string(), "b.js".to_string()),
+        ("c".to_string(), "c.js".to_string()),
+        ("e".to_string(), "e.js".to_string()),
+      ])
+    );
+  }
+}
@@ -0,0 +1,240 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::path::Path;
+
+use indexmap::IndexMap;
+use serde::de::Visitor;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde_json::value::RawValue;
+use serde_json::Map;
+use serde_json::Number;
+use serde_json::Value;
+
+use crate::NodeModuleKind;
+use crate::PackageJson;
+use crate::PackageJsonLoadError;
+use crate::PackageJsonLoadErrorKind;
+
+/// A read-only view of a package.json that borrows from the source text.
+///
+/// Only the top level of the document is parsed. String fields borrow
+/// from the source unless they contain escape sequences, and the other
+/// fields are kept as raw JSON until requested.
+#[derive(Debug, Clone)]
+pub struct PackageJsonRef<'a> {
+  pub path: &'a Path,
+  pub name: Option<Cow<'a, str>>,
+  pub version: Option<Cow<'a, str>>,
+  /// "module", "commonjs", or "none".
+  pub typ: &'static str,
+  pub types: Option<Cow<'a, str>>,
+  main: Option<Cow<'a, str>>,
+  module: Option<Cow<'a, str>>,
+  raw: IndexMap<BorrowedStr<'a>, &'a RawValue>,
+}
+
+impl<'a> PackageJsonRef<'a> {
+  pub fn parse(
+    path: &'a Path,
+    source: &'a str,
+  ) -> Result<PackageJsonRef<'a>, PackageJsonLoadError> {
+    let raw: IndexMap<BorrowedStr<'a>, &'a RawValue> =
+      if source.trim().is_empty() {
+        IndexMap::new()
+      } else {
+        let deserialize_error = |err| {
+          PackageJsonLoadErrorKind::Deserialize {
+            path: path.to_path_buf(),
+            source: err,
+          }
+          .into_box()
+        };
+        let value: &RawValue =
+          serde_json::from_str(source).map_err(deserialize_error)?;
+        if value.get().starts_with('{') {
+          serde_json::from_str(value.get()).map_err(deserialize_error)?
+        } else {
+          IndexMap::new()
+        }
+      };
+    let string_field = |name: &str| {
+      let value: &'a RawValue = raw.get(name).copied()?;
+      match serde_json::from_str::<BorrowedStr<'a>>(value.get()) {
+        Ok(value) => Some(value.0),
+        // numbers are converted to strings like the owned loader does
+        Err(_) => serde_json::from_str::<Number>(value.get())
+          .ok()
+          .map(|number| Cow::Owned(number.to_string())),
+      }
+    };
+    let typ = match string_field("type").as_deref() {
+      Some("module") => "module",
+      Some("commonjs") => "commonjs",
+      _ => "none",
+    };
+    Ok(PackageJsonRef {
+      path,
+      name: string_field("name"),
+      version: string_field("version"),
+      typ,
+      // for typescript, it looks for "typings" first, then "types"
+      types: if raw.contains_key("typings") {
+        string_field("typings")
+      } else {
+        string_field("types")
+      },
+      main: string_field("main"),
+      module: string_field("module"),
+      raw,
+    })
+  }
+
+  pub fn main(&self, referrer_kind: NodeModuleKind) -> Option<&str> {
+    self.main_with_conditions(referrer_kind.conditions())
+  }
+
+  /// See `PackageJson::main_with_conditions`.
+  pub fn main_with_conditions(&self, conditions: &[&str]) -> Option<&str> {
+    crate::select_main(
+      self.typ,
+      self.main.as_deref(),
+      self.module.as_deref(),
+      conditions,
+      false,
+    )
+  }
+
+  /// Gets the unparsed JSON of a top level field.
+  pub fn raw_field(&self, name: &str) -> Option<&'a RawValue> {
+    self.raw.get(name).copied()
+  }
+
+  /// Fully parses the package.json into an owned `PackageJson`.
+  pub fn to_owned(&self) -> PackageJson {
+    let package_json = self
+      .raw
+      .iter()
+      .filter_map(|(key, value)| {
+        let value = serde_json::from_str::<Value>(value.get()).ok()?;
+        Some((key.0.to_string(), value))
+      })
+      .collect::<Map<_, _>>();
+    PackageJson::load_from_value(
+      self.path.to_path_buf(),
+      Value::Object(package_json),
+    )
+  }
+}
+
+/// A string that's borrowed from the source text when it has no escapes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct BorrowedStr<'a>(Cow<'a, str>);
+
+impl std::borrow::Borrow<str> for BorrowedStr<'_> {
+  fn borrow(&self) -> &str {
+    &self.0
+  }
+}
+
+impl<'de> Deserialize<'de> for BorrowedStr<'de> {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    struct BorrowedStrVisitor;
+
+    impl<'de> Visitor<'de> for BorrowedStrVisitor {
+      type Value = BorrowedStr<'de>;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string")
+      }
+
+      fn visit_borrowed_str<E>(
+        self,
+        value: &'de str,
+      ) -> Result<Self::Value, E> {
+        Ok(BorrowedStr(Cow::Borrowed(value)))
+      }
+
+      fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(BorrowedStr(Cow::Owned(value.to_string())))
+      }
+
+      fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
+        Ok(BorrowedStr(Cow::Owned(value)))
+      }
+    }
+
+    deserializer.deserialize_str(BorrowedStrVisitor)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn borrows_from_source() {
+    let source = r#"{
+      "name": "pkg",
+      "version": "1.0.0",
+      "type": "module",
+      "main": "./main.js",
+      "module": "./module.js",
+      "exports": { ".": "./index.js" }
+    }"#;
+    let path = Path::new("/package.json");
+    let package_json = PackageJsonRef::parse(path, source).unwrap();
+    assert!(matches!(package_json.name, Some(Cow::Borrowed("pkg"))));
+    assert_eq!(package_json.typ, "module");
+    assert_eq!(package_json.main(NodeModuleKind::Esm), Some("./module.js"));
+    assert_eq!(package_json.main(NodeModuleKind::Cjs), Some("./main.js"));
+    assert_eq!(
+      package_json.raw_field("exports").unwrap().get(),
+      r#"{ ".": "./index.js" }"#
+    );
+
+    let owned = package_json.to_owned();
+    assert_eq!(owned.name.as_deref(), Some("pkg"));
+    assert_eq!(owned.raw_module(), Some("./module.js"));
+    assert_eq!(
+      Value::Object(owned.exports.unwrap().as_ref().clone()),
+      serde_json::json!({ ".": "./index.js" })
+    );
+  }
+
+  #[test]
+  fn non_object_and_malformed() {
+    let path = Path::new("/package.json");
+    let package_json = PackageJsonRef::parse(path, "[]").unwrap();
+    assert!(package_json.name.is_none());
+    assert_eq!(package_json.typ, "none");
+    assert!(PackageJsonRef::parse(path, "{").is_err());
+  }
+
+  #[test]
+  fn matches_owned_loader() {
+    let path = Path::new("/package.json");
+    for source in [
+      r#"{ "name": 1, "version": 1.5, "types": "./a.d.ts" }"#,
+      r#"{ "typings": null, "types": "./a.d.ts" }"#,
+      r#"{ "typings": "./a.d.ts", "types": "./b.d.ts" }"#,
+      r#"{ "version": true, "typings": 1 }"#,
+    ] {
+      let package_json = PackageJsonRef::parse(path, source).unwrap();
+      let owned =
+        PackageJson::load_from_string(path.to_path_buf(), source).unwrap();
+      assert_eq!(package_json.name.as_deref(), owned.name.as_deref());
+      assert_eq!(package_json.version.as_deref(), owned.version.as_deref());
+      assert_eq!(package_json.types.as_deref(), owned.types.as_deref());
+    }
+    let package_json =
+      PackageJsonRef::parse(path, r#"{ "version": 1.5 }"#).unwrap();
+    assert_eq!(package_json.version.as_deref(), Some("1.5"));
+  }
+}
@@ -0,0 +1,31 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+//! Canonical condition sets for resolving "exports" and "imports".
+//!
+//! The "default" condition isn't included because it always matches.
+
+/// Conditions Node.js uses when importing an ES module.
+pub const DEFAULT_NODE_CONDITIONS: &[&str] =
+  &["node-addons", "node", "import", "module-sync"];
+
+/// Conditions Node.js uses when requiring a module.
+pub const DEFAULT_NODE_REQUIRE_CONDITIONS: &[&str] =
+  &["node-addons", "node", "require", "module-sync"];
+
+/// Conditions Deno uses when importing an npm package.
+pub const DENO_CONDITIONS: &[&str] = &["deno", "node", "import", "module-sync"];
+
+/// Conditions Deno uses when requiring a module.
+pub const DENO_REQUIRE_CONDITIONS: &[&str] =
+  &["deno", "node", "require", "module-sync"];
+
+/// Conditions used by bundlers targeting browsers.
+pub const BROWSER_CONDITIONS: &[&str] = &["browser", "import", "module"];
+
+/// Conditions TypeScript uses when resolving declaration files for an
+/// ES module import.
+pub const TYPES_CONDITIONS: &[&str] = &["types", "node", "import"];
+
+/// Conditions TypeScript uses when resolving declaration files for a
+/// require.
+pub const TYPES_REQUIRE_CONDITIONS: &[&str] = &["types", "node", "require"];
@@ -0,0 +1,79 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use indexmap::IndexMap;
+
+use crate::PackageJson;
+use crate::PackageJsonDepValue;
+
+impl PackageJson {
+  /// Converts the npm dependencies to entries for a deno.json "imports"
+  /// field (ex. `"chalk": "npm:chalk@^5.0.0"`).
+  ///
+  /// Packages from JSR's npm compatibility layer (ex. `@jsr/std__path`)
+  /// are mapped to `jsr:` specifiers. Workspace dependencies and
+  /// dependencies that failed to parse are skipped.
+  pub fn to_deno_json_imports(&self) -> IndexMap<String, String> {
+    let deps = self.resolve_local_package_json_deps();
+    let mut imports = IndexMap::new();
+    for (_, alias, value) in deps.valid() {
+      let PackageJsonDepValue::Req(req) = value else {
+        continue;
+      };
+      let specifier = match jsr_package_name(&req.name) {
+        Some(name) => format!("jsr:{}@{}", name, req.version_req),
+        None => format!("npm:{}@{}", req.name, req.version_req),
+      };
+      // dependencies win over dev dependencies
+      imports.entry(alias.to_string()).or_insert(specifier);
+    }
+    imports
+  }
+}
+
+/// Gets the JSR package name of a package from JSR's npm compatibility
+/// layer (ex. `@jsr/std__path` -> `@std/path`).
+fn jsr_package_name(npm_name: &str) -> Option<String> {
+  let (scope, name) = npm_name.strip_prefix("@jsr/")?.split_once("__")?;
+  if scope.is_empty() || name.is_empty() {
+    return None;
+  }
+  Some(format!("@{}/{}", scope, name))
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn to_deno_json_imports() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "dependencies": {
+          "chalk": "^5.0.0",
+          "@std/path": "npm:@jsr/std__path@^1.0.0",
+          "alias": "npm:other@1",
+          "workspace": "workspace:*",
+          "invalid": "git:something",
+        },
+        "devDependencies": {
+          "chalk": "^4.0.0",
+          "@types/node": "*",
+        },
+      }),
+    );
+    assert_eq!(
+      package_json.to_deno_json_imports(),
+      IndexMap::from([
+        ("chalk".to_string(), "npm:chalk@^5.0.0".to_string()),
+        ("@std/path".to_string(), "jsr:@std/path@^1.0.0".to_string()),
+        ("alias".to_string(), "npm:other@1".to_string()),
+        ("@types/node".to_string(), "npm:@types/node@*".to_string()),
+      ])
+    );
+  }
+}
@@ -0,0 +1,169 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use crate::PackageJson;
+
+/// Where a dependency is fetched from when it's not the registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackageJsonDepSource {
+  /// A git repository (ex. `git+https://...`, `github:user/repo` or
+  /// `user/repo`).
+  Git,
+  /// A tarball downloaded over http (ex. `https://example.com/pkg.tgz`).
+  Tarball,
+  /// A path on the local file system (ex. `file:../pkg` or `link:../pkg`).
+  Local,
+}
+
+impl PackageJsonDepSource {
+  /// Gets where the dependency specifier is sourced from, or `None` when
+  /// it's from the registry or the workspace.
+  pub fn from_specifier(specifier: &str) -> Option<Self> {
+    const GIT_PREFIXES: [&str; 6] =
+      ["git:", "git+", "github:", "gitlab:", "bitbucket:", "gist:"];
+    const LOCAL_PREFIXES: [&str; 7] =
+      ["file:", "link:", "portal:", "./", "../", "/", "~/"];
+    let specifier = specifier.trim();
+    if GIT_PREFIXES.iter().any(|p| specifier.starts_with(p)) {
+      Some(Self::Git)
+    } else if LOCAL_PREFIXES.iter().any(|p| specifier.starts_with(p)) {
+      Some(Self::Local)
+    } else if specifier.starts_with("http:") || specifier.starts_with("https:")
+    {
+      let path = specifier.split(['#', '?']).next().unwrap();
+      if path.ends_with(".git") {
+        Some(Self::Git)
+      } else {
+        Some(Self::Tarball)
+      }
+    } else if is_github_shorthand(specifier) {
+      Some(Self::Git)
+    } else {
+      None
+    }
+  }
+}
+
+/// The `user/repo` shorthand for GitHub repositories.
+fn is_github_shorthand(specifier: &str) -> bool {
+  let repo = specifier.split('#').next().unwrap();
+  match repo.split_once('/') {
+    Some((user, repo)) => {
+      let is_part = |part: &str| {
+        !part.is_empty()
+          && part
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+      };
+      is_part(user) && is_part(repo)
+    }
+    None => false,
+  }
+}
+
+/// A dependency that's not fetched from the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageJsonNonRegistryDep {
+  /// The dependency section (ex. `devDependencies`).
+  pub section: &'static str,
+  pub alias: String,
+  pub specifier: String,
+  pub source: PackageJsonDepSource,
+}
+
+impl PackageJson {
+  /// Lists the dependencies sourced from outside the registry (git
+  /// repositories, http tarballs and local paths), which is useful for
+  /// enforcing security policies.
+  pub fn insecure_dependency_sources(&self) -> Vec<PackageJsonNonRegistryDep> {
+    let mut deps = Vec::new();
+    for (section, map) in [
+      ("dependencies", self.dependencies.as_deref()),
+      ("devDependencies", self.dev_dependencies.as_deref()),
+      (
+        "optionalDependencies",
+        self.optional_dependencies.as_deref(),
+      ),
+    ] {
+      for (alias, specifier) in map.into_iter().flatten() {
+        if let Some(source) = PackageJsonDepSource::from_specifier(specifier) {
+          deps.push(PackageJsonNonRegistryDep {
+            section,
+            alias: alias.clone(),
+            specifier: specifier.clone(),
+            source,
+          });
+        }
+      }
+    }
+    deps
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn insecure_dependency_sources() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "dependencies": {
+          "registry": "^1.0.0",
+          "alias": "npm:other@^1",
+          "range": "1.x - 1.3",
+          "workspace": "workspace:*",
+          "git": "git+ssh://git@github.com/user/repo.git",
+          "shorthand": "user/repo#v1",
+          "git-https": "https://github.com/user/repo.git#v1",
+          "tarball": "https://example.com/pkg.tgz",
+        },
+        "devDependencies": {
+          "file": "file:../pkg",
+        },
+        "optionalDependencies": {
+          "link": "link:../other",
+        },
+      }),
+    );
+    assert_eq!(
+      package_json
+        .insecure_dependency_sources()
+        .into_iter()
+        .map(|dep| (dep.section, dep.alias, dep.source))
+        .collect::<Vec<_>>(),
+      vec![
+        ("dependencies", "git".to_string(), PackageJsonDepSource::Git),
+        (
+          "dependencies",
+          "shorthand".to_string(),
+          PackageJsonDepSource::Git
+        ),
+        (
+          "dependencies",
+          "git-https".to_string(),
+          PackageJsonDepSource::Git
+        ),
+        (
+          "dependencies",
+          "tarball".to_string(),
+          PackageJsonDepSource::Tarball
+        ),
+        (
+          "devDependencies",
+          "file".to_string(),
+          PackageJsonDepSource::Local
+        ),
+        (
+          "optionalDependencies",
+          "link".to_string(),
+          PackageJsonDepSource::Local
+        ),
+      ]
+    );
+  }
+}
@@ -0,0 +1,204 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::collections::HashMap;
+
+use deno_semver::StackString;
+
+use crate::PackageJsonDepValue;
+use crate::PackageJsonDepValueParseError;
+
+pub type PackageJsonDepsMapEntry =
+  Result<PackageJsonDepValue, PackageJsonDepValueParseError>;
+
+/// Maps with up to this many entries are searched linearly.
+const LINEAR_SEARCH_MAX_LEN: usize = 16;
+
+/// The resolved dependencies of a package.json keyed by alias in
+/// declaration order.
+///
+/// Most package.json files only have a handful of dependencies, where
+/// searching a `Vec` is faster than hashing, so an index is only built
+/// for larger maps.
+#[derive(Debug, Clone, Default)]
+pub struct PackageJsonDepsMap {
+  entries: Vec<(StackString, PackageJsonDepsMapEntry)>,
+  index: Option<HashMap<StackString, usize>>,
+}
+
+impl PackageJsonDepsMap {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self {
+      entries: Vec::with_capacity(capacity),
+      index: None,
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  pub fn get(&self, alias: &str) -> Option<&PackageJsonDepsMapEntry> {
+    self.get_index_of(alias).map(|index| &self.entries[index].1)
+  }
+
+  pub fn contains_key(&self, alias: &str) -> bool {
+    self.get_index_of(alias).is_some()
+  }
+
+  pub fn get_index_of(&self, alias: &str) -> Option<usize> {
+    match &self.index {
+      Some(index) => index.get(alias).copied(),
+      None => self.entries.iter().position(|(key, _)| key == alias),
+    }
+  }
+
+  pub fn get_index(
+    &self,
+    index: usize,
+  ) -> Option<(&StackString, &PackageJsonDepsMapEntry)> {
+    self.entries.get(index).map(|(key, value)| (key, value))
+  }
+
+  /// Inserts the entry, keeping the position of an existing entry with
+  /// the same alias and returning its previous value.
+  pub fn insert(
+    &mut self,
+    alias: StackString,
+    value: PackageJsonDepsMapEntry,
+  ) -> Option<PackageJsonDepsMapEntry> {
+    if let Some(index) = self.get_index_of(&alias) {
+      return Some(std::mem::replace(&mut self.entries[index].1, value));
+    }
+    if let Some(index) = &mut self.index {
+      index.insert(alias.clone(), self.entries.len());
+    } else if self.entries.len() == LINEAR_SEARCH_MAX_LEN {
+      let mut index = self
+        .entries
+        .iter()
+        .enumerate()
+        .map(|(i, (key, _))| (key.clone(), i))
+        .collect::<HashMap<_, _>>();
+      index.insert(alias.clone(), self.entries.len());
+      self.index = Some(index);
+    }
+    self.entries.push((alias, value));
+    None
+  }
+
+  pub fn iter(
+    &self,
+  ) -> impl DoubleEndedIterator<Item = (&StackString, &PackageJsonDepsMapEntry)>
+       + ExactSizeIterator {
+    self.entries.iter().map(|(key, value)| (key, value))
+  }
+
+  pub fn keys(&self) -> impl DoubleEndedIterator<Item = &StackString> {
+    self.entries.iter().map(|(key, _)| key)
+  }
+
+  pub fn values(
+    &self,
+  ) -> impl DoubleEndedIterator<Item = &PackageJsonDepsMapEntry> {
+    self.entries.iter().map(|(_, value)| value)
+  }
+}
+
+/// Maps are equal when they have the same entries in any order.
+impl PartialEq for PackageJsonDepsMap {
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len()
+      && self
+        .iter()
+        .all(|(key, value)| other.get(key).is_some_and(|v| v == value))
+  }
+}
+
+impl Eq for PackageJsonDepsMap {}
+
+impl FromIterator<(StackString, PackageJsonDepsMapEntry)>
+  for PackageJsonDepsMap
+{
+  fn from_iter<
+    T: IntoIterator<Item = (StackString, PackageJsonDepsMapEntry)>,
+  >(
+    iter: T,
+  ) -> Self {
+    let iter = iter.into_iter();
+    let mut map = Self::with_capacity(iter.size_hint().0);
+    for (key, value) in iter {
+      map.insert(key, value);
+    }
+    map
+  }
+}
+
+impl IntoIterator for PackageJsonDepsMap {
+  type Item = (StackString, PackageJsonDepsMapEntry);
+  type IntoIter = std::vec::IntoIter<Self::Item>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.entries.into_iter()
+  }
+}
+
+impl<'a> IntoIterator for &'a PackageJsonDepsMap {
+  type Item = (&'a StackString, &'a PackageJsonDepsMapEntry);
+  type IntoIter = std::iter::Map<
+    std::slice::Iter<'a, (StackString, PackageJsonDepsMapEntry)>,
+    fn(
+      &'a (StackString, PackageJsonDepsMapEntry),
+    ) -> (&'a StackString, &'a PackageJsonDepsMapEntry),
+  >;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.entries.iter().map(|(key, value)| (key, value))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use deno_semver::package::PackageReq;
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn entry(text: &str) -> PackageJsonDepValue {
+    PackageJsonDepValue::Req(PackageReq::from_str(text).unwrap())
+  }
+
+  #[test]
+  fn keeps_insertion_order_when_growing() {
+    let mut map = PackageJsonDepsMap::new();
+    let aliases = (0..40)
+      .rev()
+      .map(|i| format!("pkg-{i}"))
+      .collect::<Vec<_>>();
+    for alias in &aliases {
+      assert!(map
+        .insert(StackString::from_str(alias), Ok(entry("a@1")))
+        .is_none());
+    }
+    assert!(map.index.is_some());
+    assert_eq!(
+      map.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+      aliases.iter().map(|a| a.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!(map.get("pkg-3"), Some(&Ok(entry("a@1"))));
+    assert_eq!(
+      map.insert(StackString::from_str("pkg-3"), Ok(entry("b@2"))),
+      Some(Ok(entry("a@1")))
+    );
+    assert_eq!(map.get_index_of("pkg-3"), Some(36));
+    assert_eq!(map.get("pkg-3"), Some(&Ok(entry("b@2"))));
+    assert_eq!(map.len(), 40);
+    assert!(!map.contains_key("pkg-40"));
+  }
+}
@@ -0,0 +1,1027 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use deno_semver::VersionReq;
+use serde_json::Map;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::text_edit::last_occurrences;
+use crate::text_edit::source_properties;
+use crate::text_edit::source_properties_in;
+use crate::text_edit::SourceProperty;
+use crate::PackageJson;
+
+/// Standard top level fields used by npm and other tools, in addition to
+/// the ones understood by this crate.
+const STANDARD_FIELDS: &[&str] = &[
+  "$schema",
+  "config",
+  "contributors",
+  "description",
+  "directories",
+  "files",
+  "funding",
+  "homepage",
+  "keywords",
+  "libc",
+  "license",
+  "maintainers",
+  "man",
+  "publishConfig",
+  "resolutions",
+  "sideEffects",
+  "typesVersions",
+];
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PackageJsonDiagnosticsOptions<'a> {
+  /// Reports top level fields that aren't standard package.json fields.
+  /// Fields starting with `_`, which npm adds when installing, are
+  /// always allowed.
+  pub deny_unknown_fields: bool,
+  /// Fields to allow when denying unknown fields (ex. `"prettier"` for
+  /// tool configuration).
+  pub allowed_fields: &'a [&'a str],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackageJsonDiagnosticSeverity {
+  Warning,
+  Error,
+}
+
+/// A problem found in a package.json.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageJsonDiagnostic {
+  /// JSON pointer (RFC 6901) to the offending value (ex. `/exports/.~1dir~1`).
+  pub pointer: String,
+  pub kind: PackageJsonDiagnosticKind,
+}
+
+impl PackageJsonDiagnostic {
+  pub fn severity(&self) -> PackageJsonDiagnosticSeverity {
+    self.kind.severity()
+  }
+
+  pub fn code(&self) -> &'static str {
+    self.kind.code()
+  }
+
+  pub fn message(&self) -> String {
+    self.kind.to_string()
+  }
+}
+
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum PackageJsonDiagnosticKind {
+  #[error("Folder mapping \"{key}\" ends in \"/\", which is no longer supported by Node. Use a subpath pattern like \"{key}*\" instead.")]
+  DeprecatedFolderMapping { key: String },
+  #[error("The \"default\" condition must come last. Conditions after it are never matched: {}", .unreachable.join(", "))]
+  DefaultConditionNotLast { unreachable: Vec<String> },
+  #[error("Missing required \"{field}\" field.")]
+  MissingField { field: &'static str },
+  #[error("Invalid package name \"{name}\": {reason}.")]
+  InvalidName { name: String, reason: &'static str },
+  #[error("Invalid version \"{version}\".")]
+  InvalidVersion { version: String },
+  #[error("Private packages can't be published.")]
+  PrivatePackage,
+  #[error("Target \"{target}\" must be a relative path within the package.")]
+  InvalidTarget { target: String },
+  #[error("Dependency \"{name}\" uses \"{specifier}\", which can't be resolved outside of this project.")]
+  LocalDependency { name: String, specifier: String },
+  #[error("Dependency \"{name}\" is \"{specifier}\" in \"{section}\", which conflicts with \"{winner_specifier}\" in \"{winner_section}\". npm uses the one in \"{winner_section}\".")]
+  ConflictingDependency {
+    name: String,
+    section: &'static str,
+    specifier: String,
+    winner_section: &'static str,
+    winner_specifier: String,
+  },
+  #[error("Dependency \"{name}\" has an empty version requirement, which matches any version. Use \"*\" instead.")]
+  EmptyVersionReq { name: String },
+  #[error("Dependency \"{name}\" has whitespace around \"{}\", which is ignored.", .specifier.trim())]
+  UntrimmedVersionReq { name: String, specifier: String },
+  #[error("Both \"typings\" (\"{typings}\") and \"types\" (\"{types}\") are set. TypeScript uses \"typings\".")]
+  ConflictingTypes { typings: String, types: String },
+  #[error("Pattern \"{key}\" has more than one \"*\", so it never matches.")]
+  MultipleWildcards { key: String },
+  #[error("Target \"{target}\" contains \"*\", but \"{key}\" isn't a pattern, so the \"*\" is used literally.")]
+  WildcardWithoutPattern { key: String, target: String },
+  #[error("Unknown field \"{field}\".{}", .suggestion.map(|s| format!(" Did you mean \"{s}\"?")).unwrap_or_default())]
+  UnknownField {
+    field: String,
+    /// A known field with a similar name.
+    suggestion: Option<&'static str>,
+  },
+  #[error(
+    "Script \"{script}\" runs \"{name}\", which isn't defined in \"scripts\"."
+  )]
+  UndefinedScript { script: String, name: String },
+  #[error("\"{field}\" is \"{target}\", which isn't reachable through \"exports\". Resolvers that support \"exports\" ignore \"{field}\".")]
+  UnreachableEntryPoint { field: &'static str, target: String },
+  #[error("\"exports\" can't mix keys starting with \".\" and keys that don't. It must either be an object of subpath keys or an object of condition keys, so it was ignored.")]
+  MixedExportsKeys,
+  #[error("Version {version} is a number, but it must be a string.")]
+  NumberVersion { version: String },
+  #[error(
+    "Key \"{key}\" is defined more than once. Only the last one is used."
+  )]
+  DuplicateKey { key: String },
+  #[error("{message}")]
+  SchemaViolation { message: String },
+  #[error("{message} (byte {offset}).")]
+  SyntaxError {
+    /// Byte offset in the source text.
+    offset: usize,
+    message: &'static str,
+  },
+}
+
+impl PackageJsonDiagnosticKind {
+  pub fn severity(&self) -> PackageJsonDiagnosticSeverity {
+    match self {
+      Self::DeprecatedFolderMapping { .. } => {
+        PackageJsonDiagnosticSeverity::Warning
+      }
+      Self::DefaultConditionNotLast { .. } => {
+        PackageJsonDiagnosticSeverity::Warning
+      }
+      Self::ConflictingDependency { .. } => {
+        PackageJsonDiagnosticSeverity::Warning
+      }
+      Self::EmptyVersionReq { .. } => PackageJsonDiagnosticSeverity::Warning,
+      Self::UntrimmedVersionReq { .. } => {
+        PackageJsonDiagnosticSeverity::Warning
+      }
+      Self::ConflictingTypes { .. } => PackageJsonDiagnosticSeverity::Warning,
+      Self::WildcardWithoutPattern { .. } => {
+        PackageJsonDiagnosticSeverity::Warning
+      }
+      Self::UndefinedScript { .. } => PackageJsonDiagnosticSeverity::Warning,
+      Self::UnreachableEntryPoint { .. } => {
+        PackageJsonDiagnosticSeverity::Warning
+      }
+      Self::NumberVersion { .. } => PackageJsonDiagnosticSeverity::Warning,
+      Self::DuplicateKey { .. } => PackageJsonDiagnosticSeverity::Warning,
+      Self::MissingField { .. }
+      | Self::InvalidName { .. }
+      | Self::InvalidVersion { .. }
+      | Self::PrivatePackage
+      | Self::InvalidTarget { .. }
+      | Self::LocalDependency { .. }
+      | Self::MultipleWildcards { .. }
+      | Self::UnknownField { .. }
+      | Self::MixedExportsKeys
+      | Self::SchemaViolation { .. }
+      | Self::SyntaxError { .. } => PackageJsonDiagnosticSeverity::Error,
+    }
+  }
+
+  pub fn code(&self) -> &'static str {
+    match self {
+      Self::DeprecatedFolderMapping { .. } => "deprecated-folder-mapping",
+      Self::DefaultConditionNotLast { .. } => "default-condition-not-last",
+      Self::MissingField { .. } => "missing-field",
+      Self::InvalidName { .. } => "invalid-name",
+      Self::InvalidVersion { .. } => "invalid-version",
+      Self::PrivatePackage => "private-package",
+      Self::InvalidTarget { .. } => "invalid-target",
+      Self::LocalDependency { .. } => "local-dependency",
+      Self::ConflictingDependency { .. } => "conflicting-dependency",
+      Self::EmptyVersionReq { .. } => "empty-version-requirement",
+      Self::UntrimmedVersionReq { .. } => "untrimmed-version-requirement",
+      Self::ConflictingTypes { .. } => "conflicting-types",
+      Self::MultipleWildcards { .. } => "multiple-wildcards",
+      Self::WildcardWithoutPattern { .. } => "wildcard-without-pattern",
+      Self::UnknownField { .. } => "unknown-field",
+      Self::UndefinedScript { .. } => "undefined-script",
+      Self::UnreachableEntryPoint { .. } => "unreachable-entry-point",
+      Self::MixedExportsKeys => "mixed-exports-keys",
+      Self::NumberVersion { .. } => "number-version",
+      Self::DuplicateKey { .. } => "duplicate-key",
+      Self::SchemaViolation { .. } => "schema-violation",
+      Self::SyntaxError { .. } => "syntax-error",
+    }
+  }
+}
+
+impl PackageJson {
+  /// Lints the package.json for common authoring mistakes.
+  pub fn diagnostics(&self) -> Vec<PackageJsonDiagnostic> {
+    self.diagnostics_with_options(Default::default())
+  }
+
+  pub fn diagnostics_with_options(
+    &self,
+    options: PackageJsonDiagnosticsOptions,
+  ) -> Vec<PackageJsonDiagnostic> {
+    let mut diagnostics = Vec::new();
+    if let Some(diagnostic) = self.mixed_exports_keys_diagnostic() {
+      diagnostics.push(diagnostic);
+    }
+    if options.deny_unknown_fields {
+      lint_unknown_fields(self, options.allowed_fields, &mut diagnostics);
+    }
+    for (field, map) in [
+      ("exports", self.exports.as_deref()),
+      ("imports", self.imports.as_deref()),
+    ] {
+      if let Some(map) = map {
+        lint_folder_mappings(field, map, &mut diagnostics);
+        lint_wildcards(field, map, &mut diagnostics);
+        for (key, target) in map {
+          lint_default_condition_last(
+            &mut vec![field.to_string(), key.clone()],
+            target,
+            &mut diagnostics,
+          );
+        }
+      }
+    }
+    lint_conflicting_dependencies(self, &mut diagnostics);
+    lint_empty_version_reqs(self, &mut diagnostics);
+    lint_untrimmed_version_reqs(self, &mut diagnostics);
+    lint_undefined_scripts(self, &mut diagnostics);
+    lint_unreachable_entry_points(self, &mut diagnostics);
+    if let (Some(typings), Some(types)) = (&self.types, &self.shadowed_types) {
+      diagnostics.push(PackageJsonDiagnostic {
+        pointer: "/types".to_string(),
+        kind: PackageJsonDiagnosticKind::ConflictingTypes {
+          typings: typings.clone(),
+          types: types.clone(),
+        },
+      });
+    }
+    diagnostics
+  }
+
+  /// Lints the package.json source text for mistakes that are lost once
+  /// it's parsed, such as duplicate keys or a number "version".
+  pub fn source_diagnostics(
+    source: &str,
+  ) -> Result<Vec<PackageJsonDiagnostic>, serde_json::Error> {
+    let mut diagnostics = Vec::new();
+    let properties = source_properties(source)?;
+    let maybe_version = properties.iter().rev().find(|p| p.key == "version");
+    if let Some(property) = maybe_version {
+      let text = &source[property.value_range.clone()];
+      if let Ok(Value::Number(_)) = serde_json::from_str(text) {
+        diagnostics.push(PackageJsonDiagnostic {
+          pointer: "/version".to_string(),
+          kind: PackageJsonDiagnosticKind::NumberVersion {
+            version: text.to_string(),
+          },
+        });
+      }
+    }
+    lint_duplicate_keys(source, properties, &mut Vec::new(), &mut diagnostics)?;
+    Ok(diagnostics)
+  }
+
+  /// Gets the diagnostic for "exports" that were dropped while loading
+  /// because they mixed subpath and condition keys.
+  pub(crate) fn mixed_exports_keys_diagnostic(
+    &self,
+  ) -> Option<PackageJsonDiagnostic> {
+    self.has_mixed_exports_keys.then(|| PackageJsonDiagnostic {
+      pointer: "/exports".to_string(),
+      kind: PackageJsonDiagnosticKind::MixedExportsKeys,
+    })
+  }
+}
+
+fn lint_duplicate_keys(
+  source: &str,
+  properties: Vec<SourceProperty>,
+  path: &mut Vec<String>,
+  diagnostics: &mut Vec<PackageJsonDiagnostic>,
+) -> Result<(), serde_json::Error> {
+  for (i, property) in properties.iter().enumerate() {
+    let is_first = !properties[..i].iter().any(|p| p.key == property.key);
+    let is_duplicate =
+      properties[i + 1..].iter().any(|p| p.key == property.key);
+    if is_first && is_duplicate {
+      path.push(property.key.clone());
+      diagnostics.push(PackageJsonDiagnostic {
+        pointer: json_pointer(path),
+        kind: PackageJsonDiagnosticKind::DuplicateKey {
+          key: property.key.clone(),
+        },
+      });
+      path.pop();
+    }
+  }
+  for property in last_occurrences(properties) {
+    let children = source_properties_in(source, property.value_range)?;
+    path.push(property.key);
+    lint_duplicate_keys(source, children, path, diagnostics)?;
+    path.pop();
+  }
+  Ok(())
+}
+
+fn lint_unknown_fields(
+  package_json: &PackageJson,
+  allowed_fields: &[&str],
+  diagnostics: &mut Vec<PackageJsonDiagnostic>,
+) {
+  for field in package_json.unknown_fields() {
+    if field.starts_with('_')
+      || package_json.extensions().contains(field)
+      || STANDARD_FIELDS.contains(&field.as_str())
+      || allowed_fields.contains(&field.as_str())
+    {
+      continue;
+    }
+    diagnostics.push(PackageJsonDiagnostic {
+      pointer: json_pointer(&[field]),
+      kind: PackageJsonDiagnosticKind::UnknownField {
+        field: field.clone(),
+        suggestion: suggest_field(field),
+      },
+    });
+  }
+}
+
+/// Gets the known field closest to a misspelled one, if any is close
+/// enough to be a likely typo (ex. "dependancies").
+fn suggest_field(field: &str) -> Option<&'static str> {
+  let lowercase_field = field.to_ascii_lowercase();
+  let max_distance = (field.chars().count() / 4).clamp(1, 3);
+  crate::field_set::KNOWN_FIELDS
+    .iter()
+    .chain(STANDARD_FIELDS)
+    .map(|known| {
+      let distance =
+        edit_distance(&lowercase_field, &known.to_ascii_lowercase());
+      (distance, *known)
+    })
+    .filter(|(distance, _)| *distance <= max_distance)
+    .min_by_key(|(distance, _)| *distance)
+    .map(|(_, known)| known)
+}
+
+/// Optimal string alignment distance, which counts swapping two adjacent
+/// characters as a single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+  let a = a.chars().collect::<Vec<_>>();
+  let b = b.chars().collect::<Vec<_>>();
+  let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+  for (i, row) in rows.iter_mut().enumerate() {
+    row[0] = i;
+  }
+  for (j, cell) in rows[0].iter_mut().enumerate() {
+    *cell = j;
+  }
+  for i in 1..=a.len() {
+    for j in 1..=b.len() {
+      let cost = usize::from(a[i - 1] != b[j - 1]);
+      let mut distance = (rows[i - 1][j] + 1)
+        .min(rows[i][j - 1] + 1)
+        .min(rows[i - 1][j - 1] + cost);
+      if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+        distance = distance.min(rows[i - 2][j - 2] + 1);
+      }
+      rows[i][j] = distance;
+    }
+  }
+  rows[a.len()][b.len()]
+}
+
+fn lint_undefined_scripts(
+  package_json: &PackageJson,
+  diagnostics: &mut Vec<PackageJsonDiagnostic>,
+) {
+  let Some(scripts) = &package_json.scripts else {
+    return;
+  };
+  for (script, command) in scripts.iter() {
+    for name in crate::scripts::referenced_scripts(command) {
+      if !scripts.contains_key(name) {
+        diagnostics.push(PackageJsonDiagnostic {
+          pointer: json_pointer(&["scripts", script]),
+          kind: PackageJsonDiagnosticKind::UndefinedScript {
+            script: script.clone(),
+            name: name.to_string(),
+          },
+        });
+      }
+    }
+  }
+}
+
+/// Lints "main" and "module" when they point to a file that none of the
+/// "exports" targets match, since they're only used by resolvers without
+/// support for "exports".
+fn lint_unreachable_entry_points(
+  package_json: &PackageJson,
+  diagnostics: &mut Vec<PackageJsonDiagnostic>,
+) {
+  fn is_reachable(target: &Value, candidates: &[String]) -> bool {
+    match target {
+      Value::String(target) => candidates.iter().any(|candidate| {
+        // deprecated folder mappings (ex. "./dist/")
+        if target.ends_with('/') {
+          candidate.starts_with(target.as_str())
+        } else {
+          crate::module_kind::target_matches(target, candidate)
+        }
+      }),
+      Value::Array(targets) => targets
+        .iter()
+        .any(|target| is_reachable(target, candidates)),
+      Value::Object(conditions) => conditions
+        .values()
+        .any(|target| is_reachable(target, candidates)),
+      _ => false,
+    }
+  }
+
+  let Some(exports) = package_json.exports.as_deref() else {
+    return;
+  };
+  for (field, target) in [
+    ("main", package_json.raw_main()),
+    ("module", package_json.raw_module()),
+  ] {
+    let Some(target) = target else {
+      continue;
+    };
+    let path = target.trim_start_matches("./").trim_end_matches('/');
+    if path.is_empty() || path == "." {
+      continue;
+    }
+    let mut candidates = vec![format!("./{}", path)];
+    // extensionless entry points are resolved like CommonJS
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    if !file_name.contains('.') {
+      for suffix in [".js", ".json", ".node", "/index.js"] {
+        candidates.push(format!("./{}{}", path, suffix));
+      }
+    }
+    if !exports
+      .values()
+      .any(|target| is_reachable(target, &candidates))
+    {
+      diagnostics.push(PackageJsonDiagnostic {
+        pointer: json_pointer(&[field]),
+        kind: PackageJsonDiagnosticKind::UnreachableEntryPoint {
+          field,
+          target: target.to_string(),
+        },
+      });
+    }
+  }
+}
+
+fn lint_folder_mappings(
+  field: &str,
+  map: &Map<String, Value>,
+  diagnostics: &mut Vec<PackageJsonDiagnostic>,
+) {
+  for key in map.keys() {
+    if key.ends_with('/') && !key.contains('*') {
+      diagnostics.push(PackageJsonDiagnostic {
+        pointer: json_pointer(&[field, key]),
+        kind: PackageJsonDiagnosticKind::DeprecatedFolderMapping {
+          key: key.clone(),
+        },
+      });
+    }
+  }
+}
+
+/// Lints patterns with more than one "*", which Node never matches, and
+/// "*" in the targets of keys that aren't patterns.
+fn lint_wildcards(
+  field: &str,
+  map: &Map<String, Value>,
+  diagnostics: &mut Vec<PackageJsonDiagnostic>,
+) {
+  fn lint_targets(
+    key: &str,
+    path: &mut Vec<String>,
+    target: &Value,
+    diagnostics: &mut Vec<PackageJsonDiagnostic>,
+  ) {
+    match target {
+      Value::String(target) if target.contains('*') => {
+        diagnostics.push(PackageJsonDiagnostic {
+          pointer: json_pointer(path),
+          kind: PackageJsonDiagnosticKind::WildcardWithoutPattern {
+            key: key.to_string(),
+            target: target.clone(),
+          },
+        });
+      }
+      Value::Object(conditions) => {
+        for (condition, target) in conditions {
+          path.push(condition.clone());
+          lint_targets(key, path, target, diagnostics);
+          path.pop();
+        }
+      }
+      Value::Array(targets) => {
+        for (index, target) in targets.iter().enumerate() {
+          path.push(index.to_string());
+          lint_targets(key, path, target, diagnostics);
+          path.pop();
+        }
+      }
+      _ => {}
+    }
+  }
+
+  for (key, target) in map {
+    match key.matches('*').count() {
+      0 => lint_targets(
+        key,
+        &mut vec![field.to_string(), key.clone()],
+        target,
+        diagnostics,
+      ),
+      1 => {}
+      _ => diagnostics.push(PackageJsonDiagnostic {
+        pointer: json_pointer(&[field, key]),
+        kind: PackageJsonDiagnosticKind::MultipleWildcards { key: key.clone() },
+      }),
+    }
+  }
+}
+
+fn lint_default_condition_last(
+  path: &mut Vec<String>,
+  target: &Value,
+  diagnostics: &mut Vec<PackageJsonDiagnostic>,
+) {
+  match target {
+    Value::Object(conditions) => {
+      if let Some(index) = conditions.keys().position(|k| k == "default") {
+        if index + 1 < conditions.len() {
+          path.push("default".to_string());
+          diagnostics.push(PackageJsonDiagnostic {
+            pointer: json_pointer(path),
+            kind: PackageJsonDiagnosticKind::DefaultConditionNotLast {
+              unreachable: conditions.keys().skip(index + 1).cloned().collect(),
+            },
+          });
+          path.pop();
+        }
+      }
+      for (condition, target) in conditions {
+        path.push(condition.clone());
+        lint_default_condition_last(path, target, diagnostics);
+        path.pop();
+      }
+    }
+    Value::Array(targets) => {
+      for (index, target) in targets.iter().enumerate() {
+        path.push(index.to_string());
+        lint_default_condition_last(path, target, diagnostics);
+        path.pop();
+      }
+    }
+    _ => {}
+  }
+}
+
+/// Lints dependencies found in multiple sections with incompatible
+/// requirements.
+///
+/// For the root package, npm adds the dependencies, then the optional
+/// dependencies, then the dev dependencies, with the later ones replacing
+/// the earlier ones.
+fn lint_conflicting_dependencies(
+  package_json: &PackageJson,
+  diagnostics: &mut Vec<PackageJsonDiagnostic>,
+) {
+  // in the order npm loads them, where later sections win
+  let sections = [
+    (
+      "peerDependencies",
+      package_json.peer_dependencies.as_deref(),
+    ),
+    ("dependencies", package_json.dependencies.as_deref()),
+    (
+      "optionalDependencies",
+      package_json.optional_dependencies.as_deref(),
+    ),
+    ("devDependencies", package_json.dev_dependencies.as_deref()),
+  ];
+  for (index, (section, deps)) in sections.iter().enumerate() {
+    let Some(deps) = deps else {
+      continue;
+    };
+    for (name, specifier) in *deps {
+      let winner = sections[index + 1..]
+        .iter()
+        .rev()
+        .find_map(|(section, deps)| Some((*section, (*deps)?.get(name)?)));
+      let Some((winner_section, winner_specifier)) = winner else {
+        continue;
+      };
+      if !is_compatible_specifier(name, specifier, winner_specifier) {
+        diagnostics.push(PackageJsonDiagnostic {
+          pointer: json_pointer(&[*section, name]),
+          kind: PackageJsonDiagnosticKind::ConflictingDependency {
+            name: name.clone(),
+            section,
+            specifier: specifier.clone(),
+            winner_section,
+            winner_specifier: winner_specifier.clone(),
+          },
+        });
+      }
+    }
+  }
+}
+
+fn lint_empty_version_reqs(
+  package_json: &PackageJson,
+  diagnostics: &mut Vec<PackageJsonDiagnostic>,
+) {
+  for (section, deps) in [
+    ("dependencies", package_json.dependencies.as_deref()),
+    ("devDependencies", package_json.dev_dependencies.as_deref()),
+    (
+      "optionalDependencies",
+      package_json.optional_dependencies.as_deref(),
+    ),
+  ] {
+    for (name, specifier) in deps.into_iter().flatten() {
+      if specifier.trim().is_empty() {
+        diagnostics.push(PackageJsonDiagnostic {
+          pointer: json_pointer(&[section, name]),
+          kind: PackageJsonDiagnosticKind::EmptyVersionReq {
+            name: name.clone(),
+          },
+        });
+      }
+    }
+  }
+}
+
+fn lint_untrimmed_version_reqs(
+  package_json: &PackageJson,
+  diagnostics: &mut Vec<PackageJsonDiagnostic>,
+) {
+  for (section, deps) in [
+    ("dependencies", package_json.dependencies.as_deref()),
+    ("devDependencies", package_json.dev_dependencies.as_deref()),
+    (
+      "optionalDependencies",
+      package_json.optional_dependencies.as_deref(),
+    ),
+    (
+      "peerDependencies",
+      package_json.peer_dependencies.as_deref(),
+    ),
+  ] {
+    for (name, specifier) in deps.into_iter().flatten() {
+      let trimmed = specifier.trim();
+      // empty requirements are reported separately
+      if !trimmed.is_empty() && trimmed.len() != specifier.len() {
+        diagnostics.push(PackageJsonDiagnostic {
+          pointer: json_pointer(&[section, name]),
+          kind: PackageJsonDiagnosticKind::UntrimmedVersionReq {
+            name: name.clone(),
+            specifier: specifier.clone(),
+          },
+        });
+      }
+    }
+  }
+}
+
+fn is_compatible_specifier(alias: &str, a: &str, b: &str) -> bool {
+  let (a, b) = (a.trim(), b.trim());
+  if a == b {
+    return true;
+  }
+  let (a_name, a_req) = crate::parse_dep_entry_name_and_raw_version(alias, a);
+  let (b_name, b_req) = crate::parse_dep_entry_name_and_raw_version(alias, b);
+  if a_name != b_name {
+    return false;
+  }
+  match (
+    VersionReq::parse_from_npm(a_req),
+    VersionReq::parse_from_npm(b_req),
+  ) {
+    (Ok(a), Ok(b)) => a.intersects(&b),
+    _ => false,
+  }
+}
+
+pub(crate) fn json_pointer(segments: &[impl AsRef<str>]) -> String {
+  let mut pointer = String::new();
+  for segment in segments {
+    pointer.push('/');
+    pointer.push_str(&segment.as_ref().replace('~', "~0").replace('/', "~1"));
+  }
+  pointer
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn diagnostics(value: Value) -> Vec<(&'static str, String)> {
+    PackageJson::load_from_value(PathBuf::from("/package.json"), value)
+      .diagnostics()
+      .into_iter()
+      .map(|d| (d.code(), d.pointer))
+      .collect()
+  }
+
+  #[test]
+  fn deprecated_folder_mappings() {
+    assert_eq!(
+      diagnostics(serde_json::json!({
+        "exports": {
+          ".": "./index.js",
+          "./dir/": "./dir/",
+          "./pattern/*": "./pattern/*.js",
+        },
+        "imports": {
+          "#internal/": "./internal/",
+        },
+      })),
+      vec![
+        ("deprecated-folder-mapping", "/exports/.~1dir~1".to_string()),
+        (
+          "deprecated-folder-mapping",
+          "/imports/#internal~1".to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn wildcards() {
+    assert_eq!(
+      diagnostics(serde_json::json!({
+        "exports": {
+          "./a/*": "./a/*.js",
+          "./b/*/*": "./b/*/*.js",
+          "./c": { "import": "./c/*.mjs", "default": "./c.js" },
+        },
+        "imports": {
+          "#d/*": ["./d/*.js", "./d/*/index.js"],
+        },
+      })),
+      vec![
+        ("multiple-wildcards", "/exports/.~1b~1*~1*".to_string()),
+        (
+          "wildcard-without-pattern",
+          "/exports/.~1c/import".to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn default_condition_not_last() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "exports": {
+          ".": {
+            "default": "./index.js",
+            "import": "./index.mjs",
+            "require": "./index.cjs",
+          },
+          "./a": [{ "node": { "default": "./a.js", "types": "./a.d.ts" } }],
+          "./b": { "import": "./b.mjs", "default": "./b.js" },
+        },
+      }),
+    );
+    let diagnostics = package_json.diagnostics();
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|d| d.pointer.as_str())
+        .collect::<Vec<_>>(),
+      vec!["/exports/./default", "/exports/.~1a/0/node/default"]
+    );
+    assert_eq!(
+      diagnostics[0].message(),
+      "The \"default\" condition must come last. Conditions after it are never matched: import, require"
+    );
+  }
+
+  #[test]
+  fn conflicting_dependencies() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "dependencies": {
+          "a": "^1.0.0",
+          "b": "^1.0.0",
+          "c": "^1.0.0",
+          "d": "npm:other@^1",
+        },
+        "optionalDependencies": {
+          "b": "^2.0.0",
+        },
+        "devDependencies": {
+          "a": "^1.2.0",
+          "c": "^2.0.0",
+          "d": "npm:d@^1",
+          "e": "",
+        },
+      }),
+    );
+    let diagnostics = package_json.diagnostics();
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|d| d.pointer.as_str())
+        .collect::<Vec<_>>(),
+      vec![
+        "/dependencies/b",
+        "/dependencies/c",
+        "/dependencies/d",
+        "/devDependencies/e"
+      ]
+    );
+    assert_eq!(
+      diagnostics[0].message(),
+      "Dependency \"b\" is \"^1.0.0\" in \"dependencies\", which conflicts with \"^2.0.0\" in \"optionalDependencies\". npm uses the one in \"optionalDependencies\"."
+    );
+  }
+
+  #[test]
+  fn conflicting_peer_dependencies() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "peerDependencies": {
+          "a": "^1.0.0",
+          "b": "^2.0.0",
+        },
+        "dependencies": {
+          "a": "^1.2.0",
+          "b": "^3.0.0",
+        },
+      }),
+    );
+    let diagnostics = package_json.diagnostics();
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|d| d.pointer.as_str())
+        .collect::<Vec<_>>(),
+      vec!["/peerDependencies/b"]
+    );
+    assert_eq!(
+      diagnostics[0].message(),
+      "Dependency \"b\" is \"^2.0.0\" in \"peerDependencies\", which conflicts with \"^3.0.0\" in \"dependencies\". npm uses the one in \"dependencies\"."
+    );
+  }
+
+  #[test]
+  fn unknown_fields() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "name": "pkg",
+        "description": "A package",
+        "dependancies": {},
+        "devDepedencies": {},
+        "_resolved": "https://registry.npmjs.org/pkg/-/pkg-1.0.0.tgz",
+        "prettier": {},
+        "eslintConfig": {},
+      }),
+    );
+    assert_eq!(package_json.diagnostics(), vec![]);
+    let diagnostics =
+      package_json.diagnostics_with_options(PackageJsonDiagnosticsOptions {
+        deny_unknown_fields: true,
+        allowed_fields: &["prettier"],
+      });
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|d| (d.pointer.as_str(), d.message()))
+        .collect::<Vec<_>>(),
+      vec![
+        (
+          "/dependancies",
+          "Unknown field \"dependancies\". Did you mean \"dependencies\"?"
+            .to_string()
+        ),
+        (
+          "/devDepedencies",
+          "Unknown field \"devDepedencies\". Did you mean \"devDependencies\"?"
+            .to_string()
+        ),
+        (
+          "/eslintConfig",
+          "Unknown field \"eslintConfig\".".to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn undefined_scripts() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "scripts": {
+          "build": "tsc",
+          "ci": "npm run build && npm run lnt && npm test",
+        }
+      }),
+    );
+    let diagnostics = package_json.diagnostics();
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|d| (d.pointer.as_str(), d.message()))
+        .collect::<Vec<_>>(),
+      vec![
+        (
+          "/scripts/ci",
+          "Script \"ci\" runs \"lnt\", which isn't defined in \"scripts\"."
+            .to_string()
+        ),
+        (
+          "/scripts/ci",
+          "Script \"ci\" runs \"test\", which isn't defined in \"scripts\"."
+            .to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn conflicting_types() {
+    assert_eq!(
+      diagnostics(serde_json::json!({
+        "typings": "./a.d.ts",
+        "types": "./a.d.ts",
+      })),
+      vec![]
+    );
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "typings": "./a.d.ts",
+        "types": "./b.d.ts",
+      }),
+    );
+    let diagnostics = package_json.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].pointer, "/types");
+    assert_eq!(
+      diagnostics[0].message(),
+      "Both \"typings\" (\"./a.d.ts\") and \"types\" (\"./b.d.ts\") are set. TypeScript uses \"typings\"."
+    );
+  }
+
+  #[test]
+  fn unreachable_entry_points() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "main": "lib/index",
+        "module": "./esm/index.mjs",
+        "exports": {
+          ".": {
+            "import": "./dist/index.mjs",
+            "require": ["./lib/index.js"],
+          },
+          "./package.json": "./package.json",
+        },
+      }),
+    );
+    let diagnostics = package_json.diagnostics();
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|d| (d.pointer.as_str(), d.code()))
+        .collect::<Vec<_>>(),
+      vec![("/module", "unreachable-entry-point")]
+    );
+    assert_eq!(
+      diagnostics[0].message(),
+      "\"module\" is \"./esm/index.mjs\", which isn't reachable through \"exports\". Resolvers that support \"exports\" ignore \"module\"."
+    );
+
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "main": "./dist/cjs/main.js",
+        "module": "dist/esm/main.js",
+        "exports": { "./*": "./dist/*" },
+      }),
+    );
+    assert_eq!(package_json.diagnostics(), Vec::new());
+  }
+}
@@ -0,0 +1,165 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use deno_semver::Version;
+use deno_semver::VersionReq;
+
+use crate::PackageJson;
+
+/// Versions of the runtimes and tools to check the "engines" field
+/// against. Engines without a version aren't checked.
+#[derive(Debug, Clone, Default)]
+pub struct PackageJsonEnginesEnv {
+  pub node: Option<Version>,
+  pub deno: Option<Version>,
+  pub npm: Option<Version>,
+}
+
+impl PackageJsonEnginesEnv {
+  fn version(&self, engine: &str) -> Option<&Version> {
+    match engine {
+      "node" => self.node.as_ref(),
+      "deno" => self.deno.as_ref(),
+      "npm" => self.npm.as_ref(),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageJsonEngineStatus {
+  Satisfied,
+  Unsatisfied {
+    version: Version,
+  },
+  /// The range couldn't be parsed, which npm treats as unsatisfied.
+  InvalidRange,
+  /// No version was supplied for the engine.
+  Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageJsonEngineCheck {
+  /// Name of the engine (ex. "node").
+  pub name: String,
+  /// The declared version range (ex. ">=18").
+  pub range: String,
+  pub status: PackageJsonEngineStatus,
+}
+
+/// The result of checking each declared engine.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageJsonEnginesReport {
+  pub checks: Vec<PackageJsonEngineCheck>,
+}
+
+impl PackageJsonEnginesReport {
+  /// Gets if no engine with a known version failed, which is when
+  /// `npm install --engine-strict` would succeed.
+  pub fn passed(&self) -> bool {
+    self.failures().next().is_none()
+  }
+
+  pub fn failures(&self) -> impl Iterator<Item = &PackageJsonEngineCheck> {
+    self.checks.iter().filter(|check| {
+      matches!(
+        check.status,
+        PackageJsonEngineStatus::Unsatisfied { .. }
+          | PackageJsonEngineStatus::InvalidRange
+      )
+    })
+  }
+}
+
+impl PackageJson {
+  /// Compares the declared "engines" against the supplied versions.
+  pub fn check_engines(
+    &self,
+    env: &PackageJsonEnginesEnv,
+  ) -> PackageJsonEnginesReport {
+    let checks = self
+      .engines
+      .iter()
+      .flatten()
+      .map(|(name, range)| {
+        let status = match env.version(name) {
+          None => PackageJsonEngineStatus::Unknown,
+          Some(version) => match VersionReq::parse_from_npm(range) {
+            Ok(req) if req.matches(version) => {
+              PackageJsonEngineStatus::Satisfied
+            }
+            Ok(_) => PackageJsonEngineStatus::Unsatisfied {
+              version: version.clone(),
+            },
+            Err(_) => PackageJsonEngineStatus::InvalidRange,
+          },
+        };
+        PackageJsonEngineCheck {
+          name: name.clone(),
+          range: range.clone(),
+          status,
+        }
+      })
+      .collect();
+    PackageJsonEnginesReport { checks }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn check_engines() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "engines": {
+          "node": ">=18",
+          "deno": "^2.1",
+          "npm": "not a range",
+          "yarn": "^4",
+        }
+      }),
+    );
+    let env = PackageJsonEnginesEnv {
+      node: Some(Version::parse_standard("20.11.0").unwrap()),
+      deno: Some(Version::parse_standard("2.0.0").unwrap()),
+      npm: None,
+    };
+    let report = package_json.check_engines(&env);
+    assert_eq!(
+      report
+        .checks
+        .iter()
+        .map(|check| (check.name.as_str(), &check.status))
+        .collect::<Vec<_>>(),
+      vec![
+        ("node", &PackageJsonEngineStatus::Satisfied),
+        (
+          "deno",
+          &PackageJsonEngineStatus::Unsatisfied {
+            version: Version::parse_standard("2.0.0").unwrap(),
+          }
+        ),
+        ("npm", &PackageJsonEngineStatus::Unknown),
+        ("yarn", &PackageJsonEngineStatus::Unknown),
+      ]
+    );
+    assert!(!report.passed());
+    assert_eq!(report.failures().count(), 1);
+
+    let env = PackageJsonEnginesEnv {
+      npm: Some(Version::parse_standard("10.0.0").unwrap()),
+      ..Default::default()
+    };
+    let report = package_json.check_engines(&env);
+    assert_eq!(
+      report.checks[2].status,
+      PackageJsonEngineStatus::InvalidRange
+    );
+  }
+}
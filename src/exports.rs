@@ -0,0 +1,396 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use deno_semver::Version;
+use deno_semver::VersionReq;
+use serde_json::Map;
+use serde_json::Value;
+
+/// A key of an "exports" or "imports" map that matched a subpath.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SubpathMatch<'a, 'b> {
+  pub key: &'a str,
+  pub target: &'a Value,
+  /// The text matched by the `*` when the key was a pattern.
+  pub pattern_match: Option<&'b str>,
+}
+
+/// Finds the entry for a subpath (ex. `./foo` or `#foo`) in an "exports"
+/// or "imports" map following Node's PACKAGE_IMPORTS_EXPORTS_RESOLVE.
+pub(crate) fn match_subpath<'a, 'b>(
+  map: &'a Map<String, Value>,
+  subpath: &'b str,
+) -> Option<SubpathMatch<'a, 'b>> {
+  if !subpath.contains('*') {
+    if let Some((key, target)) = map.get_key_value(subpath) {
+      return Some(SubpathMatch {
+        key,
+        target,
+        pattern_match: None,
+      });
+    }
+  }
+
+  let mut best_match: Option<SubpathMatch<'a, 'b>> = None;
+  for (key, target) in map {
+    let Some((base, trailer)) = key.split_once('*') else {
+      continue;
+    };
+    // Node ignores patterns with more than one "*"
+    if trailer.contains('*') {
+      continue;
+    }
+    if subpath.len() > base.len()
+      && subpath.starts_with(base)
+      && (trailer.is_empty()
+        || (subpath.len() >= key.len() && subpath.ends_with(trailer)))
+      && best_match.as_ref().map_or(true, |best| {
+        pattern_key_compare(best.key, key) == Ordering::Greater
+      })
+    {
+      best_match = Some(SubpathMatch {
+        key,
+        target,
+        pattern_match: Some(
+          &subpath[base.len()..subpath.len() - trailer.len()],
+        ),
+      });
+    }
+  }
+  best_match
+}
+
+/// Rewrites an "exports" map into a canonical form so that the export
+/// surfaces of two packages can be compared structurally.
+pub(crate) fn normalize_exports(
+  exports: &Map<String, Value>,
+) -> Map<String, Value> {
+  let mut entries = exports
+    .iter()
+    .map(|(key, target)| (key.clone(), normalize_target(target)))
+    .collect::<Vec<_>>();
+  entries.sort_by(|(a, _), (b, _)| canonical_key_compare(a, b));
+  entries.into_iter().collect()
+}
+
+/// Orders the pattern keys from most to least specific followed by the
+/// other keys, breaking ties by the text so the order is total.
+fn canonical_key_compare(a: &str, b: &str) -> Ordering {
+  match (a.contains('*'), b.contains('*')) {
+    (true, true) => pattern_key_compare(a, b),
+    (true, false) => Ordering::Less,
+    (false, true) => Ordering::Greater,
+    (false, false) => Ordering::Equal,
+  }
+  .then_with(|| a.cmp(b))
+}
+
+fn normalize_target(target: &Value) -> Value {
+  match target {
+    Value::Array(targets) if targets.len() == 1 => {
+      normalize_target(&targets[0])
+    }
+    Value::Array(targets) => {
+      Value::Array(targets.iter().map(normalize_target).collect())
+    }
+    Value::Object(conditions) => {
+      let mut normalized = Map::with_capacity(conditions.len());
+      for (condition, target) in conditions {
+        normalized.insert(condition.clone(), normalize_target(target));
+        // conditions after "default" are unreachable
+        if condition == "default" {
+          break;
+        }
+      }
+      if normalized.len() == 1 && normalized.contains_key("default") {
+        normalized.remove("default").unwrap()
+      } else {
+        Value::Object(normalized)
+      }
+    }
+    _ => target.clone(),
+  }
+}
+
+/// The outcome of resolving a target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ResolvedTarget<'a> {
+  Path(Cow<'a, str>),
+  /// The target was `null`, which blocks the subpath.
+  Null,
+  /// None of the conditions matched.
+  NoMatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TargetError {
+  /// The target isn't valid (ex. it doesn't start with `./`). This is the
+  /// only error that falls back to the next target of an array.
+  InvalidTarget { target: String },
+  /// The text matched by the pattern contains invalid path segments.
+  InvalidSubpath,
+}
+
+/// Resolves an "exports" or "imports" target for the provided conditions
+/// following Node's PACKAGE_TARGET_RESOLVE.
+///
+/// Targets in the "imports" may also be bare specifiers of dependencies
+/// (ex. `dep/feature`), which are returned as is.
+pub(crate) fn resolve_target<'a>(
+  target: &'a Value,
+  pattern_match: Option<&str>,
+  conditions: &[&str],
+  types_version: Option<&Version>,
+  is_imports: bool,
+) -> Result<ResolvedTarget<'a>, TargetError> {
+  match target {
+    Value::String(target) => {
+      if is_imports && is_bare_specifier(target) {
+        return Ok(ResolvedTarget::Path(match pattern_match {
+          Some(pattern_match) => Cow::Owned(target.replace('*', pattern_match)),
+          None => Cow::Borrowed(target),
+        }));
+      }
+      let is_valid = target
+        .strip_prefix("./")
+        .is_some_and(|path| !has_invalid_segment(path));
+      if !is_valid {
+        return Err(TargetError::InvalidTarget {
+          target: Value::String(target.clone()).to_string(),
+        });
+      }
+      Ok(ResolvedTarget::Path(match pattern_match {
+        Some(pattern_match) => {
+          if has_invalid_segment(pattern_match) {
+            return Err(TargetError::InvalidSubpath);
+          }
+          Cow::Owned(target.replace('*', pattern_match))
+        }
+        None => Cow::Borrowed(target),
+      }))
+    }
+    Value::Object(map) => {
+      for (key, target) in map {
+        if condition_matches(key, conditions, types_version) {
+          match resolve_target(
+            target,
+            pattern_match,
+            conditions,
+            types_version,
+            is_imports,
+          )? {
+            ResolvedTarget::NoMatch => continue,
+            resolved => return Ok(resolved),
+          }
+        }
+      }
+      Ok(ResolvedTarget::NoMatch)
+    }
+    Value::Array(targets) => {
+      if targets.is_empty() {
+        return Ok(ResolvedTarget::Null);
+      }
+      let mut last = Ok(ResolvedTarget::NoMatch);
+      for target in targets {
+        match resolve_target(
+          target,
+          pattern_match,
+          conditions,
+          types_version,
+          is_imports,
+        ) {
+          Ok(ResolvedTarget::Path(path)) => {
+            return Ok(ResolvedTarget::Path(path))
+          }
+          Ok(ResolvedTarget::NoMatch) => {}
+          Ok(ResolvedTarget::Null) => last = Ok(ResolvedTarget::Null),
+          Err(err @ TargetError::InvalidTarget { .. }) => last = Err(err),
+          Err(err) => return Err(err),
+        }
+      }
+      last
+    }
+    Value::Null => Ok(ResolvedTarget::Null),
+    Value::Bool(_) | Value::Number(_) => Err(TargetError::InvalidTarget {
+      target: target.to_string(),
+    }),
+  }
+}
+
+/// Gets if a condition key matches. Like TypeScript, versioned types
+/// conditions (ex. `types@>=5.2`) match along with the "types" condition
+/// when the TypeScript version satisfies the range.
+fn condition_matches(
+  key: &str,
+  conditions: &[&str],
+  types_version: Option<&Version>,
+) -> bool {
+  if key == "default" || conditions.contains(&key) {
+    return true;
+  }
+  match (key.strip_prefix("types@"), types_version) {
+    (Some(range), Some(version)) => {
+      conditions.contains(&"types")
+        && VersionReq::parse_from_npm(range)
+          .is_ok_and(|req| req.matches(version))
+    }
+    _ => false,
+  }
+}
+
+fn is_bare_specifier(target: &str) -> bool {
+  if target.starts_with("./")
+    || target.starts_with("../")
+    || target.starts_with('/')
+  {
+    return false;
+  }
+  // urls (ex. `node:fs`) aren't allowed
+  let is_url = target.split_once(':').is_some_and(|(scheme, _)| {
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+      && scheme
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+  });
+  !is_url
+}
+
+/// Gets if the path has a `.`, `..` or `node_modules` segment, which
+/// Node rejects like its invalidSegmentRegEx, including percent-encoded
+/// and uppercase forms. Empty segments (ex. `a//b`) are only deprecated,
+/// so they're allowed.
+fn has_invalid_segment(path: &str) -> bool {
+  path.split(['/', '\\']).any(|segment| {
+    let segment = decode_percent(segment).to_ascii_lowercase();
+    segment == "." || segment == ".." || segment == "node_modules"
+  })
+}
+
+pub(crate) fn decode_percent(text: &str) -> Cow<str> {
+  if !text.contains('%') {
+    return Cow::Borrowed(text);
+  }
+  let bytes = text.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    let hex = text.get(i + 1..i + 3);
+    match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+      Some(byte) if bytes[i] == b'%' => {
+        decoded.push(byte);
+        i += 3;
+      }
+      _ => {
+        decoded.push(bytes[i]);
+        i += 1;
+      }
+    }
+  }
+  Cow::Owned(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+/// Node's PATTERN_KEY_COMPARE, which orders keys from most to least
+/// specific.
+pub(crate) fn pattern_key_compare(a: &str, b: &str) -> Ordering {
+  let a_pattern_index = a.find('*');
+  let b_pattern_index = b.find('*');
+  let base_len_a = a_pattern_index.map_or(a.len(), |i| i + 1);
+  let base_len_b = b_pattern_index.map_or(b.len(), |i| i + 1);
+  base_len_b.cmp(&base_len_a).then_with(|| {
+    match (a_pattern_index, b_pattern_index) {
+      (None, _) => Ordering::Greater,
+      (_, None) => Ordering::Less,
+      _ => b.len().cmp(&a.len()),
+    }
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn matches_subpaths() {
+    let map = serde_json::json!({
+      "./a": "./a.js",
+      "./features/*": "./features/*.js",
+      "./features/*.js": "./features/*.js",
+      "./features/private/*": null,
+      "./*/nested": "./nested/*.js",
+    });
+    let map = map.as_object().unwrap();
+    let get =
+      |subpath| match_subpath(map, subpath).map(|m| (m.key, m.pattern_match));
+    assert_eq!(get("./a"), Some(("./a", None)));
+    assert_eq!(get("./b"), None);
+    assert_eq!(get("./features/x"), Some(("./features/*", Some("x"))));
+    assert_eq!(get("./features/x.js"), Some(("./features/*.js", Some("x"))));
+    assert_eq!(
+      get("./features/private/x"),
+      Some(("./features/private/*", Some("x")))
+    );
+    assert_eq!(get("./x/nested"), Some(("./*/nested", Some("x"))));
+    assert_eq!(get("./features/"), None);
+  }
+
+  #[test]
+  fn normalizes_exports() {
+    let a = serde_json::json!({
+      "./*": "./*.js",
+      ".": ["./index.js"],
+      "./a/*": { "default": "./a/*.js", "import": "./a/*.mjs" },
+      "./a": { "types": "./a.d.ts", "default": ["./a.js"] },
+    });
+    let b = serde_json::json!({
+      "./a": { "types": "./a.d.ts", "default": "./a.js" },
+      ".": "./index.js",
+      "./a/*": "./a/*.js",
+      "./*": "./*.js",
+    });
+    let a = normalize_exports(a.as_object().unwrap());
+    let b = normalize_exports(b.as_object().unwrap());
+    assert_eq!(a, b);
+    assert_eq!(
+      a.keys().collect::<Vec<_>>(),
+      vec!["./a/*", "./*", ".", "./a"]
+    );
+    // `Map` equality ignores the order, so compare the keys
+    assert_eq!(a.keys().collect::<Vec<_>>(), b.keys().collect::<Vec<_>>());
+
+    let keys = [
+      "./z", "./b/*", "./a", "./c", "./*.js", "./b", "./a/*", "./y", "./*",
+    ];
+    let forward = keys
+      .iter()
+      .map(|key| (key.to_string(), Value::from("./x.js")))
+      .collect::<Map<_, _>>();
+    let backward = keys
+      .iter()
+      .rev()
+      .map(|key| (key.to_string(), Value::from("./x.js")))
+      .collect::<Map<_, _>>();
+    let forward = normalize_exports(&forward);
+    let backward = normalize_exports(&backward);
+    assert_eq!(
+      forward.keys().collect::<Vec<_>>(),
+      vec![
+        "./a/*", "./b/*", "./*.js", "./*", "./a", "./b", "./c", "./y", "./z"
+      ]
+    );
+    assert_eq!(
+      forward.keys().collect::<Vec<_>>(),
+      backward.keys().collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn compares_pattern_keys() {
+    assert_eq!(pattern_key_compare("./a/*", "./*"), Ordering::Less);
+    assert_eq!(pattern_key_compare("./*", "./a/*"), Ordering::Greater);
+    assert_eq!(pattern_key_compare("./a*", "./a"), Ordering::Less);
+    assert_eq!(pattern_key_compare("./a/*.js", "./a/*"), Ordering::Less);
+    assert_eq!(pattern_key_compare("./a/*", "./a/*"), Ordering::Equal);
+  }
+}
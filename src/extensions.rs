@@ -0,0 +1,191 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::any::Any;
+use std::fmt;
+
+use indexmap::IndexMap;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::sync::MaybeSend;
+use crate::sync::MaybeSync;
+use crate::PackageJson;
+
+trait FieldParser: MaybeSend + MaybeSync {
+  fn parse(&self, value: &Value) -> Option<ExtensionValueRc>;
+}
+
+impl<T, F> FieldParser for F
+where
+  T: Any + MaybeSend + MaybeSync,
+  F: Fn(&Value) -> Option<T> + MaybeSend + MaybeSync,
+{
+  fn parse(&self, value: &Value) -> Option<ExtensionValueRc> {
+    let value = self(value)?;
+    Some(crate::sync::new_rc(value))
+  }
+}
+
+trait ExtensionValue: MaybeSend + MaybeSync {
+  fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Any + MaybeSend + MaybeSync> ExtensionValue for T {
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+}
+
+#[allow(clippy::disallowed_types)]
+type ExtensionValueRc = crate::sync::MaybeArc<dyn ExtensionValue>;
+
+/// Parsers for extra top level fields (ex. tool configuration), which
+/// are provided to the loaders with `PackageJsonLoadOptions`.
+///
+/// The results are available with `PackageJson::extension`.
+#[derive(Default)]
+pub struct PackageJsonFieldParsers {
+  parsers: Vec<(String, Box<dyn FieldParser>)>,
+}
+
+impl fmt::Debug for PackageJsonFieldParsers {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list()
+      .entries(self.parsers.iter().map(|(field, _)| field))
+      .finish()
+  }
+}
+
+impl PackageJsonFieldParsers {
+  /// Registers a parser for a top level field, which is called with the
+  /// raw value when the field is present. Returning `None` ignores the
+  /// value.
+  ///
+  /// Parsers registered later for the same field replace earlier ones.
+  pub fn register<T: Any + MaybeSend + MaybeSync>(
+    &mut self,
+    field: impl Into<String>,
+    parse: impl Fn(&Value) -> Option<T> + MaybeSend + MaybeSync + 'static,
+  ) {
+    let field = field.into();
+    self.parsers.retain(|(f, _)| *f != field);
+    self.parsers.push((field, Box::new(parse)));
+  }
+
+  pub(crate) fn parse(
+    &self,
+    map: &Map<String, Value>,
+  ) -> PackageJsonExtensions {
+    let mut values = IndexMap::new();
+    for (field, parser) in &self.parsers {
+      if let Some(value) = map.get(field).and_then(|v| parser.parse(v)) {
+        values.insert(field.clone(), value);
+      }
+    }
+    PackageJsonExtensions { values }
+  }
+}
+
+/// The parsed values of the extra top level fields.
+#[derive(Clone, Default)]
+pub struct PackageJsonExtensions {
+  values: IndexMap<String, ExtensionValueRc>,
+}
+
+impl fmt::Debug for PackageJsonExtensions {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.values.keys()).finish()
+  }
+}
+
+impl PackageJsonExtensions {
+  /// Gets the parsed value of the field, or `None` when the field wasn't
+  /// parsed or was parsed to a different type.
+  pub fn get<T: Any>(&self, field: &str) -> Option<&T> {
+    let value: &dyn ExtensionValue = &**self.values.get(field)?;
+    value.as_any().downcast_ref()
+  }
+
+  pub fn contains(&self, field: &str) -> bool {
+    self.values.contains_key(field)
+  }
+
+  /// The parsed fields in the order the parsers were registered.
+  pub fn fields(&self) -> impl Iterator<Item = &str> {
+    self.values.keys().map(|field| field.as_str())
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+}
+
+impl PackageJson {
+  /// Gets the value parsed from an extra top level field by a parser in
+  /// `PackageJsonLoadOptions::maybe_field_parsers`.
+  pub fn extension<T: Any>(&self, field: &str) -> Option<&T> {
+    self.extensions.get(field)
+  }
+
+  pub fn extensions(&self) -> &PackageJsonExtensions {
+    &self.extensions
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::Path;
+
+  use pretty_assertions::assert_eq;
+  use sys_traits::impls::InMemorySys;
+
+  use super::*;
+  use crate::PackageJsonLoadOptions;
+
+  #[derive(Debug, PartialEq)]
+  struct TscConfig {
+    strict: bool,
+  }
+
+  #[test]
+  fn parses_extensions() {
+    let mut parsers = PackageJsonFieldParsers::default();
+    parsers.register("tsc", |value| {
+      Some(TscConfig {
+        strict: value.get("strict")?.as_bool()?,
+      })
+    });
+    parsers.register("nodemonConfig", |value| value.as_str().map(String::from));
+    parsers.register("prettier", |value| Some(value.clone()));
+
+    let sys = InMemorySys::default();
+    sys.fs_insert(
+      "/package.json",
+      r#"{ "tsc": { "strict": true }, "nodemonConfig": 1, "name": "pkg" }"#,
+    );
+    let package_json = PackageJson::load_from_path_with_options(
+      &sys,
+      None,
+      Path::new("/package.json"),
+      PackageJsonLoadOptions {
+        maybe_field_parsers: Some(&parsers),
+        ..Default::default()
+      },
+    )
+    .unwrap();
+    assert_eq!(
+      package_json.extension::<TscConfig>("tsc"),
+      Some(&TscConfig { strict: true })
+    );
+    assert_eq!(package_json.extension::<String>("tsc"), None);
+    // the parser ignored the value
+    assert_eq!(package_json.extension::<String>("nodemonConfig"), None);
+    // absent
+    assert_eq!(package_json.extension::<Value>("prettier"), None);
+    assert_eq!(
+      package_json.extensions().fields().collect::<Vec<_>>(),
+      vec!["tsc"]
+    );
+    assert_eq!(package_json.name.as_deref(), Some("pkg"));
+  }
+}
@@ -0,0 +1,152 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::ops::BitOr;
+
+/// The top level package.json keys understood by this crate.
+pub(crate) const KNOWN_FIELDS: [&str; 35] = [
+  "name",
+  "version",
+  "private",
+  "type",
+  "main",
+  "module",
+  "types",
+  "typings",
+  "browser",
+  "exports",
+  "imports",
+  "bin",
+  "dependencies",
+  "devDependencies",
+  "optionalDependencies",
+  "bundleDependencies",
+  "bundledDependencies",
+  "scripts",
+  "workspaces",
+  "repository",
+  "bugs",
+  "author",
+  "jsnext:main",
+  "esnext",
+  "unpkg",
+  "jsdelivr",
+  "browserslist",
+  "os",
+  "cpu",
+  "packageManager",
+  "engines",
+  "peerDependencies",
+  "peerDependenciesMeta",
+  "pnpm",
+  "overrides",
+];
+
+/// A set of package.json fields to load.
+///
+/// Fields outside the set are skipped while loading and will be `None`
+/// (or the default) on the `PackageJson`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackageJsonFieldSet(u32);
+
+impl PackageJsonFieldSet {
+  pub const NAME: Self = Self(1 << 0);
+  pub const VERSION: Self = Self(1 << 1);
+  pub const TYPE: Self = Self(1 << 2);
+  /// The "main", "module", "jsnext:main", and "esnext" fields.
+  pub const MAIN: Self = Self(1 << 3);
+  /// The "types" and "typings" fields.
+  pub const TYPES: Self = Self(1 << 4);
+  pub const EXPORTS: Self = Self(1 << 5);
+  pub const IMPORTS: Self = Self(1 << 6);
+  pub const BIN: Self = Self(1 << 7);
+  pub const BROWSER: Self = Self(1 << 8);
+  /// The "dependencies", "devDependencies", "optionalDependencies",
+  /// "peerDependencies", and "peerDependenciesMeta" fields.
+  pub const DEPENDENCIES: Self = Self(1 << 9);
+  /// The "bundleDependencies" and "bundledDependencies" fields.
+  pub const BUNDLE_DEPENDENCIES: Self = Self(1 << 10);
+  pub const SCRIPTS: Self = Self(1 << 11);
+  pub const WORKSPACES: Self = Self(1 << 12);
+  /// The "repository", "bugs", "author", "private", "unpkg", "jsdelivr",
+  /// and "browserslist" fields.
+  pub const METADATA: Self = Self(1 << 13);
+  /// The "os" and "cpu" fields.
+  pub const PLATFORM: Self = Self(1 << 14);
+  pub const PACKAGE_MANAGER: Self = Self(1 << 15);
+  pub const ENGINES: Self = Self(1 << 16);
+  /// The "pnpm" field.
+  pub const PNPM: Self = Self(1 << 17);
+  pub const OVERRIDES: Self = Self(1 << 18);
+
+  pub const ALL: Self = Self((1 << 19) - 1);
+  /// The fields needed for module resolution.
+  pub const RESOLUTION: Self = Self(
+    Self::NAME.0
+      | Self::TYPE.0
+      | Self::MAIN.0
+      | Self::TYPES.0
+      | Self::EXPORTS.0
+      | Self::IMPORTS.0,
+  );
+
+  pub const fn contains(self, other: Self) -> bool {
+    self.0 & other.0 == other.0
+  }
+
+  /// Gets the field set a top level package.json key belongs to.
+  pub(crate) fn for_key(key: &str) -> Option<Self> {
+    Some(match key {
+      "name" => Self::NAME,
+      "version" => Self::VERSION,
+      "type" => Self::TYPE,
+      "main" | "module" | "jsnext:main" | "esnext" => Self::MAIN,
+      "types" | "typings" => Self::TYPES,
+      "exports" => Self::EXPORTS,
+      "imports" => Self::IMPORTS,
+      "bin" => Self::BIN,
+      "browser" => Self::BROWSER,
+      "dependencies"
+      | "devDependencies"
+      | "optionalDependencies"
+      | "peerDependencies"
+      | "peerDependenciesMeta" => Self::DEPENDENCIES,
+      "bundleDependencies" | "bundledDependencies" => Self::BUNDLE_DEPENDENCIES,
+      "scripts" => Self::SCRIPTS,
+      "workspaces" => Self::WORKSPACES,
+      "repository" | "bugs" | "author" | "private" | "unpkg" | "jsdelivr"
+      | "browserslist" => Self::METADATA,
+      "os" | "cpu" => Self::PLATFORM,
+      "packageManager" => Self::PACKAGE_MANAGER,
+      "engines" => Self::ENGINES,
+      "pnpm" => Self::PNPM,
+      "overrides" => Self::OVERRIDES,
+      _ => return None,
+    })
+  }
+}
+
+impl Default for PackageJsonFieldSet {
+  fn default() -> Self {
+    Self::ALL
+  }
+}
+
+impl BitOr for PackageJsonFieldSet {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self {
+    Self(self.0 | rhs.0)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn known_fields_have_field_set() {
+    for key in KNOWN_FIELDS {
+      assert!(PackageJsonFieldSet::for_key(key).is_some(), "{key}");
+    }
+  }
+}
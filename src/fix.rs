@@ -0,0 +1,211 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use serde_json::Value;
+
+use crate::text_edit::remove_property_edit;
+use crate::text_edit::reorder_properties_edit;
+use crate::text_edit::source_properties_at;
+use crate::PackageJsonDiagnostic;
+use crate::PackageJsonDiagnosticKind;
+use crate::PackageJsonTextEdit;
+
+/// A suggested fix for a diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageJsonFix {
+  pub title: String,
+  pub edits: Vec<PackageJsonTextEdit>,
+}
+
+impl PackageJsonDiagnostic {
+  /// Gets the fix for the diagnostic when it can be fixed mechanically.
+  ///
+  /// The source must be the text of the package.json the diagnostic was
+  /// found in.
+  pub fn fix(&self, source: &str) -> Option<PackageJsonFix> {
+    let (parent_pointer, _) = self.pointer.rsplit_once('/')?;
+    match &self.kind {
+      PackageJsonDiagnosticKind::DefaultConditionNotLast { .. } => {
+        let properties = source_properties_at(source, parent_pointer)?;
+        let mut order = properties.iter().collect::<Vec<_>>();
+        // stable, so the other conditions keep their order
+        order.sort_by_key(|property| property.key == "default");
+        let edit = reorder_properties_edit(source, &properties, &order)?;
+        Some(PackageJsonFix {
+          title: "Move the \"default\" condition last".to_string(),
+          edits: vec![edit],
+        })
+      }
+      PackageJsonDiagnosticKind::DeprecatedFolderMapping { key } => {
+        let properties = source_properties_at(source, parent_pointer)?;
+        let property = properties.iter().rev().find(|p| &p.key == key)?;
+        let target: Value =
+          serde_json::from_str(&source[property.value_range.clone()]).ok()?;
+        let target = target.as_str().filter(|t| t.ends_with('/'))?;
+        let new_key = format!("{}*", key);
+        Some(PackageJsonFix {
+          title: format!("Replace with the \"{}\" subpath pattern", new_key),
+          edits: vec![
+            PackageJsonTextEdit {
+              range: property.key_range.clone(),
+              new_text: Value::from(new_key).to_string(),
+            },
+            PackageJsonTextEdit {
+              range: property.value_range.clone(),
+              new_text: Value::from(format!("{}*", target)).to_string(),
+            },
+          ],
+        })
+      }
+      PackageJsonDiagnosticKind::UntrimmedVersionReq { name, specifier } => {
+        let properties = source_properties_at(source, parent_pointer)?;
+        let property = properties.iter().rev().find(|p| &p.key == name)?;
+        Some(PackageJsonFix {
+          title: "Remove the surrounding whitespace".to_string(),
+          edits: vec![PackageJsonTextEdit {
+            range: property.value_range.clone(),
+            new_text: Value::from(specifier.trim()).to_string(),
+          }],
+        })
+      }
+      PackageJsonDiagnosticKind::NumberVersion { version } => {
+        let properties = source_properties_at(source, parent_pointer)?;
+        let property = properties.iter().rev().find(|p| p.key == "version")?;
+        Some(PackageJsonFix {
+          title: "Convert to a string".to_string(),
+          edits: vec![PackageJsonTextEdit {
+            range: property.value_range.clone(),
+            new_text: Value::from(version.as_str()).to_string(),
+          }],
+        })
+      }
+      PackageJsonDiagnosticKind::DuplicateKey { key } => {
+        let properties = source_properties_at(source, parent_pointer)?;
+        let last_index = properties.iter().rposition(|p| &p.key == key)?;
+        let edits = (0..last_index)
+          .filter(|i| &properties[*i].key == key)
+          .map(|i| remove_property_edit(&properties, i))
+          .collect::<Vec<_>>();
+        if edits.is_empty() {
+          return None;
+        }
+        Some(PackageJsonFix {
+          title: format!("Remove the earlier \"{}\" keys", key),
+          edits,
+        })
+      }
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+
+  use crate::apply_text_edits;
+  use crate::PackageJson;
+
+  #[test]
+  fn fixes_diagnostics() {
+    let source = r#"{
+  "exports": {
+    ".": [{ "default": "./a.js", "import": "./a.mjs" }],
+    "./dir/": "./dist/dir/",
+    "./other/": { "default": "./dist/other/" }
+  }
+}"#;
+    let package_json =
+      PackageJson::load_from_string(PathBuf::from("/package.json"), source)
+        .unwrap();
+    let fixes = package_json
+      .diagnostics()
+      .iter()
+      .map(|d| d.fix(source))
+      .collect::<Vec<_>>();
+    assert_eq!(fixes.len(), 3);
+    // conditions can't be fixed mechanically
+    assert!(fixes[1].is_none());
+    let edits = fixes
+      .iter()
+      .flatten()
+      .flat_map(|fix| fix.edits.clone())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      apply_text_edits(source, &edits),
+      r#"{
+  "exports": {
+    ".": [{ "import": "./a.mjs", "default": "./a.js" }],
+    "./dir/*": "./dist/dir/*",
+    "./other/": { "default": "./dist/other/" }
+  }
+}"#
+    );
+  }
+
+  #[test]
+  fn fixes_untrimmed_version_reqs() {
+    let source = r#"{ "dependencies": { "a": " ^1.2 ", "b": "^2" } }"#;
+    let package_json =
+      PackageJson::load_from_string(PathBuf::from("/package.json"), source)
+        .unwrap();
+    let diagnostics = package_json.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+      diagnostics[0].message(),
+      "Dependency \"a\" has whitespace around \"^1.2\", which is ignored."
+    );
+    let fix = diagnostics[0].fix(source).unwrap();
+    assert_eq!(
+      apply_text_edits(source, &fix.edits),
+      r#"{ "dependencies": { "a": "^1.2", "b": "^2" } }"#
+    );
+    let deps = package_json.resolve_local_package_json_deps();
+    assert_eq!(deps.dependency_req("a").unwrap().to_string(), "a@^1.2");
+  }
+
+  #[test]
+  fn fixes_source_diagnostics() {
+    let source = r#"{
+  "name": "a",
+  "version": 1.0,
+  "name": "b",
+  "exports": { ".": "./a.js", "./b": "./b.js", ".": "./c.js" },
+  "name": "c"
+}"#;
+    let diagnostics = PackageJson::source_diagnostics(source).unwrap();
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|d| (d.pointer.as_str(), d.code()))
+        .collect::<Vec<_>>(),
+      vec![
+        ("/version", "number-version"),
+        ("/name", "duplicate-key"),
+        ("/exports/.", "duplicate-key"),
+      ]
+    );
+    assert_eq!(
+      diagnostics[0].message(),
+      "Version 1.0 is a number, but it must be a string."
+    );
+    assert_eq!(
+      diagnostics[1].message(),
+      "Key \"name\" is defined more than once. Only the last one is used."
+    );
+    let edits = diagnostics
+      .iter()
+      .flat_map(|d| d.fix(source).unwrap().edits)
+      .collect::<Vec<_>>();
+    assert_eq!(
+      apply_text_edits(source, &edits),
+      r#"{
+  "version": "1.0",
+  "exports": { "./b": "./b.js", ".": "./c.js" },
+  "name": "c"
+}"#
+    );
+    assert!(PackageJson::source_diagnostics("{").is_err());
+  }
+}
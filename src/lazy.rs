@@ -0,0 +1,237 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+use serde_json::value::RawValue;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::map_object;
+use crate::map_string;
+use crate::normalize_path;
+use crate::parse_exports;
+use crate::parse_string_array;
+use crate::parse_string_map;
+use crate::parse_type;
+use crate::NodeModuleKind;
+use crate::PackageJson;
+use crate::PackageJsonLoadError;
+use crate::PackageJsonLoadErrorKind;
+
+#[allow(clippy::disallowed_types)]
+type LazyField<T> = crate::sync::MaybeOnceLock<Option<T>>;
+
+/// A package.json that only parses the cheap fields (`name`, `version`,
+/// `type`, `main`, `module`, and `types`) upfront and keeps the remaining
+/// fields as raw JSON until they're first accessed.
+///
+/// This is useful on hot paths, such as node_modules discovery, where
+/// most manifests are only inspected for their module type or entrypoint.
+#[derive(Debug)]
+pub struct LazyPackageJson {
+  pub path: PathBuf,
+  pub name: Option<String>,
+  pub version: Option<String>,
+  pub typ: String,
+  pub types: Option<String>,
+  main: Option<String>,
+  module: Option<String>,
+  raw: IndexMap<String, Box<RawValue>>,
+  exports: LazyField<Map<String, Value>>,
+  imports: LazyField<Map<String, Value>>,
+  dependencies: LazyField<IndexMap<String, String>>,
+  dev_dependencies: LazyField<IndexMap<String, String>>,
+  scripts: LazyField<IndexMap<String, String>>,
+  workspaces: LazyField<Vec<String>>,
+}
+
+impl LazyPackageJson {
+  pub fn load_from_string(
+    path: PathBuf,
+    source: &str,
+  ) -> Result<LazyPackageJson, PackageJsonLoadError> {
+    let raw: IndexMap<String, Box<RawValue>> = if source.trim().is_empty() {
+      IndexMap::new()
+    } else {
+      let deserialize_error = |err| {
+        PackageJsonLoadErrorKind::Deserialize {
+          path: path.clone(),
+          source: err,
+        }
+        .into_box()
+      };
+      // only the top level is parsed here and the field values are kept
+      // as raw JSON until they're requested
+      let value: &RawValue =
+        serde_json::from_str(source).map_err(deserialize_error)?;
+      if value.get().starts_with('{') {
+        serde_json::from_str(value.get()).map_err(deserialize_error)?
+      } else {
+        IndexMap::new()
+      }
+    };
+    // the cheap fields are kept in `raw` so `to_owned` sees the source
+    let field = |name: &str| raw.get(name).and_then(|value| parse_raw(value));
+    let name = field("name").and_then(map_string);
+    let version = field("version").and_then(map_string);
+    let typ = parse_type(field("type"));
+    let main = field("main").and_then(map_string);
+    let module = field("module").and_then(map_string);
+    // for typescript, it looks for "typings" first, then "types"
+    let types = field("typings")
+      .or_else(|| field("types"))
+      .and_then(map_string);
+    Ok(LazyPackageJson {
+      path: normalize_path(path),
+      name,
+      version,
+      typ,
+      types,
+      main,
+      module,
+      raw,
+      exports: Default::default(),
+      imports: Default::default(),
+      dependencies: Default::default(),
+      dev_dependencies: Default::default(),
+      scripts: Default::default(),
+      workspaces: Default::default(),
+    })
+  }
+
+  pub fn main(&self, referrer_kind: NodeModuleKind) -> Option<&str> {
+    self.main_with_conditions(referrer_kind.conditions())
+  }
+
+  /// See `PackageJson::main_with_conditions`.
+  pub fn main_with_conditions(&self, conditions: &[&str]) -> Option<&str> {
+    crate::select_main(
+      &self.typ,
+      self.main.as_deref(),
+      self.module.as_deref(),
+      conditions,
+      false,
+    )
+  }
+
+  pub fn exports(&self) -> Option<&Map<String, Value>> {
+    self
+      .exports
+      .get_or_init(|| {
+        self
+          .raw_field("exports")
+          .and_then(|exports| parse_exports(exports).ok().flatten())
+      })
+      .as_ref()
+  }
+
+  pub fn imports(&self) -> Option<&Map<String, Value>> {
+    self
+      .imports
+      .get_or_init(|| self.raw_field("imports").and_then(map_object))
+      .as_ref()
+  }
+
+  pub fn dependencies(&self) -> Option<&IndexMap<String, String>> {
+    self
+      .dependencies
+      .get_or_init(|| self.raw_field("dependencies").and_then(parse_string_map))
+      .as_ref()
+  }
+
+  pub fn dev_dependencies(&self) -> Option<&IndexMap<String, String>> {
+    self
+      .dev_dependencies
+      .get_or_init(|| {
+        self.raw_field("devDependencies").and_then(parse_string_map)
+      })
+      .as_ref()
+  }
+
+  pub fn scripts(&self) -> Option<&IndexMap<String, String>> {
+    self
+      .scripts
+      .get_or_init(|| self.raw_field("scripts").and_then(parse_string_map))
+      .as_ref()
+  }
+
+  pub fn workspaces(&self) -> Option<&Vec<String>> {
+    self
+      .workspaces
+      .get_or_init(|| self.raw_field("workspaces").and_then(parse_string_array))
+      .as_ref()
+  }
+
+  /// Fully parses the package.json.
+  pub fn to_owned(&self) -> PackageJson {
+    let package_json = self
+      .raw
+      .iter()
+      .filter_map(|(key, value)| Some((key.clone(), parse_raw(value)?)))
+      .collect::<Map<_, _>>();
+    PackageJson::load_from_value(self.path.clone(), Value::Object(package_json))
+  }
+
+  fn raw_field(&self, name: &str) -> Option<Value> {
+    self.raw.get(name).and_then(|value| parse_raw(value))
+  }
+}
+
+fn parse_raw(value: &RawValue) -> Option<Value> {
+  serde_json::from_str(value.get()).ok()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn lazily_parses_fields() {
+    let package_json = LazyPackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{
+        "name": "pkg",
+        "type": "module",
+        "main": "./main.js",
+        "module": "./module.js",
+        "exports": "./index.js",
+        "dependencies": { "a": "^1" }
+      }"#,
+    )
+    .unwrap();
+    assert_eq!(package_json.main(NodeModuleKind::Esm), Some("./module.js"));
+    assert_eq!(package_json.main(NodeModuleKind::Cjs), Some("./main.js"));
+    assert!(package_json.exports.get().is_none());
+    assert_eq!(package_json.raw["exports"].get(), r#""./index.js""#);
+    assert_eq!(
+      package_json.exports().unwrap().get(".").unwrap(),
+      "./index.js"
+    );
+    assert_eq!(package_json.dependencies().unwrap().get("a").unwrap(), "^1");
+    assert!(package_json.scripts().is_none());
+
+    let owned = package_json.to_owned();
+    assert_eq!(owned.name.as_deref(), Some("pkg"));
+    assert_eq!(owned.typ, "module");
+    assert_eq!(owned.main(NodeModuleKind::Esm), Some("./module.js"));
+    assert_eq!(owned.exports.as_deref(), package_json.exports());
+
+    let package_json = LazyPackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{ "typings": "./a.d.ts", "types": "./b.d.ts" }"#,
+    )
+    .unwrap();
+    assert_eq!(package_json.types.as_deref(), Some("./a.d.ts"));
+    let owned = package_json.to_owned();
+    assert!(!owned.has_field("type"));
+    assert_eq!(
+      owned
+        .diagnostics()
+        .iter()
+        .map(|d| d.code())
+        .collect::<Vec<_>>(),
+      vec!["conflicting-types"]
+    );
+  }
+}
@@ -5,35 +5,281 @@
 #![deny(clippy::unused_async)]
 #![deny(clippy::unnecessary_wraps)]
 
+use std::borrow::Borrow;
+use std::borrow::Cow;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use boxed_error::Boxed;
 use deno_error::JsError;
+#[cfg(feature = "url")]
+use deno_path_util::PathToUrlError;
 use deno_semver::npm::NpmVersionReqParseError;
 use deno_semver::package::PackageReq;
 use deno_semver::StackString;
 use deno_semver::VersionReq;
 use indexmap::IndexMap;
+use indexmap::IndexSet;
+use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Map;
 use serde_json::Value;
+use sys_traits::EnvCurrentDir;
+use sys_traits::FsCanonicalize;
 use sys_traits::FsRead;
 use thiserror::Error;
+#[cfg(feature = "url")]
 use url::Url;
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+mod bin;
+mod borrowed;
+mod conditions;
+mod deno_imports;
+mod dep_source;
+mod deps_map;
+mod diagnostics;
+mod engines;
+mod exports;
+mod extensions;
+mod field_set;
+mod fix;
+mod lazy;
+mod limits;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod module_kind;
+mod normalize;
+mod overrides;
+mod package_extensions;
+mod package_manager;
+mod platform;
+mod publish;
+mod recover;
+mod resolve;
+#[cfg(feature = "schema-validation")]
+mod schema_validation;
+mod scripts;
+#[cfg(feature = "snapshot")]
+mod snapshot;
+mod sort;
+mod string_pool;
 mod sync;
+mod text_edit;
+mod version_req_kind;
+mod visit;
+mod workspace;
+
+#[cfg(feature = "arbitrary")]
+pub use arbitrary_impls::arbitrary_exports;
+#[cfg(feature = "arbitrary")]
+pub use arbitrary_impls::arbitrary_imports;
+pub use bin::PackageJsonBinConflict;
+pub use bin::PackageJsonBinEntries;
+pub use borrowed::PackageJsonRef;
+pub use conditions::BROWSER_CONDITIONS;
+pub use conditions::DEFAULT_NODE_CONDITIONS;
+pub use conditions::DEFAULT_NODE_REQUIRE_CONDITIONS;
+pub use conditions::DENO_CONDITIONS;
+pub use conditions::DENO_REQUIRE_CONDITIONS;
+pub use conditions::TYPES_CONDITIONS;
+pub use conditions::TYPES_REQUIRE_CONDITIONS;
+pub use dep_source::PackageJsonDepSource;
+pub use dep_source::PackageJsonNonRegistryDep;
+pub use deps_map::PackageJsonDepsMap;
+pub use deps_map::PackageJsonDepsMapEntry;
+pub use diagnostics::PackageJsonDiagnostic;
+pub use diagnostics::PackageJsonDiagnosticKind;
+pub use diagnostics::PackageJsonDiagnosticSeverity;
+pub use diagnostics::PackageJsonDiagnosticsOptions;
+pub use engines::PackageJsonEngineCheck;
+pub use engines::PackageJsonEngineStatus;
+pub use engines::PackageJsonEnginesEnv;
+pub use engines::PackageJsonEnginesReport;
+pub use extensions::PackageJsonExtensions;
+pub use extensions::PackageJsonFieldParsers;
+pub use field_set::PackageJsonFieldSet;
+pub use fix::PackageJsonFix;
+pub use lazy::LazyPackageJson;
+pub use limits::PackageJsonLimitError;
+pub use limits::PackageJsonLimits;
+pub use overrides::PackageJsonOverride;
+pub use overrides::PackageJsonOverridesError;
+pub use package_extensions::PackageJsonPackageExtension;
+pub use package_extensions::PackageJsonPnpmConfig;
+pub use package_manager::PackageJsonPackageManager;
+pub use package_manager::PackageJsonPackageManagerHash;
+pub use package_manager::PackageJsonPackageManagerMismatch;
+pub use platform::npm_cpu_from_rust;
+pub use platform::npm_cpu_to_rust;
+pub use platform::npm_os_from_rust;
+pub use platform::npm_os_to_rust;
+pub use platform::npm_platform_from_target_triple;
+pub use resolve::PackageJsonEnv;
+pub use resolve::PackageJsonResolutionOptions;
+pub use resolve::PackageJsonResolveError;
+pub use resolve::PackageJsonResolveErrorKind;
+#[cfg(feature = "schema-validation")]
+pub use schema_validation::PackageJsonSchemaValidator;
+pub use scripts::PackageJsonLifecycleEvent;
+#[cfg(feature = "snapshot")]
+pub use snapshot::PackageJsonSnapshotError;
+pub use sort::PACKAGE_JSON_FIELD_ORDER;
+pub use string_pool::PackageJsonStringPool;
+pub use string_pool::PackageJsonStringPoolRc;
+pub use text_edit::apply_text_edits;
+pub use text_edit::PackageJsonTextEdit;
+pub use version_req_kind::PackageJsonVersionReqKind;
+pub use visit::PackageJsonNode;
+pub use visit::PackageJsonVisitor;
+pub use visit::PackageJsonWalkOptions;
+pub use workspace::PackageJsonWorkspace;
+pub use workspace::PackageJsonWorkspaceBumpEdit;
+pub use workspace::PackageJsonWorkspaceDep;
+pub use workspace::PackageJsonWorkspaceDepError;
 
 #[allow(clippy::disallowed_types)]
 pub type PackageJsonRc = crate::sync::MaybeArc<PackageJson>;
 #[allow(clippy::disallowed_types)]
 pub type PackageJsonDepsRc = crate::sync::MaybeArc<PackageJsonDeps>;
 #[allow(clippy::disallowed_types)]
+pub type PackageJsonValueMapRc = crate::sync::MaybeArc<Map<String, Value>>;
+#[allow(clippy::disallowed_types)]
+pub type PackageJsonStringMapRc =
+  crate::sync::MaybeArc<IndexMap<String, String>>;
+#[allow(clippy::disallowed_types)]
+pub type PackageJsonPathRc = crate::sync::MaybeArc<Path>;
+#[allow(clippy::disallowed_types)]
+pub type PackageJsonStringsRc = crate::sync::MaybeArc<[String]>;
+#[allow(clippy::disallowed_types)]
 type PackageJsonDepsRcCell = crate::sync::MaybeOnceLock<PackageJsonDepsRc>;
 
-pub trait PackageJsonCache: std::fmt::Debug {
+pub trait PackageJsonCache:
+  std::fmt::Debug + crate::sync::MaybeSend + crate::sync::MaybeSync
+{
   fn get(&self, path: &Path) -> Option<PackageJsonRc>;
   fn set(&self, path: PathBuf, package_json: PackageJsonRc);
+
+  /// Gets the cached package.json or else caches the one created by `f`.
+  ///
+  /// The default implementation uses `get` and `set`, so concurrent
+  /// callers may each run `f` for the same path. Implementations that can
+  /// lock per path should override this so only one caller parses the
+  /// file while the others wait for its result.
+  fn get_or_try_insert_with(
+    &self,
+    path: &Path,
+    f: Box<dyn FnOnce() -> Result<PackageJsonRc, PackageJsonLoadError> + '_>,
+  ) -> Result<PackageJsonRc, PackageJsonLoadError> {
+    if let Some(item) = self.get(path) {
+      return Ok(item);
+    }
+    let item = f()?;
+    self.set(path.to_path_buf(), item.clone());
+    Ok(item)
+  }
+}
+
+/// Receives notifications about package.json loading, which is useful
+/// for attributing startup time to manifest parsing and cache usage.
+///
+/// All methods have empty default implementations.
+#[allow(unused_variables)]
+pub trait PackageJsonObserver:
+  std::fmt::Debug + crate::sync::MaybeSend + crate::sync::MaybeSync
+{
+  /// The package.json was found in the cache.
+  fn on_cache_hit(&self, path: &Path) {}
+  /// The package.json was not found in the cache and will be read.
+  fn on_cache_miss(&self, path: &Path) {}
+  /// Parsing of the file's text is about to start.
+  fn on_parse_start(&self, path: &Path) {}
+  /// Parsing finished (successfully or not) after the provided duration.
+  fn on_parse_end(&self, path: &Path, duration: Duration) {}
+}
+
+/// `Instant::now()` panics on wasm32-unknown-unknown, so no timings are
+/// reported there.
+struct ParseTimer {
+  #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+  start: std::time::Instant,
+}
+
+impl ParseTimer {
+  fn start() -> Self {
+    Self {
+      #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+      start: std::time::Instant::now(),
+    }
+  }
+
+  fn elapsed(&self) -> Duration {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    return self.start.elapsed();
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    Duration::ZERO
+  }
+}
+
+/// How the `path` of a loaded package.json is stored.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PackageJsonPathPolicy {
+  /// The path as provided by the caller.
+  #[default]
+  AsGiven,
+  /// Relative paths are resolved against the current directory and `.`
+  /// and `..` components are removed without accessing the file system.
+  Absolute,
+  /// Symlinks in the directory of the package.json are resolved.
+  Canonical,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PackageJsonLoadOptions<'a> {
+  pub maybe_observer: Option<&'a dyn PackageJsonObserver>,
+  /// Pool used to share dependency names and version requirements
+  /// between the loaded package.json files.
+  pub maybe_string_pool: Option<&'a PackageJsonStringPoolRc>,
+  /// Limits to enforce on the package.json, which should be provided
+  /// when loading untrusted files.
+  ///
+  /// The cache is bypassed when these are provided because the cached
+  /// package.json files may not have been checked against them.
+  pub maybe_limits: Option<&'a PackageJsonLimits>,
+  /// Fields to load, which defaults to all of them. Loading fewer fields
+  /// is faster, but bypasses the cache so that later loads of all the
+  /// fields don't get the partial package.json.
+  pub fields: PackageJsonFieldSet,
+  /// Resolve dependencies with unsupported schemes to
+  /// `PackageJsonDepValue::Raw` instead of erroring.
+  ///
+  /// The cache is bypassed when this is enabled so that strict loads
+  /// never get a lenient package.json from it.
+  pub lenient_dep_schemes: bool,
+  /// Lowercase the paths used as cache keys, so that paths differing only
+  /// in case (ex. `C:\Proj\package.json` and `c:\proj\package.json`) share
+  /// an entry. Enable this on case-insensitive file systems.
+  pub case_insensitive_cache_keys: bool,
+  /// Parsers for extra top level fields, whose results are available
+  /// with `PackageJson::extension`.
+  ///
+  /// The cache is bypassed when these are provided because the cached
+  /// package.json files may have been parsed with other parsers.
+  pub maybe_field_parsers: Option<&'a PackageJsonFieldParsers>,
+}
+
+impl PackageJsonLoadOptions<'_> {
+  /// Gets if the loaded package.json can be shared through the cache,
+  /// which is only the case when it's the same as the one loaded with
+  /// the default options.
+  fn is_cacheable(&self) -> bool {
+    self.fields == PackageJsonFieldSet::ALL
+      && self.maybe_limits.is_none()
+      && !self.lenient_dep_schemes
+      && self.maybe_field_parsers.is_none()
+  }
 }
 
 #[derive(Debug, Clone, JsError, PartialEq, Eq, Boxed)]
@@ -63,16 +309,76 @@ pub enum PackageJsonDepWorkspaceReq {
   VersionReq(VersionReq),
 }
 
+/// Displays the requirement as written in a package.json, including the
+/// `workspace:` prefix.
+impl std::fmt::Display for PackageJsonDepWorkspaceReq {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      PackageJsonDepWorkspaceReq::Tilde => write!(f, "workspace:~"),
+      PackageJsonDepWorkspaceReq::Caret => write!(f, "workspace:^"),
+      PackageJsonDepWorkspaceReq::VersionReq(req) => {
+        write!(f, "workspace:{}", req)
+      }
+    }
+  }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum PackageJsonDepValue {
   Req(PackageReq),
   Workspace(PackageJsonDepWorkspaceReq),
+  /// A dependency using a scheme that isn't supported (ex. `git:`), which
+  /// is only produced when loading with `lenient_dep_schemes`.
+  Raw {
+    scheme: String,
+    /// The whole specifier (ex. `git:github.com/user/repo`).
+    value: String,
+  },
+}
+
+impl PackageJsonDepValue {
+  /// Gets the text to write as the value of the dependency with the
+  /// provided alias in a package.json (ex. `^1.0.0` for `"pkg": "^1.0.0"`
+  /// or `npm:pkg@^1.0.0` when the alias differs from the package name).
+  pub fn to_specifier_string(&self, alias: &str) -> String {
+    match self {
+      PackageJsonDepValue::Req(req) if req.name == alias => {
+        req.version_req.to_string()
+      }
+      _ => self.to_string(),
+    }
+  }
+
+  /// Gets the dist tag to look up in the registry for the package, which
+  /// is also found in aliases (ex. `beta` for `npm:pkg@beta`).
+  pub fn dist_tag(&self) -> Option<&str> {
+    match self {
+      PackageJsonDepValue::Req(req) => req.version_req.tag(),
+      _ => None,
+    }
+  }
+}
+
+/// Displays the value in a form that's valid in a package.json
+/// regardless of the dependency's alias.
+impl std::fmt::Display for PackageJsonDepValue {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      PackageJsonDepValue::Req(req) => {
+        write!(f, "npm:{}@{}", req.name, req.version_req)
+      }
+      PackageJsonDepValue::Workspace(req) => req.fmt(f),
+      PackageJsonDepValue::Raw { value, .. } => f.write_str(value),
+    }
+  }
 }
 
-pub type PackageJsonDepsMap = IndexMap<
-  StackString,
-  Result<PackageJsonDepValue, PackageJsonDepValueParseError>,
->;
+/// The section of the package.json a dependency is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackageJsonDepKind {
+  Normal,
+  Dev,
+}
 
 #[derive(Debug, Clone)]
 pub struct PackageJsonDeps {
@@ -91,10 +397,106 @@ impl PackageJsonDeps {
       .get(alias)
       .or_else(|| self.dev_dependencies.get(alias))
   }
+
+  /// Gets if the alias is in any dependency section, even when its value
+  /// failed to parse.
+  pub fn has_dependency(&self, alias: &str) -> bool {
+    self.get(alias).is_some()
+  }
+
+  /// Gets a successfully parsed dependency by alias along with the
+  /// section it's in.
+  pub fn find_dependency(
+    &self,
+    alias: &str,
+  ) -> Option<(PackageJsonDepKind, &PackageJsonDepValue)> {
+    self
+      .dependencies
+      .get(alias)
+      .map(|value| (PackageJsonDepKind::Normal, value))
+      .or_else(|| {
+        self
+          .dev_dependencies
+          .get(alias)
+          .map(|value| (PackageJsonDepKind::Dev, value))
+      })
+      .and_then(|(kind, value)| Some((kind, value.as_ref().ok()?)))
+  }
+
+  /// Gets the npm package requirement of a dependency by alias.
+  pub fn dependency_req(&self, alias: &str) -> Option<&PackageReq> {
+    match self.find_dependency(alias)? {
+      (_, PackageJsonDepValue::Req(req)) => Some(req),
+      _ => None,
+    }
+  }
+
+  /// Gets the npm package requirements of the dependencies that parsed
+  /// successfully, without duplicates.
+  pub fn package_reqs(&self, include_dev: bool) -> Vec<PackageReq> {
+    self
+      .valid()
+      .filter(|(kind, _, _)| include_dev || *kind != PackageJsonDepKind::Dev)
+      .filter_map(|(_, _, value)| match value {
+        PackageJsonDepValue::Req(req) => Some(req.clone()),
+        _ => None,
+      })
+      .collect::<IndexSet<_>>()
+      .into_iter()
+      .collect()
+  }
+
+  fn iter_with_kind(
+    &self,
+  ) -> impl Iterator<
+    Item = (
+      PackageJsonDepKind,
+      &StackString,
+      &Result<PackageJsonDepValue, PackageJsonDepValueParseError>,
+    ),
+  > {
+    let normal = self
+      .dependencies
+      .iter()
+      .map(|(alias, value)| (PackageJsonDepKind::Normal, alias, value));
+    let dev = self
+      .dev_dependencies
+      .iter()
+      .map(|(alias, value)| (PackageJsonDepKind::Dev, alias, value));
+    normal.chain(dev)
+  }
+
+  /// Gets the dependencies that failed to parse.
+  pub fn errors(
+    &self,
+  ) -> impl Iterator<
+    Item = (
+      PackageJsonDepKind,
+      &StackString,
+      &PackageJsonDepValueParseError,
+    ),
+  > {
+    self.iter_with_kind().filter_map(|(kind, alias, value)| {
+      Some((kind, alias, value.as_ref().err()?))
+    })
+  }
+
+  /// Gets the dependencies that parsed successfully.
+  pub fn valid(
+    &self,
+  ) -> impl Iterator<Item = (PackageJsonDepKind, &StackString, &PackageJsonDepValue)>
+  {
+    self.iter_with_kind().filter_map(|(kind, alias, value)| {
+      Some((kind, alias, value.as_ref().ok()?))
+    })
+  }
 }
 
+#[derive(Debug, JsError, Boxed)]
+pub struct PackageJsonLoadError(pub Box<PackageJsonLoadErrorKind>);
+
 #[derive(Debug, Error, JsError)]
-pub enum PackageJsonLoadError {
+pub enum PackageJsonLoadErrorKind {
   #[class(inherit)]
   #[error("Failed reading '{}'.", .path.display())]
   Io {
@@ -111,6 +513,86 @@ pub enum PackageJsonLoadError {
     #[inherit]
     source: serde_json::Error,
   },
+  #[class(inherit)]
+  #[error("Exceeded limits of package.json '{}'.", .path.display())]
+  LimitExceeded {
+    path: PathBuf,
+    #[source]
+    #[inherit]
+    source: PackageJsonLimitError,
+  },
+}
+
+#[derive(Debug, Error, JsError)]
+#[class(generic)]
+#[error("Could not get the directory of package.json '{}'.", .0.display())]
+pub struct PackageJsonDirPathError(pub PathBuf);
+
+/// The "bundleDependencies" field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageJsonBundleDependencies {
+  /// `true`, which bundles all the dependencies.
+  All,
+  /// The names of the dependencies to bundle.
+  Names(Vec<String>),
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for PackageJsonBundleDependencies {
+  fn schema_name() -> std::borrow::Cow<'static, str> {
+    "PackageJsonBundleDependencies".into()
+  }
+
+  fn json_schema(
+    generator: &mut schemars::SchemaGenerator,
+  ) -> schemars::Schema {
+    schemars::json_schema!({
+      "oneOf": [
+        generator.subschema_for::<bool>(),
+        generator.subschema_for::<Vec<String>>(),
+      ]
+    })
+  }
+}
+
+impl Serialize for PackageJsonBundleDependencies {
+  fn serialize<S: serde::Serializer>(
+    &self,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    match self {
+      Self::All => serializer.serialize_bool(true),
+      Self::Names(names) => names.serialize(serializer),
+    }
+  }
+}
+
+/// The "browserslist" field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum PackageJsonBrowserslist {
+  /// Queries used for every environment. A single query string is
+  /// stored as one item.
+  Queries(Vec<String>),
+  /// Queries keyed by environment (ex. "production" or "development").
+  Envs(IndexMap<String, Vec<String>>),
+}
+
+impl PackageJsonBrowserslist {
+  /// Gets the queries for the environment, which browserslist takes from
+  /// `BROWSERSLIST_ENV` or `NODE_ENV` and defaults to "production".
+  ///
+  /// Environments without an entry fall back to the "defaults" key.
+  pub fn queries(&self, env: Option<&str>) -> Option<&[String]> {
+    match self {
+      Self::Queries(queries) => Some(queries),
+      Self::Envs(envs) => envs
+        .get(env.unwrap_or("production"))
+        .or_else(|| envs.get("defaults"))
+        .map(|queries| queries.as_slice()),
+    }
+  }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -119,27 +601,120 @@ pub enum NodeModuleKind {
   Cjs,
 }
 
+impl NodeModuleKind {
+  fn conditions(self) -> &'static [&'static str] {
+    match self {
+      NodeModuleKind::Esm => &["import"],
+      NodeModuleKind::Cjs => &["require"],
+    }
+  }
+}
+
 #[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct PackageJson {
-  pub exports: Option<Map<String, Value>>,
-  pub imports: Option<Map<String, Value>>,
+  // large fields are reference counted to keep the struct small and
+  // cloning cheap, so use `make_mut` on them to edit
+  pub exports: Option<PackageJsonValueMapRc>,
+  pub imports: Option<PackageJsonValueMapRc>,
   pub bin: Option<Value>,
-  main: Option<String>,   // use .main(...)
-  module: Option<String>, // use .main(...)
+  main: Option<String>,   // use .main(...) or .raw_main()
+  module: Option<String>, // use .main(...) or .raw_module()
   pub name: Option<String>,
   pub version: Option<String>,
+  pub private: Option<bool>,
   #[serde(skip)]
-  pub path: PathBuf,
+  pub path: PackageJsonPathRc,
   #[serde(rename = "type")]
   pub typ: String,
   pub types: Option<String>,
-  pub dependencies: Option<IndexMap<String, String>>,
-  pub dev_dependencies: Option<IndexMap<String, String>>,
-  pub scripts: Option<IndexMap<String, String>>,
-  pub workspaces: Option<Vec<String>>,
+  pub browser: Option<Value>,
+  pub dependencies: Option<PackageJsonStringMapRc>,
+  pub dev_dependencies: Option<PackageJsonStringMapRc>,
+  pub optional_dependencies: Option<PackageJsonStringMapRc>,
+  pub scripts: Option<PackageJsonStringMapRc>,
+  pub workspaces: Option<PackageJsonStringsRc>,
+  pub bundle_dependencies: Option<PackageJsonBundleDependencies>,
+  pub repository: Option<Value>,
+  pub bugs: Option<Value>,
+  pub author: Option<Value>,
+  #[serde(rename = "jsnext:main")]
+  pub jsnext_main: Option<String>,
+  pub esnext: Option<String>,
+  /// Default file served by unpkg for the bare package URL.
+  pub unpkg: Option<String>,
+  /// Default file served by jsDelivr for the bare package URL.
+  pub jsdelivr: Option<String>,
+  pub browserslist: Option<PackageJsonBrowserslist>,
+  /// The "types" value ignored in favor of a different "typings" value.
   #[serde(skip_serializing)]
+  #[cfg_attr(feature = "schemars", schemars(skip))]
+  shadowed_types: Option<String>,
+  /// Operating systems the package runs on (ex. `["linux", "!win32"]`).
+  pub os: Option<Vec<String>>,
+  /// CPU architectures the package runs on (ex. `["x64", "arm64"]`).
+  pub cpu: Option<Vec<String>>,
+  pub package_manager: Option<PackageJsonPackageManager>,
+  pub engines: Option<IndexMap<String, String>>,
+  pub peer_dependencies: Option<PackageJsonStringMapRc>,
+  pub peer_dependencies_meta: Option<Map<String, Value>>,
+  pub pnpm: Option<PackageJsonPnpmConfig>,
+  overrides: Option<Map<String, Value>>, // use .overrides()
+  /// Location of a manifest that isn't on the file system.
+  #[cfg(feature = "url")]
+  #[serde(skip)]
+  #[cfg_attr(feature = "schemars", schemars(skip))]
+  url: Option<Url>, // use .specifier()
+  #[serde(skip_serializing)]
+  #[cfg_attr(feature = "schemars", schemars(skip))]
   resolved_deps: PackageJsonDepsRcCell,
+  #[serde(skip_serializing)]
+  #[cfg_attr(feature = "schemars", schemars(skip))]
+  export_resolutions: resolve::ExportResolutionCache,
+  #[serde(skip_serializing)]
+  #[cfg_attr(feature = "schemars", schemars(skip))]
+  string_pool: Option<PackageJsonStringPoolRc>,
+  #[serde(skip_serializing)]
+  #[cfg_attr(feature = "schemars", schemars(skip))]
+  lenient_dep_schemes: bool,
+  #[serde(skip_serializing)]
+  #[cfg_attr(feature = "schemars", schemars(skip))]
+  is_empty_source: bool,
+  /// The "exports" were dropped because they mixed subpath and condition
+  /// keys.
+  #[serde(skip_serializing)]
+  #[cfg_attr(feature = "schemars", schemars(skip))]
+  has_mixed_exports_keys: bool,
+  #[serde(skip_serializing)]
+  #[cfg_attr(feature = "schemars", schemars(skip))]
+  unknown_fields: Vec<String>,
+  #[serde(skip_serializing)]
+  #[cfg_attr(feature = "schemars", schemars(skip))]
+  extensions: PackageJsonExtensions, // use .extension()
+  /// Bit flags of the indexes in `KNOWN_FIELDS` that were present.
+  #[serde(skip_serializing)]
+  #[cfg_attr(feature = "schemars", schemars(skip))]
+  present_fields: u64,
+}
+
+/// Deserializes the package.json the same way as `load_from_value`.
+///
+/// The location isn't part of the document, so `path` will be empty
+/// and should be assigned by the caller.
+impl<'de> Deserialize<'de> for PackageJson {
+  fn deserialize<D: serde::Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    Ok(PackageJson::load_from_value(PathBuf::new(), value))
+  }
+}
+
+impl Default for PackageJson {
+  fn default() -> Self {
+    PackageJson::empty(PathBuf::new())
+  }
 }
 
 impl PackageJson {
@@ -148,117 +723,416 @@ impl PackageJson {
     maybe_cache: Option<&dyn PackageJsonCache>,
     path: &Path,
   ) -> Result<PackageJsonRc, PackageJsonLoadError> {
-    if let Some(item) = maybe_cache.and_then(|c| c.get(path)) {
-      Ok(item)
-    } else {
-      match sys.fs_read_to_string_lossy(path) {
-        Ok(file_text) => {
-          let pkg_json =
-            PackageJson::load_from_string(path.to_path_buf(), &file_text)?;
-          let pkg_json = crate::sync::new_rc(pkg_json);
-          if let Some(cache) = maybe_cache {
-            cache.set(path.to_path_buf(), pkg_json.clone());
-          }
-          Ok(pkg_json)
-        }
-        Err(err) => Err(PackageJsonLoadError::Io {
+    Self::load_from_path_with_options(
+      sys,
+      maybe_cache,
+      path,
+      PackageJsonLoadOptions::default(),
+    )
+  }
+
+  #[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+      level = "debug",
+      name = "package_json::load_from_path",
+      skip_all,
+      fields(path = %path.display())
+    )
+  )]
+  pub fn load_from_path_with_options(
+    sys: &impl FsRead,
+    maybe_cache: Option<&dyn PackageJsonCache>,
+    path: &Path,
+    options: PackageJsonLoadOptions,
+  ) -> Result<PackageJsonRc, PackageJsonLoadError> {
+    Self::load_with_cache(maybe_cache, path, options, |path| {
+      let file_text = sys.fs_read_to_string_lossy(path).map_err(|err| {
+        PackageJsonLoadErrorKind::Io {
           path: path.to_path_buf(),
           source: err,
-        }),
+        }
+        .into_box()
+      })?;
+      Self::load_from_text(path, &file_text, options)
+    })
+  }
+
+  /// Gets the package.json from the cache or else loads it with the
+  /// provided function and caches it.
+  pub(crate) fn load_with_cache(
+    maybe_cache: Option<&dyn PackageJsonCache>,
+    path: &Path,
+    options: PackageJsonLoadOptions,
+    load: impl FnOnce(&Path) -> Result<PackageJson, PackageJsonLoadError>,
+  ) -> Result<PackageJsonRc, PackageJsonLoadError> {
+    let maybe_observer = options.maybe_observer;
+    let path = normalize_path(path.to_path_buf());
+    let path = path.as_path();
+    let cache_key = match path.to_str() {
+      Some(text) if options.case_insensitive_cache_keys => {
+        Cow::Owned(PathBuf::from(text.to_lowercase()))
+      }
+      _ => Cow::Borrowed(path),
+    };
+    let load = move || {
+      let mut pkg_json = load(path)?;
+      pkg_json.string_pool = options.maybe_string_pool.cloned();
+      pkg_json.lenient_dep_schemes = options.lenient_dep_schemes;
+      Ok(crate::sync::new_rc(pkg_json))
+    };
+    let Some(cache) = maybe_cache.filter(|_| options.is_cacheable()) else {
+      return load();
+    };
+    let mut is_miss = false;
+    let result = cache.get_or_try_insert_with(
+      &cache_key,
+      Box::new(|| {
+        is_miss = true;
+        if let Some(observer) = maybe_observer {
+          observer.on_cache_miss(path);
+        }
+        load()
+      }),
+    );
+    if !is_miss {
+      if let Some(observer) = maybe_observer {
+        observer.on_cache_hit(path);
       }
     }
+    result
   }
 
-  pub fn load_from_string(
-    path: PathBuf,
-    source: &str,
+  /// Parses the text of a package.json read from the file system.
+  pub(crate) fn load_from_text(
+    path: &Path,
+    file_text: &str,
+    options: PackageJsonLoadOptions,
   ) -> Result<PackageJson, PackageJsonLoadError> {
-    if source.trim().is_empty() {
-      return Ok(PackageJson {
-        path,
-        main: None,
-        name: None,
-        version: None,
-        module: None,
-        typ: "none".to_string(),
-        types: None,
-        exports: None,
-        imports: None,
-        bin: None,
-        dependencies: None,
-        dev_dependencies: None,
-        scripts: None,
-        workspaces: None,
-        resolved_deps: Default::default(),
-      });
+    let maybe_observer = options.maybe_observer;
+    let start = maybe_observer.map(|observer| {
+      observer.on_parse_start(path);
+      ParseTimer::start()
+    });
+    let result = PackageJson::load_from_string_inner(
+      path.to_path_buf(),
+      file_text,
+      options.maybe_limits,
+      options.fields,
+      options.maybe_field_parsers,
+    );
+    if let (Some(observer), Some(start)) = (maybe_observer, start) {
+      observer.on_parse_end(path, start.elapsed());
     }
+    result
+  }
 
-    let package_json: Value = serde_json::from_str(source).map_err(|err| {
-      PackageJsonLoadError::Deserialize {
-        path: path.clone(),
+  /// Loads the package.json in the provided directory, returning `None`
+  /// when the directory doesn't have one.
+  pub fn load_from_dir(
+    sys: &impl FsRead,
+    maybe_cache: Option<&dyn PackageJsonCache>,
+    dir: &Path,
+  ) -> Result<Option<PackageJsonRc>, PackageJsonLoadError> {
+    match Self::load_from_path(sys, maybe_cache, &dir.join("package.json")) {
+      Ok(pkg_json) => Ok(Some(pkg_json)),
+      Err(err)
+        if matches!(
+          err.as_kind(),
+          PackageJsonLoadErrorKind::Io { source, .. }
+            if source.kind() == std::io::ErrorKind::NotFound
+        ) =>
+      {
+        Ok(None)
+      }
+      Err(err) => Err(err),
+    }
+  }
+
+  /// Loads the package.json after resolving symlinks in its directory
+  /// path.
+  ///
+  /// The canonical path is used as the cache key and stored in `path`, so
+  /// a package reached through several symlinked directories (ex. a pnpm
+  /// node_modules layout) is only loaded once and has a single
+  /// `dir_path()`.
+  pub fn load_from_canonicalized_path(
+    sys: &(impl FsRead + FsCanonicalize),
+    maybe_cache: Option<&dyn PackageJsonCache>,
+    path: &Path,
+    options: PackageJsonLoadOptions,
+  ) -> Result<PackageJsonRc, PackageJsonLoadError> {
+    let path = canonicalize_package_json_path(sys, path).map_err(|err| {
+      PackageJsonLoadErrorKind::Io {
+        path: path.to_path_buf(),
         source: err,
       }
+      .into_box()
     })?;
-    Ok(Self::load_from_value(path, package_json))
+    Self::load_from_path_with_options(sys, maybe_cache, &path, options)
   }
 
-  pub fn load_from_value(
-    path: PathBuf,
-    package_json: serde_json::Value,
-  ) -> PackageJson {
-    fn parse_string_map(
-      value: serde_json::Value,
-    ) -> Option<IndexMap<String, String>> {
-      if let Value::Object(map) = value {
-        let mut result = IndexMap::with_capacity(map.len());
-        for (k, v) in map {
-          if let Some(v) = map_string(v) {
-            result.insert(k, v);
-          }
-        }
-        Some(result)
-      } else {
-        None
+  /// Loads the package.json, storing its `path` according to the policy.
+  ///
+  /// The stored path is also used as the cache key, which makes
+  /// `dir_path()` and `specifier()` predictable for callers that provide
+  /// relative paths.
+  pub fn load_from_path_with_policy(
+    sys: &(impl FsRead + FsCanonicalize + EnvCurrentDir),
+    maybe_cache: Option<&dyn PackageJsonCache>,
+    path: &Path,
+    policy: PackageJsonPathPolicy,
+    options: PackageJsonLoadOptions,
+  ) -> Result<PackageJsonRc, PackageJsonLoadError> {
+    let resolved_path = match policy {
+      PackageJsonPathPolicy::AsGiven => Ok(path.to_path_buf()),
+      PackageJsonPathPolicy::Absolute => sys
+        .env_current_dir()
+        .map(|cwd| deno_path_util::normalize_path(cwd.join(path))),
+      PackageJsonPathPolicy::Canonical => {
+        return Self::load_from_canonicalized_path(
+          sys,
+          maybe_cache,
+          path,
+          options,
+        );
       }
+    };
+    let path = resolved_path.map_err(|err| {
+      PackageJsonLoadErrorKind::Io {
+        path: path.to_path_buf(),
+        source: err,
+      }
+      .into_box()
+    })?;
+    Self::load_from_path_with_options(sys, maybe_cache, &path, options)
+  }
+
+  /// Gets a mutable reference to a shared package.json, cloning it first
+  /// when other references to it exist.
+  ///
+  /// The lazily resolved dependencies are cleared so they're recomputed
+  /// from any edits.
+  pub fn make_mut(package_json: &mut PackageJsonRc) -> &mut PackageJson {
+    let package_json = crate::sync::make_mut(package_json);
+    package_json.resolved_deps = Default::default();
+    package_json
+  }
+
+  /// Creates a package.json with no fields, which is the same as loading
+  /// an empty file.
+  pub fn empty(path: PathBuf) -> PackageJson {
+    PackageJson {
+      path: normalize_path(path).into(),
+      main: None,
+      name: None,
+      version: None,
+      private: None,
+      module: None,
+      typ: "none".to_string(),
+      types: None,
+      browser: None,
+      exports: None,
+      imports: None,
+      bin: None,
+      dependencies: None,
+      dev_dependencies: None,
+      scripts: None,
+      workspaces: None,
+      bundle_dependencies: None,
+      optional_dependencies: None,
+      repository: None,
+      bugs: None,
+      author: None,
+      jsnext_main: None,
+      esnext: None,
+      unpkg: None,
+      jsdelivr: None,
+      browserslist: None,
+      shadowed_types: None,
+      os: None,
+      cpu: None,
+      package_manager: None,
+      engines: None,
+      peer_dependencies: None,
+      peer_dependencies_meta: None,
+      pnpm: None,
+      overrides: None,
+      #[cfg(feature = "url")]
+      url: None,
+      resolved_deps: Default::default(),
+      export_resolutions: Default::default(),
+      string_pool: None,
+      lenient_dep_schemes: false,
+      is_empty_source: true,
+      has_mixed_exports_keys: false,
+      unknown_fields: Vec::new(),
+      extensions: Default::default(),
+      present_fields: 0,
     }
+  }
 
-    fn map_object(value: serde_json::Value) -> Option<Map<String, Value>> {
-      match value {
-        Value::Object(v) => Some(v),
-        _ => None,
-      }
+  /// Creates a package.json with the provided dependencies, which is
+  /// useful for materializing a package.json for interop.
+  ///
+  /// Dependencies are written in the form npm uses for the alias (ex.
+  /// `"alias": "npm:pkg@^1.0.0"` or `"pkg": "workspace:^"`).
+  pub fn from_deps<S: AsRef<str>>(
+    path: PathBuf,
+    deps: impl IntoIterator<Item = (S, impl Borrow<PackageJsonDepValue>)>,
+  ) -> PackageJson {
+    let dependencies = deps
+      .into_iter()
+      .map(|(alias, value)| {
+        let alias = alias.as_ref();
+        let specifier = value.borrow().to_specifier_string(alias);
+        (alias.to_string(), Value::String(specifier))
+      })
+      .collect::<Map<_, _>>();
+    Self::load_from_value(
+      path,
+      serde_json::json!({ "dependencies": dependencies }),
+    )
+  }
+
+  pub fn load_from_string(
+    path: PathBuf,
+    source: &str,
+  ) -> Result<PackageJson, PackageJsonLoadError> {
+    Self::load_from_string_inner(
+      path,
+      source,
+      None,
+      PackageJsonFieldSet::ALL,
+      None,
+    )
+  }
+
+  /// Loads the package.json from a reader, such as an entry of a tarball
+  /// or the output of a process.
+  ///
+  /// `path` is only used as the location of the package.json. Like the
+  /// file system loaders, invalid UTF-8 is replaced instead of erroring.
+  pub fn load_from_reader(
+    path: PathBuf,
+    mut reader: impl std::io::Read,
+  ) -> Result<PackageJson, PackageJsonLoadError> {
+    let mut bytes = Vec::new();
+    if let Err(err) = reader.read_to_end(&mut bytes) {
+      return Err(
+        PackageJsonLoadErrorKind::Io { path, source: err }.into_box(),
+      );
     }
+    Self::load_from_string(path, &String::from_utf8_lossy(&bytes))
+  }
 
-    fn map_string(value: serde_json::Value) -> Option<String> {
-      match value {
-        Value::String(v) => Some(v),
-        Value::Number(v) => Some(v.to_string()),
-        _ => None,
-      }
+  /// Loads the package.json from a string, erroring when it exceeds
+  /// the provided limits.
+  pub fn load_from_string_with_limits(
+    path: PathBuf,
+    source: &str,
+    limits: &PackageJsonLimits,
+  ) -> Result<PackageJson, PackageJsonLoadError> {
+    Self::load_from_string_inner(
+      path,
+      source,
+      Some(limits),
+      PackageJsonFieldSet::ALL,
+      None,
+    )
+  }
+
+  fn load_from_string_inner(
+    path: PathBuf,
+    source: &str,
+    maybe_limits: Option<&PackageJsonLimits>,
+    fields: PackageJsonFieldSet,
+    maybe_field_parsers: Option<&PackageJsonFieldParsers>,
+  ) -> Result<PackageJson, PackageJsonLoadError> {
+    if source.trim().is_empty() {
+      return Ok(PackageJson::empty(path));
     }
 
-    fn map_array(value: serde_json::Value) -> Option<Vec<Value>> {
-      match value {
-        Value::Array(v) => Some(v),
-        _ => None,
+    let mut package_json: Value = parse_json_value(source).map_err(|err| {
+      PackageJsonLoadErrorKind::Deserialize {
+        path: path.clone(),
+        source: err,
+      }
+      .into_box()
+    })?;
+    if let Some(limits) = maybe_limits {
+      if let Err(err) = limits.check(&package_json) {
+        return Err(
+          PackageJsonLoadErrorKind::LimitExceeded { path, source: err }
+            .into_box(),
+        );
       }
     }
-
-    fn parse_string_array(value: serde_json::Value) -> Option<Vec<String>> {
-      let value = map_array(value)?;
-      let mut result = Vec::with_capacity(value.len());
-      for v in value {
-        if let Some(v) = map_string(v) {
-          result.push(v);
-        }
+    // before the unselected fields are removed
+    let extensions = match (maybe_field_parsers, &package_json) {
+      (Some(parsers), Value::Object(map)) => parsers.parse(map),
+      _ => Default::default(),
+    };
+    if fields != PackageJsonFieldSet::ALL {
+      if let Value::Object(map) = &mut package_json {
+        map.retain(|key, _| {
+          PackageJsonFieldSet::for_key(key)
+            .is_some_and(|field| fields.contains(field))
+        });
       }
-      Some(result)
     }
+    let mut package_json = Self::load_from_value(path, package_json);
+    package_json.extensions = extensions;
+    Ok(package_json)
+  }
+
+  /// Loads a package.json located at a URL, such as one in the virtual
+  /// file system of a compiled binary.
+  ///
+  /// `specifier()` returns the URL, while `path` is the file path of
+  /// `file:` URLs or the path of the URL otherwise (ex.
+  /// `/pkg/package.json` for `https://deno.land/pkg/package.json`).
+  #[cfg(feature = "url")]
+  pub fn load_from_url_string(
+    url: Url,
+    source: &str,
+  ) -> Result<PackageJson, PackageJsonLoadError> {
+    let mut package_json = Self::load_from_string(url_to_path(&url), source)?;
+    package_json.url = Some(url);
+    Ok(package_json)
+  }
+
+  /// Loads a package.json located at a URL from a JSON value. See
+  /// `load_from_url_string`.
+  #[cfg(feature = "url")]
+  pub fn load_from_url_value(
+    url: Url,
+    value: serde_json::Value,
+  ) -> PackageJson {
+    let mut package_json = Self::load_from_value(url_to_path(&url), value);
+    package_json.url = Some(url);
+    package_json
+  }
 
+  pub fn load_from_value(
+    path: PathBuf,
+    package_json: serde_json::Value,
+  ) -> PackageJson {
     let mut package_json = match package_json {
       Value::Object(o) => o,
       _ => Default::default(),
     };
+    let mut present_fields = 0;
+    let mut unknown_fields = Vec::new();
+    for key in package_json.keys() {
+      match field_set::KNOWN_FIELDS
+        .iter()
+        .position(|field| field == key)
+      {
+        Some(index) => present_fields |= 1u64 << index,
+        None => unknown_fields.push(key.clone()),
+      }
+    }
     let imports_val = package_json.remove("imports");
     let main_val = package_json.remove("main");
     let module_val = package_json.remove("module");
@@ -266,15 +1140,12 @@ impl PackageJson {
     let version_val = package_json.remove("version");
     let type_val = package_json.remove("type");
     let bin = package_json.remove("bin");
-    let exports = package_json.remove("exports").and_then(|exports| {
-      Some(if is_conditional_exports_main_sugar(&exports) {
-        let mut map = Map::new();
-        map.insert(".".to_string(), exports.to_owned());
-        map
-      } else {
-        exports.as_object()?.to_owned()
-      })
-    });
+    let (exports, has_mixed_exports_keys) =
+      match package_json.remove("exports").map(parse_exports) {
+        Some(Ok(exports)) => (exports, false),
+        Some(Err(MixedExportsKeys)) => (None, true),
+        None => (None, false),
+      };
 
     let imports = imports_val.and_then(map_object);
     let main = main_val.and_then(map_string);
@@ -288,105 +1159,345 @@ impl PackageJson {
     let dev_dependencies = package_json
       .remove("devDependencies")
       .and_then(parse_string_map);
+    let optional_dependencies = package_json
+      .remove("optionalDependencies")
+      .and_then(parse_string_map);
 
     let scripts: Option<IndexMap<String, String>> =
       package_json.remove("scripts").and_then(parse_string_map);
 
-    // Ignore unknown types for forwards compatibility
-    let typ = if let Some(t) = type_val {
-      if let Some(t) = t.as_str() {
-        if t != "module" && t != "commonjs" {
-          "none".to_string()
-        } else {
-          t.to_string()
-        }
-      } else {
-        "none".to_string()
-      }
-    } else {
-      "none".to_string()
-    };
+    let typ = parse_type(type_val);
 
     // for typescript, it looks for "typings" first, then "types"
-    let types = package_json
-      .remove("typings")
-      .or_else(|| package_json.remove("types"))
-      .and_then(map_string);
+    let (types, shadowed_types) = match package_json.remove("typings") {
+      Some(typings) => {
+        let typings = map_string(typings);
+        let shadowed_types = package_json
+          .remove("types")
+          .and_then(map_string)
+          .filter(|types| Some(types) != typings.as_ref());
+        (typings, shadowed_types)
+      }
+      None => (package_json.remove("types").and_then(map_string), None),
+    };
     let workspaces = package_json
       .remove("workspaces")
       .and_then(parse_string_array);
+    // npm accepts both spellings
+    let bundle_dependencies = package_json
+      .remove("bundleDependencies")
+      .or_else(|| package_json.remove("bundledDependencies"))
+      .and_then(|value| match value {
+        Value::Bool(true) => Some(PackageJsonBundleDependencies::All),
+        value => {
+          parse_string_array(value).map(PackageJsonBundleDependencies::Names)
+        }
+      });
+
+    let private = package_json.remove("private").and_then(|v| v.as_bool());
+    let browser = package_json.remove("browser");
+    let repository = package_json.remove("repository");
+    let bugs = package_json.remove("bugs");
+    let author = package_json.remove("author");
+
+    let jsnext_main = package_json.remove("jsnext:main").and_then(map_string);
+    // "esnext" is either the entrypoint or an object with a "main" entry
+    let esnext = package_json.remove("esnext").and_then(|value| match value {
+      Value::Object(mut o) => o.remove("main").and_then(map_string),
+      value => map_string(value),
+    });
+
+    let unpkg = package_json.remove("unpkg").and_then(map_string);
+    let jsdelivr = package_json.remove("jsdelivr").and_then(map_string);
+
+    let browserslist = package_json
+      .remove("browserslist")
+      .and_then(parse_browserslist);
+
+    let os = package_json.remove("os").and_then(parse_platform_list);
+    let cpu = package_json.remove("cpu").and_then(parse_platform_list);
+
+    let package_manager = package_json
+      .remove("packageManager")
+      .and_then(map_string)
+      .and_then(|value| PackageJsonPackageManager::parse(&value));
+
+    let engines = package_json.remove("engines").and_then(parse_string_map);
+
+    let peer_dependencies = package_json
+      .remove("peerDependencies")
+      .and_then(parse_string_map);
+    let peer_dependencies_meta = package_json
+      .remove("peerDependenciesMeta")
+      .and_then(map_object);
+    let pnpm = package_json
+      .remove("pnpm")
+      .and_then(package_extensions::parse_pnpm_config);
+
+    let overrides = package_json.remove("overrides").and_then(map_object);
 
     PackageJson {
-      path,
+      path: normalize_path(path).into(),
       main,
       name,
       version,
+      private,
       module,
       typ,
       types,
-      exports,
-      imports,
+      browser,
+      exports: exports.map(PackageJsonValueMapRc::new),
+      imports: imports.map(PackageJsonValueMapRc::new),
       bin,
-      dependencies,
-      dev_dependencies,
-      scripts,
-      workspaces,
+      dependencies: dependencies.map(PackageJsonStringMapRc::new),
+      dev_dependencies: dev_dependencies.map(PackageJsonStringMapRc::new),
+      scripts: scripts.map(PackageJsonStringMapRc::new),
+      workspaces: workspaces.map(PackageJsonStringsRc::from),
+      bundle_dependencies,
+      optional_dependencies: optional_dependencies
+        .map(PackageJsonStringMapRc::new),
+      repository,
+      bugs,
+      author,
+      jsnext_main,
+      esnext,
+      unpkg,
+      jsdelivr,
+      browserslist,
+      shadowed_types,
+      os,
+      cpu,
+      package_manager,
+      engines,
+      peer_dependencies: peer_dependencies.map(PackageJsonStringMapRc::new),
+      peer_dependencies_meta,
+      pnpm,
+      overrides,
+      #[cfg(feature = "url")]
+      url: None,
       resolved_deps: Default::default(),
+      export_resolutions: Default::default(),
+      string_pool: None,
+      lenient_dep_schemes: false,
+      is_empty_source: false,
+      has_mixed_exports_keys,
+      unknown_fields,
+      extensions: Default::default(),
+      present_fields,
     }
   }
 
+  /// Gets if the package.json was created from an empty file or
+  /// synthesized with `PackageJson::empty()`, as opposed to a document
+  /// that was explicitly written (even if it's just `{}`).
+  pub fn is_empty_source(&self) -> bool {
+    self.is_empty_source
+  }
+
+  /// Gets the top level keys that were present in the source, but
+  /// aren't understood by this crate (ex. "description" or "keywords").
+  ///
+  /// These aren't tracked when only some fields are loaded.
+  pub fn unknown_fields(&self) -> &[String] {
+    &self.unknown_fields
+  }
+
+  /// Gets the understood top level keys that were present in the source
+  /// (ex. to tell an absent "dependencies" from an explicitly empty one).
+  ///
+  /// Keys are returned in a fixed order rather than the source order.
+  pub fn present_fields(&self) -> impl Iterator<Item = &'static str> + '_ {
+    field_set::KNOWN_FIELDS
+      .iter()
+      .enumerate()
+      .filter(|(index, _)| self.present_fields & (1 << index) != 0)
+      .map(|(_, field)| *field)
+  }
+
+  /// Gets if the understood top level key was present in the source.
+  pub fn has_field(&self, key: &str) -> bool {
+    self.present_fields().any(|field| field == key)
+  }
+
+  /// Gets if the provided dependency is bundled when packing.
+  pub fn is_bundled(&self, name: &str) -> bool {
+    match &self.bundle_dependencies {
+      Some(PackageJsonBundleDependencies::All) => self
+        .dependencies
+        .as_ref()
+        .is_some_and(|deps| deps.contains_key(name)),
+      Some(PackageJsonBundleDependencies::Names(names)) => {
+        names.iter().any(|n| n == name)
+      }
+      None => false,
+    }
+  }
+
+  /// Gets the "exports" in a canonical form, which is useful for
+  /// comparing the export surfaces of packages.
+  ///
+  /// Subpath keys are sorted by specificity, single element fallback
+  /// arrays are unwrapped, conditions that can never match (after
+  /// "default") are removed, and conditions objects with only a "default"
+  /// are replaced by its target.
+  pub fn normalized_exports(&self) -> Option<Map<String, Value>> {
+    self.exports.as_deref().map(exports::normalize_exports)
+  }
+
+  /// Gets if the subpath (ex. `./internal/foo`) is explicitly blocked by a
+  /// `null` target in the "exports" (ex. `"./internal/*": null`).
+  ///
+  /// This distinguishes subpaths that are encapsulated from ones that
+  /// are simply not exported.
+  pub fn is_export_blocked(&self, subpath: &str) -> bool {
+    self
+      .exports
+      .as_ref()
+      .and_then(|exports| exports::match_subpath(exports, subpath))
+      .is_some_and(|m| m.target.is_null())
+  }
+
+  /// The file URL of this package.json.
+  ///
+  /// Requires the `url` feature. Without it, use the `path` field
+  /// and `dir_path()` to identify the package.json's location.
+  ///
+  /// Panics when the path can't be converted to a URL (ex. it's relative).
+  /// Use `try_specifier()` to handle that case.
+  #[cfg(feature = "url")]
   pub fn specifier(&self) -> Url {
-    deno_path_util::url_from_file_path(&self.path).unwrap()
+    self.try_specifier().unwrap()
+  }
+
+  #[cfg(feature = "url")]
+  pub fn try_specifier(&self) -> Result<Url, PathToUrlError> {
+    if let Some(url) = &self.url {
+      return Ok(url.clone());
+    }
+    // the path field is public, so it may not have been normalized
+    #[cfg(windows)]
+    let path = &normalize_path(self.path.to_path_buf());
+    #[cfg(not(windows))]
+    let path = &self.path;
+    deno_path_util::url_from_file_path(path)
   }
 
+  /// The URL of the directory containing this package.json, which ends
+  /// with a slash.
+  #[cfg(feature = "url")]
+  pub fn dir_specifier(&self) -> Url {
+    self.try_dir_specifier().unwrap()
+  }
+
+  #[cfg(feature = "url")]
+  pub fn try_dir_specifier(&self) -> Result<Url, PathToUrlError> {
+    let specifier = self.try_specifier()?;
+    // a path without a parent (ex. "/") resolves to itself
+    Ok(specifier.join("./").unwrap_or(specifier))
+  }
+
+  /// The directory containing this package.json.
+  ///
+  /// Panics when the path has no parent (ex. it's a root path). Use
+  /// `try_dir_path()` to handle that case.
   pub fn dir_path(&self) -> &Path {
-    self.path.parent().unwrap()
+    self.try_dir_path().unwrap()
+  }
+
+  pub fn try_dir_path(&self) -> Result<&Path, PackageJsonDirPathError> {
+    self
+      .path
+      .parent()
+      .ok_or_else(|| PackageJsonDirPathError(self.path.to_path_buf()))
   }
 
   pub fn main(&self, referrer_kind: NodeModuleKind) -> Option<&str> {
-    let main = if referrer_kind == NodeModuleKind::Esm && self.typ == "module" {
-      self.module.as_ref().or(self.main.as_ref())
-    } else {
-      self.main.as_ref()
-    };
-    main.map(|m| m.trim()).filter(|m| !m.is_empty())
+    self.main_with_conditions(referrer_kind.conditions())
+  }
+
+  /// The "main" field as written in the package.json.
+  pub fn raw_main(&self) -> Option<&str> {
+    self.main.as_deref()
+  }
+
+  pub fn set_raw_main(&mut self, main: Option<String>) {
+    self.main = main;
+  }
+
+  /// The "module" field as written in the package.json.
+  pub fn raw_module(&self) -> Option<&str> {
+    self.module.as_deref()
+  }
+
+  pub fn set_raw_module(&mut self, module: Option<String>) {
+    self.module = module;
+  }
+
+  /// Gets the entrypoint for the provided resolution conditions.
+  ///
+  /// The "module" field is used for the "module" condition (the bundler
+  /// convention) or for the "import" condition in an ES module package,
+  /// otherwise the "main" field is used. See `main_with_options` for
+  /// using it for "import" regardless of the "type" field.
+  pub fn main_with_conditions(&self, conditions: &[&str]) -> Option<&str> {
+    select_main(
+      &self.typ,
+      self.main.as_deref(),
+      self.module.as_deref(),
+      conditions,
+      false,
+    )
   }
 
   /// Resolve the package.json's dependencies.
   pub fn resolve_local_package_json_deps(&self) -> &PackageJsonDepsRc {
-    /// Gets the name and raw version constraint for a registry info or
-    /// package.json dependency entry taking into account npm package aliases.
-    fn parse_dep_entry_name_and_raw_version<'a>(
-      key: &'a str,
-      value: &'a str,
-    ) -> (&'a str, &'a str) {
-      if let Some(package_and_version) = value.strip_prefix("npm:") {
-        if let Some((name, version)) = package_and_version.rsplit_once('@') {
-          // if empty, then the name was scoped and there's no version
-          if name.is_empty() {
-            (package_and_version, "*")
-          } else {
-            (name, version)
-          }
-        } else {
-          (package_and_version, "*")
-        }
-      } else {
-        (key, value)
+    fn parse_version_req(
+      text: &str,
+      pool: Option<&PackageJsonStringPool>,
+    ) -> Result<VersionReq, NpmVersionReqParseError> {
+      match pool {
+        Some(pool) => pool.parse_version_req(text),
+        None => VersionReq::parse_from_npm(text),
+      }
+    }
+
+    fn intern(text: &str, pool: Option<&PackageJsonStringPool>) -> StackString {
+      match pool {
+        Some(pool) => pool.intern(text),
+        None => StackString::from(text),
       }
     }
 
     fn parse_entry(
       key: &str,
       value: &str,
+      pool: Option<&PackageJsonStringPool>,
+      lenient_schemes: bool,
     ) -> Result<PackageJsonDepValue, PackageJsonDepValueParseError> {
+      // like npm, ignore surrounding whitespace (ex. " ^1.2 ")
+      let value = value.trim();
+      if lenient_schemes {
+        if let Some((scheme, _)) = value.split_once(':') {
+          let is_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+              .chars()
+              .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+          if is_scheme && scheme != "npm" && scheme != "workspace" {
+            return Ok(PackageJsonDepValue::Raw {
+              scheme: scheme.to_string(),
+              value: value.to_string(),
+            });
+          }
+        }
+      }
       if let Some(workspace_key) = value.strip_prefix("workspace:") {
         let workspace_req = match workspace_key {
           "~" => PackageJsonDepWorkspaceReq::Tilde,
           "^" => PackageJsonDepWorkspaceReq::Caret,
-          _ => PackageJsonDepWorkspaceReq::VersionReq(
-            VersionReq::parse_from_npm(workspace_key)?,
-          ),
+          _ => PackageJsonDepWorkspaceReq::VersionReq(parse_version_req(
+            workspace_key,
+            pool,
+          )?),
         };
         return Ok(PackageJsonDepValue::Workspace(workspace_req));
       }
@@ -404,10 +1515,10 @@ impl PackageJson {
       }
       let (name, version_req) =
         parse_dep_entry_name_and_raw_version(key, value);
-      let result = VersionReq::parse_from_npm(version_req);
+      let result = parse_version_req(version_req, pool);
       match result {
         Ok(version_req) => Ok(PackageJsonDepValue::Req(PackageReq {
-          name: name.into(),
+          name: intern(name, pool),
           version_req,
         })),
         Err(err) => {
@@ -416,35 +1527,272 @@ impl PackageJson {
       }
     }
 
-    fn get_map(deps: Option<&IndexMap<String, String>>) -> PackageJsonDepsMap {
+    fn get_map(
+      deps: Option<&IndexMap<String, String>>,
+      pool: Option<&PackageJsonStringPool>,
+      lenient_schemes: bool,
+    ) -> PackageJsonDepsMap {
       let Some(deps) = deps else {
         return Default::default();
       };
-      let mut result = IndexMap::with_capacity(deps.len());
+      let mut result = PackageJsonDepsMap::with_capacity(deps.len());
       for (key, value) in deps {
-        result
-          .entry(StackString::from(key.as_str()))
-          .or_insert_with(|| parse_entry(key, value));
+        result.insert(
+          intern(key, pool),
+          parse_entry(key, value, pool, lenient_schemes),
+        );
       }
       result
     }
 
     self.resolved_deps.get_or_init(|| {
+      let pool = self.string_pool.as_deref();
+      #[cfg(feature = "tracing")]
+      let _span = tracing::debug_span!(
+        "package_json::resolve_deps",
+        path = %self.path.display()
+      )
+      .entered();
+      let lenient = self.lenient_dep_schemes;
       PackageJsonDepsRc::new(PackageJsonDeps {
-        dependencies: get_map(self.dependencies.as_ref()),
-        dev_dependencies: get_map(self.dev_dependencies.as_ref()),
+        dependencies: get_map(self.dependencies.as_deref(), pool, lenient),
+        dev_dependencies: get_map(
+          self.dev_dependencies.as_deref(),
+          pool,
+          lenient,
+        ),
       })
     })
   }
 }
 
-fn is_conditional_exports_main_sugar(exports: &Value) -> bool {
+/// Gets the name and raw version constraint for a registry info or
+/// package.json dependency entry taking into account npm package aliases.
+///
+/// Like npm, an empty version constraint matches any version.
+fn parse_dep_entry_name_and_raw_version<'a>(
+  key: &'a str,
+  value: &'a str,
+) -> (&'a str, &'a str) {
+  let (name, version) = parse_dep_entry_name_and_raw_version_inner(key, value);
+  if version.trim().is_empty() {
+    (name, "*")
+  } else {
+    (name, version)
+  }
+}
+
+fn parse_dep_entry_name_and_raw_version_inner<'a>(
+  key: &'a str,
+  value: &'a str,
+) -> (&'a str, &'a str) {
+  if let Some(package_and_version) = value.strip_prefix("npm:") {
+    if let Some((name, version)) = package_and_version.rsplit_once('@') {
+      // if empty, then the name was scoped and there's no version
+      if name.is_empty() {
+        (package_and_version, "*")
+      } else {
+        (name, version)
+      }
+    } else {
+      (package_and_version, "*")
+    }
+  } else {
+    (key, value)
+  }
+}
+
+/// Splits a package selector (ex. `@scope/name@^1`) into the name and the
+/// optional version range.
+fn split_package_selector(selector: &str) -> (&str, Option<&str>) {
+  // skip the "@" of scoped names
+  match selector.get(1..).and_then(|s| s.find('@')) {
+    Some(index) => (&selector[..index + 1], Some(&selector[index + 2..])),
+    None => (selector, None),
+  }
+}
+
+fn parse_string_map(
+  value: serde_json::Value,
+) -> Option<IndexMap<String, String>> {
+  if let Value::Object(map) = value {
+    let mut result = IndexMap::with_capacity(map.len());
+    for (k, v) in map {
+      if let Some(v) = map_string(v) {
+        result.insert(k, v);
+      }
+    }
+    Some(result)
+  } else {
+    None
+  }
+}
+
+fn map_object(value: serde_json::Value) -> Option<Map<String, Value>> {
+  match value {
+    Value::Object(v) => Some(v),
+    _ => None,
+  }
+}
+
+fn map_string(value: serde_json::Value) -> Option<String> {
+  match value {
+    Value::String(v) => Some(v),
+    Value::Number(v) => Some(v.to_string()),
+    _ => None,
+  }
+}
+
+fn map_array(value: serde_json::Value) -> Option<Vec<Value>> {
+  match value {
+    Value::Array(v) => Some(v),
+    _ => None,
+  }
+}
+
+fn parse_browserslist(value: Value) -> Option<PackageJsonBrowserslist> {
+  fn parse_queries(value: Value) -> Option<Vec<String>> {
+    match value {
+      Value::String(query) => Some(vec![query]),
+      value => parse_string_array(value),
+    }
+  }
+
+  match value {
+    Value::Object(envs) => Some(PackageJsonBrowserslist::Envs(
+      envs
+        .into_iter()
+        .filter_map(|(env, queries)| Some((env, parse_queries(queries)?)))
+        .collect(),
+    )),
+    value => parse_queries(value).map(PackageJsonBrowserslist::Queries),
+  }
+}
+
+/// npm accepts a single string in the platform fields.
+fn parse_platform_list(value: Value) -> Option<Vec<String>> {
+  match value {
+    Value::String(entry) => Some(vec![entry]),
+    value => parse_string_array(value),
+  }
+}
+
+fn parse_string_array(value: serde_json::Value) -> Option<Vec<String>> {
+  let value = map_array(value)?;
+  let mut result = Vec::with_capacity(value.len());
+  for v in value {
+    if let Some(v) = map_string(v) {
+      result.push(v);
+    }
+  }
+  Some(result)
+}
+
+#[cfg(not(feature = "simd-json"))]
+fn parse_json_value(source: &str) -> Result<Value, serde_json::Error> {
+  serde_json::from_str(source)
+}
+
+#[cfg(feature = "simd-json")]
+fn parse_json_value(source: &str) -> Result<Value, serde_json::Error> {
+  // simd-json parses in place, so it needs its own mutable copy
+  let mut bytes = source.as_bytes().to_vec();
+  simd_json::serde::from_slice(&mut bytes).map_err(serde::de::Error::custom)
+}
+
+/// The "exports" object mixes subpath keys (ex. `"./a"`) with condition
+/// keys (ex. `"import"`), which Node rejects.
+struct MixedExportsKeys;
+
+fn parse_exports(
+  exports: Value,
+) -> Result<Option<Map<String, Value>>, MixedExportsKeys> {
+  if is_conditional_exports_main_sugar(&exports)? {
+    let mut map = Map::new();
+    map.insert(".".to_string(), exports);
+    Ok(Some(map))
+  } else {
+    Ok(map_object(exports))
+  }
+}
+
+fn parse_type(type_val: Option<Value>) -> String {
+  // Ignore unknown types for forwards compatibility
+  if let Some(t) = type_val {
+    if let Some(t) = t.as_str() {
+      if t != "module" && t != "commonjs" {
+        "none".to_string()
+      } else {
+        t.to_string()
+      }
+    } else {
+      "none".to_string()
+    }
+  } else {
+    "none".to_string()
+  }
+}
+
+fn select_main<'a>(
+  typ: &str,
+  main: Option<&'a str>,
+  module: Option<&'a str>,
+  conditions: &[&str],
+  module_for_import: bool,
+) -> Option<&'a str> {
+  let use_module = conditions.contains(&"module")
+    || ((module_for_import || typ == "module")
+      && conditions.contains(&"import"));
+  let main = if use_module { module.or(main) } else { main };
+  main.map(|m| m.trim()).filter(|m| !m.is_empty())
+}
+
+/// Gets the path stored for a package.json at a URL.
+#[cfg(feature = "url")]
+fn url_to_path(url: &Url) -> PathBuf {
+  if url.scheme() == "file" {
+    if let Ok(path) = deno_path_util::url_to_file_path(url) {
+      return path;
+    }
+  }
+  let path = exports::decode_percent(url.path());
+  if path.is_empty() {
+    PathBuf::from("/")
+  } else {
+    PathBuf::from(path.as_ref())
+  }
+}
+
+/// Resolves symlinks in the directory of the package.json, which keeps
+/// the path valid when the file itself doesn't exist.
+fn canonicalize_package_json_path(
+  sys: &impl FsCanonicalize,
+  path: &Path,
+) -> std::io::Result<PathBuf> {
+  match (path.parent(), path.file_name()) {
+    (Some(dir), Some(file_name)) => {
+      sys.fs_canonicalize(dir).map(|dir| dir.join(file_name))
+    }
+    _ => sys.fs_canonicalize(path),
+  }
+}
+
+/// Strips verbatim (`\\?\C:\...`) and verbatim UNC
+/// (`\\?\UNC\server\share\...`) prefixes on Windows so that cache keys
+/// and file URLs don't depend on how the caller obtained the path.
+fn normalize_path(path: PathBuf) -> PathBuf {
+  deno_path_util::strip_unc_prefix(path)
+}
+
+fn is_conditional_exports_main_sugar(
+  exports: &Value,
+) -> Result<bool, MixedExportsKeys> {
   if exports.is_string() || exports.is_array() {
-    return true;
+    return Ok(true);
   }
 
   if exports.is_null() || !exports.is_object() {
-    return false;
+    return Ok(false);
   }
 
   let exports_obj = exports.as_object().unwrap();
@@ -456,13 +1804,11 @@ fn is_conditional_exports_main_sugar(exports: &Value) -> bool {
       is_conditional_sugar = cur_is_conditional_sugar;
       i += 1;
     } else if is_conditional_sugar != cur_is_conditional_sugar {
-      panic!("\"exports\" cannot contains some keys starting with \'.\' and some not.
-        The exports object must either be an object of package subpath keys
-        or an object of main entry condition name keys only.")
+      return Err(MixedExportsKeys);
     }
   }
 
-  is_conditional_sugar
+  Ok(is_conditional_sugar)
 }
 
 #[cfg(test)]
@@ -471,16 +1817,477 @@ mod test {
   use pretty_assertions::assert_eq;
   use std::error::Error;
   use std::path::PathBuf;
+  use std::sync::Mutex;
+  use sys_traits::EnvSetCurrentDir;
+  use sys_traits::FsCreateDirAll;
+  use sys_traits::FsSymlinkDir;
+
+  #[test]
+  fn null_exports_should_not_crash() {
+    let package_json = PackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{ "exports": null }"#,
+    )
+    .unwrap();
+
+    assert!(package_json.exports.is_none());
+  }
+
+  #[test]
+  fn mixed_exports_keys_should_not_crash() {
+    let package_json = PackageJson::load_from_string_with_limits(
+      PathBuf::from("/package.json"),
+      r#"{ "exports": { ".": "./a.js", "import": "./b.js" } }"#,
+      &PackageJsonLimits::default(),
+    )
+    .unwrap();
+    assert!(package_json.exports.is_none());
+    assert_eq!(
+      package_json
+        .diagnostics()
+        .iter()
+        .map(|d| (d.pointer.as_str(), d.code()))
+        .collect::<Vec<_>>(),
+      vec![("/exports", "mixed-exports-keys")]
+    );
+  }
+
+  #[test]
+  fn exports_main_sugar() {
+    let package_json = PackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{ "exports": { "import": "./a.mjs", "require": "./a.cjs" } }"#,
+    )
+    .unwrap();
+    assert_eq!(
+      Value::Object(package_json.exports.unwrap().as_ref().clone()),
+      serde_json::json!({
+        ".": { "import": "./a.mjs", "require": "./a.cjs" },
+      })
+    );
+    let package_json = PackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{ "exports": "./a.js" }"#,
+    )
+    .unwrap();
+    assert_eq!(
+      Value::Object(package_json.exports.unwrap().as_ref().clone()),
+      serde_json::json!({ ".": "./a.js" })
+    );
+  }
+
+  #[test]
+  fn main_with_conditions() {
+    let package_json = PackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{ "main": "./main.js", "module": "./module.js" }"#,
+    )
+    .unwrap();
+    assert_eq!(package_json.main(NodeModuleKind::Esm), Some("./main.js"));
+    assert_eq!(
+      package_json.main_with_conditions(&["browser", "module", "import"]),
+      Some("./module.js")
+    );
+    assert_eq!(
+      package_json.main_with_conditions(&["worker", "require"]),
+      Some("./main.js")
+    );
+
+    let mut package_json = package_json;
+    package_json.set_raw_main(Some("  ".to_string()));
+    package_json.set_raw_module(None);
+    assert_eq!(package_json.raw_main(), Some("  "));
+    assert_eq!(package_json.raw_module(), None);
+    assert_eq!(package_json.main(NodeModuleKind::Cjs), None);
+  }
+
+  #[test]
+  fn clone_shares_fields() {
+    let package_json = PackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{ "exports": "./a.js", "dependencies": { "a": "1" } }"#,
+    )
+    .unwrap();
+    let cloned = package_json.clone();
+    #[allow(clippy::disallowed_types)]
+    let is_shared = crate::sync::MaybeArc::ptr_eq(
+      package_json.exports.as_ref().unwrap(),
+      cloned.exports.as_ref().unwrap(),
+    ) && crate::sync::MaybeArc::ptr_eq(
+      package_json.dependencies.as_ref().unwrap(),
+      cloned.dependencies.as_ref().unwrap(),
+    );
+    assert!(is_shared);
+  }
+
+  #[test]
+  fn workspace_req_display() {
+    for text in [
+      "workspace:~",
+      "workspace:^",
+      "workspace:*",
+      "workspace:^1.2",
+    ] {
+      let package_json = PackageJson::load_from_value(
+        PathBuf::from("/package.json"),
+        serde_json::json!({ "dependencies": { "a": text } }),
+      );
+      let deps = package_json.resolve_local_package_json_deps();
+      let Ok(PackageJsonDepValue::Workspace(req)) =
+        deps.dependencies.get("a").unwrap()
+      else {
+        unreachable!();
+      };
+      assert_eq!(req.to_string(), text);
+    }
+  }
+
+  #[test]
+  fn dep_value_round_trips() {
+    let mut package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "dependencies": {
+          "a": "^1.2.3",
+          "alias": "npm:@scope/b@~2",
+          "c": "workspace:~",
+          "d": "workspace:^",
+          "e": "workspace:1.x",
+          "f": "latest",
+        }
+      }),
+    );
+    let deps = package_json.resolve_local_package_json_deps().clone();
+    let written = deps
+      .dependencies
+      .iter()
+      .map(|(alias, value)| {
+        (
+          alias.to_string(),
+          value.as_ref().unwrap().to_specifier_string(alias),
+        )
+      })
+      .collect::<IndexMap<_, _>>();
+    assert_eq!(
+      written,
+      package_json.dependencies.as_deref().unwrap().clone()
+    );
+    assert_eq!(
+      deps
+        .dependencies
+        .get("a")
+        .unwrap()
+        .as_ref()
+        .unwrap()
+        .to_string(),
+      "npm:a@^1.2.3"
+    );
+
+    // reparsing the written values produces the same dependencies
+    package_json.dependencies = Some(written.into());
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::to_value(&package_json).unwrap(),
+    );
+    assert_eq!(
+      package_json.resolve_local_package_json_deps().dependencies,
+      deps.dependencies
+    );
+  }
+
+  #[test]
+  fn unknown_and_present_fields() {
+    let package_json = PackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{
+        "name": "pkg",
+        "description": "A package",
+        "typings": "./a.d.ts",
+        "types": "./b.d.ts",
+        "keywords": ["deno"]
+      }"#,
+    )
+    .unwrap();
+    assert_eq!(package_json.types.as_deref(), Some("./a.d.ts"));
+    assert_eq!(package_json.unknown_fields(), ["description", "keywords"]);
+    assert_eq!(
+      package_json.present_fields().collect::<Vec<_>>(),
+      vec!["name", "types", "typings"]
+    );
+    assert!(package_json.has_field("typings"));
+    assert!(!package_json.has_field("dependencies"));
+    assert!(!package_json.has_field("description"));
+  }
+
+  #[test]
+  fn browserslist() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({ "browserslist": "> 1%, not dead" }),
+    );
+    let browserslist = package_json.browserslist.unwrap();
+    assert_eq!(browserslist.queries(None).unwrap(), ["> 1%, not dead"]);
+
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "browserslist": {
+          "production": "> 1%",
+          "defaults": ["defaults", "not ie 11"],
+        }
+      }),
+    );
+    let browserslist = package_json.browserslist.unwrap();
+    assert_eq!(browserslist.queries(None).unwrap(), ["> 1%"]);
+    assert_eq!(
+      browserslist.queries(Some("development")).unwrap(),
+      ["defaults", "not ie 11"]
+    );
+  }
+
+  #[test]
+  fn malformed_package_json() {
+    let err = PackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{ "name": "#,
+    )
+    .unwrap_err();
+    assert!(matches!(*err, PackageJsonLoadErrorKind::Deserialize { .. }));
+  }
+
+  #[test]
+  fn exceeds_limits() {
+    let err = PackageJson::load_from_string_with_limits(
+      PathBuf::from("/package.json"),
+      r#"{ "exports": { ".": { "node": { "import": "./a.js" } } } }"#,
+      &PackageJsonLimits {
+        max_depth: 3,
+        ..Default::default()
+      },
+    )
+    .unwrap_err();
+    assert!(matches!(
+      *err,
+      PackageJsonLoadErrorKind::LimitExceeded { .. }
+    ));
+  }
+
+  #[test]
+  fn bundle_dependencies() {
+    let package_json = PackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{
+        "dependencies": { "a": "1", "b": "1" },
+        "devDependencies": { "c": "1" },
+        "bundleDependencies": true
+      }"#,
+    )
+    .unwrap();
+    assert_eq!(
+      package_json.bundle_dependencies,
+      Some(PackageJsonBundleDependencies::All)
+    );
+    assert!(package_json.is_bundled("a"));
+    assert!(package_json.is_bundled("b"));
+    assert!(!package_json.is_bundled("c"));
+    assert_eq!(
+      serde_json::to_value(&package_json).unwrap()["bundleDependencies"],
+      Value::Bool(true)
+    );
+
+    let package_json = PackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{
+        "dependencies": { "a": "1", "b": "1" },
+        "bundledDependencies": ["b"]
+      }"#,
+    )
+    .unwrap();
+    assert!(!package_json.is_bundled("a"));
+    assert!(package_json.is_bundled("b"));
+
+    let package_json = PackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{ "dependencies": { "a": "1" }, "bundleDependencies": false }"#,
+    )
+    .unwrap();
+    assert_eq!(package_json.bundle_dependencies, None);
+    assert!(!package_json.is_bundled("a"));
+  }
+
+  #[test]
+  fn export_blocked() {
+    let package_json = PackageJson::load_from_string(
+      PathBuf::from("/package.json"),
+      r#"{
+        "exports": {
+          ".": "./index.js",
+          "./internal/*": null,
+          "./internal/public.js": "./internal/public.js",
+          "./*": "./*.js"
+        }
+      }"#,
+    )
+    .unwrap();
+    assert!(package_json.is_export_blocked("./internal/foo.js"));
+    assert!(!package_json.is_export_blocked("./internal/public.js"));
+    assert!(!package_json.is_export_blocked("./other"));
+    assert!(!package_json.is_export_blocked("."));
+  }
+
+  #[test]
+  fn empty() {
+    let package_json = PackageJson::empty(PathBuf::from("/package.json"));
+    assert_eq!(&*package_json.path, Path::new("/package.json"));
+    assert_eq!(package_json.typ, "none");
+    assert!(package_json.name.is_none());
+    assert_eq!(
+      serde_json::to_value(&package_json).unwrap(),
+      serde_json::to_value(
+        PackageJson::load_from_string(PathBuf::from("/package.json"), "")
+          .unwrap()
+      )
+      .unwrap()
+    );
+    assert_eq!(&*PackageJson::default().path, Path::new(""));
+    assert!(package_json.is_empty_source());
+    let package_json =
+      PackageJson::load_from_string(PathBuf::from("/package.json"), " \n")
+        .unwrap();
+    assert!(package_json.is_empty_source());
+    let package_json =
+      PackageJson::load_from_string(PathBuf::from("/package.json"), "{}")
+        .unwrap();
+    assert!(!package_json.is_empty_source());
+  }
+
+  #[test]
+  fn test_try_dir_path() {
+    let package_json =
+      PackageJson::load_from_string(PathBuf::from("/dir/package.json"), "{}")
+        .unwrap();
+    assert_eq!(package_json.try_dir_path().unwrap(), Path::new("/dir"));
+    let package_json =
+      PackageJson::load_from_string(PathBuf::from("/"), "{}").unwrap();
+    assert!(package_json.try_dir_path().is_err());
+  }
+
+  #[cfg(feature = "url")]
+  #[test]
+  fn test_try_specifier_relative_path() {
+    let package_json =
+      PackageJson::load_from_string(PathBuf::from("package.json"), "{}")
+        .unwrap();
+    assert!(package_json.try_specifier().is_err());
+  }
+
+  #[test]
+  fn test_load_error_size() {
+    assert_eq!(
+      std::mem::size_of::<Result<PackageJsonRc, PackageJsonLoadError>>(),
+      2 * std::mem::size_of::<usize>()
+    );
+  }
+
+  #[test]
+  fn test_load_from_reader() {
+    let package_json = PackageJson::load_from_reader(
+      PathBuf::from("/package.json"),
+      br#"{ "name": "pkg" }"#.as_slice(),
+    )
+    .unwrap();
+    assert_eq!(package_json.name.as_deref(), Some("pkg"));
+
+    struct FailingReader;
+
+    impl std::io::Read for FailingReader {
+      fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::other("broken pipe"))
+      }
+    }
+
+    let err = PackageJson::load_from_reader(
+      PathBuf::from("/package.json"),
+      FailingReader,
+    )
+    .unwrap_err();
+    assert!(matches!(*err, PackageJsonLoadErrorKind::Io { .. }));
+  }
+
+  #[cfg(feature = "url")]
+  #[test]
+  fn test_load_from_url() {
+    let url = Url::parse("https://deno.land/x/my%20pkg/package.json").unwrap();
+    let package_json =
+      PackageJson::load_from_url_string(url.clone(), r#"{ "name": "pkg" }"#)
+        .unwrap();
+    assert_eq!(package_json.name.as_deref(), Some("pkg"));
+    assert_eq!(package_json.specifier(), url);
+    assert_eq!(
+      package_json.dir_specifier().as_str(),
+      "https://deno.land/x/my%20pkg/"
+    );
+    assert_eq!(&*package_json.path, Path::new("/x/my pkg/package.json"));
+    assert_eq!(package_json.dir_path(), Path::new("/x/my pkg"));
+
+    let url = Url::parse("file:///pkg/package.json").unwrap();
+    let package_json =
+      PackageJson::load_from_url_value(url.clone(), serde_json::json!({}));
+    assert_eq!(package_json.specifier(), url);
+    #[cfg(not(windows))]
+    assert_eq!(&*package_json.path, Path::new("/pkg/package.json"));
+  }
 
+  #[cfg(windows)]
   #[test]
-  fn null_exports_should_not_crash() {
+  fn test_normalizes_verbatim_paths() {
     let package_json = PackageJson::load_from_string(
-      PathBuf::from("/package.json"),
-      r#"{ "exports": null }"#,
+      PathBuf::from(r"\\?\C:\dir\package.json"),
+      "{}",
+    )
+    .unwrap();
+    assert_eq!(&*package_json.path, Path::new(r"C:\dir\package.json"));
+    assert_eq!(
+      package_json.specifier().as_str(),
+      "file:///C:/dir/package.json"
+    );
+    let package_json = PackageJson::load_from_string(
+      PathBuf::from(r"\\?\UNC\server\share\package.json"),
+      "{}",
     )
     .unwrap();
+    assert_eq!(
+      &*package_json.path,
+      Path::new(r"\\server\share\package.json")
+    );
+  }
 
-    assert!(package_json.exports.is_none());
+  #[test]
+  fn test_make_mut() {
+    let original = crate::sync::new_rc(
+      PackageJson::load_from_string(
+        PathBuf::from("/package.json"),
+        r#"{ "dependencies": { "a": "1" } }"#,
+      )
+      .unwrap(),
+    );
+    assert!(original
+      .resolve_local_package_json_deps()
+      .get("a")
+      .is_some());
+    let mut shared = original.clone();
+    let package_json = PackageJson::make_mut(&mut shared);
+    package_json.dependencies =
+      Some(IndexMap::from([("b".to_string(), "2".to_string())]).into());
+    let deps = shared.resolve_local_package_json_deps();
+    assert!(deps.get("a").is_none());
+    assert!(deps.get("b").is_some());
+    // the original is untouched
+    assert_eq!(original.dependencies.as_ref().unwrap().len(), 1);
+    assert!(original
+      .resolve_local_package_json_deps()
+      .get("a")
+      .is_some());
   }
 
   fn get_local_package_json_version_reqs_for_tests(
@@ -512,14 +2319,20 @@ mod test {
     let mut package_json =
       PackageJson::load_from_string(PathBuf::from("/package.json"), "{}")
         .unwrap();
-    package_json.dependencies = Some(IndexMap::from([
-      ("test".to_string(), "^1.2".to_string()),
-      ("other".to_string(), "npm:package@~1.3".to_string()),
-    ]));
-    package_json.dev_dependencies = Some(IndexMap::from([
-      ("package_b".to_string(), "~2.2".to_string()),
-      ("other".to_string(), "^3.2".to_string()),
-    ]));
+    package_json.dependencies = Some(
+      IndexMap::from([
+        ("test".to_string(), "^1.2".to_string()),
+        ("other".to_string(), "npm:package@~1.3".to_string()),
+      ])
+      .into(),
+    );
+    package_json.dev_dependencies = Some(
+      IndexMap::from([
+        ("package_b".to_string(), "~2.2".to_string()),
+        ("other".to_string(), "^3.2".to_string()),
+      ])
+      .into(),
+    );
     let deps = package_json.resolve_local_package_json_deps();
     assert_eq!(
       deps
@@ -568,10 +2381,9 @@ mod test {
     let mut package_json =
       PackageJson::load_from_string(PathBuf::from("/package.json"), "{}")
         .unwrap();
-    package_json.dependencies = Some(IndexMap::from([(
-      "test".to_string(),
-      "%*(#$%()".to_string(),
-    )]));
+    package_json.dependencies = Some(
+      IndexMap::from([("test".to_string(), "%*(#$%()".to_string())]).into(),
+    );
     let map = get_local_package_json_version_reqs_for_tests(&package_json);
     assert_eq!(map.len(), 1);
     let err = map.get("test").unwrap().as_ref().unwrap_err();
@@ -587,10 +2399,9 @@ mod test {
     let mut package_json =
       PackageJson::load_from_string(PathBuf::from("/package.json"), "{}")
         .unwrap();
-    package_json.dependencies = Some(IndexMap::from([(
-      "test".to_string(),
-      "1.x - 1.3".to_string(),
-    )]));
+    package_json.dependencies = Some(
+      IndexMap::from([("test".to_string(), "1.x - 1.3".to_string())]).into(),
+    );
     let map = get_local_package_json_version_reqs_for_tests(&package_json);
     assert_eq!(
       map,
@@ -605,24 +2416,239 @@ mod test {
   }
 
   #[test]
-  fn test_get_local_package_json_version_reqs_skips_certain_specifiers() {
+  fn test_get_local_package_json_version_reqs_alias_tag() {
+    let mut package_json =
+      PackageJson::load_from_string(PathBuf::from("/package.json"), "{}")
+        .unwrap();
+    package_json.dependencies = Some(
+      IndexMap::from([
+        ("alias".to_string(), "npm:other@beta".to_string()),
+        ("scoped".to_string(), "npm:@scope/other@next-2".to_string()),
+        ("range".to_string(), "npm:other@^1".to_string()),
+      ])
+      .into(),
+    );
+    let deps = package_json.resolve_local_package_json_deps();
+    let alias = deps.dependencies.get("alias").unwrap().as_ref().unwrap();
+    assert_eq!(alias.dist_tag(), Some("beta"));
+    assert_eq!(alias.to_string(), "npm:other@beta");
+    let PackageJsonDepValue::Req(req) = alias else {
+      unreachable!();
+    };
+    assert_eq!(req.name, "other");
+    let scoped = deps.dependencies.get("scoped").unwrap().as_ref().unwrap();
+    assert_eq!(scoped.dist_tag(), Some("next-2"));
+    let range = deps.dependencies.get("range").unwrap().as_ref().unwrap();
+    assert_eq!(range.dist_tag(), None);
+  }
+
+  #[test]
+  fn test_deps_errors_and_valid() {
+    let mut package_json =
+      PackageJson::load_from_string(PathBuf::from("/package.json"), "{}")
+        .unwrap();
+    package_json.dependencies = Some(
+      IndexMap::from([
+        ("a".to_string(), "^1".to_string()),
+        ("b".to_string(), "git:something".to_string()),
+      ])
+      .into(),
+    );
+    package_json.dev_dependencies = Some(
+      IndexMap::from([
+        ("c".to_string(), "https://something".to_string()),
+        ("d".to_string(), "workspace:~".to_string()),
+      ])
+      .into(),
+    );
+    let deps = package_json.resolve_local_package_json_deps();
+    assert_eq!(
+      deps
+        .errors()
+        .map(|(kind, alias, err)| (kind, alias.as_str(), err.to_string()))
+        .collect::<Vec<_>>(),
+      vec![
+        (
+          PackageJsonDepKind::Normal,
+          "b",
+          "Not implemented scheme 'git'".to_string()
+        ),
+        (
+          PackageJsonDepKind::Dev,
+          "c",
+          "Not implemented scheme 'https'".to_string()
+        ),
+      ]
+    );
+    assert_eq!(
+      deps
+        .valid()
+        .map(|(kind, alias, value)| (kind, alias.as_str(), value.to_string()))
+        .collect::<Vec<_>>(),
+      vec![
+        (PackageJsonDepKind::Normal, "a", "npm:a@^1".to_string()),
+        (PackageJsonDepKind::Dev, "d", "workspace:~".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_deps_lookups() {
+    let mut package_json =
+      PackageJson::load_from_string(PathBuf::from("/package.json"), "{}")
+        .unwrap();
+    package_json.dependencies = Some(
+      IndexMap::from([
+        ("a".to_string(), "npm:other@^1".to_string()),
+        ("b".to_string(), "git:something".to_string()),
+      ])
+      .into(),
+    );
+    package_json.dev_dependencies = Some(
+      IndexMap::from([
+        ("a".to_string(), "^2".to_string()),
+        ("c".to_string(), "workspace:~".to_string()),
+      ])
+      .into(),
+    );
+    let deps = package_json.resolve_local_package_json_deps();
+    assert!(deps.has_dependency("b"));
+    assert!(!deps.has_dependency("d"));
+    assert_eq!(
+      deps.find_dependency("c"),
+      Some((
+        PackageJsonDepKind::Dev,
+        &PackageJsonDepValue::Workspace(PackageJsonDepWorkspaceReq::Tilde)
+      ))
+    );
+    assert_eq!(deps.find_dependency("b"), None);
+    assert_eq!(
+      deps.dependency_req("a"),
+      Some(&PackageReq::from_str("other@^1").unwrap())
+    );
+    assert_eq!(deps.dependency_req("c"), None);
+  }
+
+  #[test]
+  fn test_deps_package_reqs() {
     let mut package_json =
       PackageJson::load_from_string(PathBuf::from("/package.json"), "{}")
         .unwrap();
-    package_json.dependencies = Some(IndexMap::from([
-      ("test".to_string(), "1".to_string()),
+    package_json.dependencies = Some(
+      IndexMap::from([
+        ("a".to_string(), "^1".to_string()),
+        ("b".to_string(), "workspace:~".to_string()),
+        ("c".to_string(), "npm:a@^1".to_string()),
+      ])
+      .into(),
+    );
+    package_json.dev_dependencies =
+      Some(IndexMap::from([("d".to_string(), "^2".to_string())]).into());
+    let deps = package_json.resolve_local_package_json_deps();
+    assert_eq!(
+      deps.package_reqs(false),
+      vec![PackageReq::from_str("a@^1").unwrap()]
+    );
+    assert_eq!(
+      deps.package_reqs(true),
+      vec![
+        PackageReq::from_str("a@^1").unwrap(),
+        PackageReq::from_str("d@^2").unwrap()
+      ]
+    );
+  }
+
+  #[test]
+  fn test_from_deps() {
+    let deps = [
       (
-        "work-test-version-req".to_string(),
-        "workspace:1.1.1".to_string(),
+        "pkg",
+        PackageJsonDepValue::Req(PackageReq::from_str("pkg@^1.0.0").unwrap()),
       ),
-      ("work-test-star".to_string(), "workspace:*".to_string()),
-      ("work-test-tilde".to_string(), "workspace:~".to_string()),
-      ("work-test-caret".to_string(), "workspace:^".to_string()),
-      ("file-test".to_string(), "file:something".to_string()),
-      ("git-test".to_string(), "git:something".to_string()),
-      ("http-test".to_string(), "http://something".to_string()),
-      ("https-test".to_string(), "https://something".to_string()),
-    ]));
+      (
+        "alias",
+        PackageJsonDepValue::Req(PackageReq::from_str("other@1").unwrap()),
+      ),
+      (
+        "local",
+        PackageJsonDepValue::Workspace(PackageJsonDepWorkspaceReq::Caret),
+      ),
+    ];
+    let package_json =
+      PackageJson::from_deps(PathBuf::from("/package.json"), deps.clone());
+    assert_eq!(
+      serde_json::to_value(package_json.dependencies.as_deref()).unwrap(),
+      serde_json::json!({
+        "pkg": "^1.0.0",
+        "alias": "npm:other@1",
+        "local": "workspace:^",
+      })
+    );
+    assert!(!package_json.is_empty_source());
+    let resolved = package_json.resolve_local_package_json_deps();
+    assert_eq!(
+      resolved
+        .valid()
+        .map(|(_, _, value)| value)
+        .collect::<Vec<_>>(),
+      deps.iter().map(|(_, value)| value).collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn test_get_local_package_json_version_reqs_empty() {
+    let mut package_json =
+      PackageJson::load_from_string(PathBuf::from("/package.json"), "{}")
+        .unwrap();
+    package_json.dependencies = Some(
+      IndexMap::from([
+        ("test".to_string(), "".to_string()),
+        ("alias".to_string(), "npm:other@ ".to_string()),
+      ])
+      .into(),
+    );
+    let map = get_local_package_json_version_reqs_for_tests(&package_json);
+    assert_eq!(
+      map,
+      IndexMap::from([
+        (
+          "test".to_string(),
+          Ok(PackageJsonDepValue::Req(
+            PackageReq::from_str("test@*").unwrap()
+          ))
+        ),
+        (
+          "alias".to_string(),
+          Ok(PackageJsonDepValue::Req(
+            PackageReq::from_str("other@*").unwrap()
+          ))
+        ),
+      ])
+    );
+  }
+
+  #[test]
+  fn test_get_local_package_json_version_reqs_skips_certain_specifiers() {
+    let mut package_json =
+      PackageJson::load_from_string(PathBuf::from("/package.json"), "{}")
+        .unwrap();
+    package_json.dependencies = Some(
+      IndexMap::from([
+        ("test".to_string(), "1".to_string()),
+        (
+          "work-test-version-req".to_string(),
+          "workspace:1.1.1".to_string(),
+        ),
+        ("work-test-star".to_string(), "workspace:*".to_string()),
+        ("work-test-tilde".to_string(), "workspace:~".to_string()),
+        ("work-test-caret".to_string(), "workspace:^".to_string()),
+        ("file-test".to_string(), "file:something".to_string()),
+        ("git-test".to_string(), "git:something".to_string()),
+        ("http-test".to_string(), "http://something".to_string()),
+        ("https-test".to_string(), "https://something".to_string()),
+      ])
+      .into(),
+    );
     let result = get_local_package_json_version_reqs_for_tests(&package_json);
     assert_eq!(
       result,
@@ -689,16 +2715,500 @@ mod test {
     );
   }
 
+  #[derive(Debug, Default)]
+  struct TestCache(Mutex<IndexMap<PathBuf, PackageJsonRc>>);
+
+  impl PackageJsonCache for TestCache {
+    fn get(&self, path: &Path) -> Option<PackageJsonRc> {
+      self.0.lock().unwrap().get(path).cloned()
+    }
+
+    fn set(&self, path: PathBuf, package_json: PackageJsonRc) {
+      self.0.lock().unwrap().insert(path, package_json);
+    }
+  }
+
+  #[test]
+  fn test_load_from_path_observer() {
+    #[derive(Debug, Default)]
+    struct TestObserver(Mutex<Vec<String>>);
+
+    impl PackageJsonObserver for TestObserver {
+      fn on_cache_hit(&self, path: &Path) {
+        self
+          .0
+          .lock()
+          .unwrap()
+          .push(format!("hit {}", path.display()));
+      }
+
+      fn on_cache_miss(&self, path: &Path) {
+        self
+          .0
+          .lock()
+          .unwrap()
+          .push(format!("miss {}", path.display()));
+      }
+
+      fn on_parse_start(&self, path: &Path) {
+        self
+          .0
+          .lock()
+          .unwrap()
+          .push(format!("start {}", path.display()));
+      }
+
+      fn on_parse_end(&self, path: &Path, _duration: Duration) {
+        self
+          .0
+          .lock()
+          .unwrap()
+          .push(format!("end {}", path.display()));
+      }
+    }
+
+    let sys = sys_traits::impls::InMemorySys::default();
+    let path = PathBuf::from("/package.json");
+    sys.fs_insert(&path, r#"{ "name": "pkg" }"#);
+    let cache = TestCache::default();
+    let observer = TestObserver::default();
+    let options = PackageJsonLoadOptions {
+      maybe_observer: Some(&observer),
+      ..Default::default()
+    };
+    for _ in 0..2 {
+      let pkg_json = PackageJson::load_from_path_with_options(
+        &sys,
+        Some(&cache),
+        &path,
+        options,
+      )
+      .unwrap();
+      assert_eq!(pkg_json.name.as_deref(), Some("pkg"));
+    }
+    assert_eq!(
+      observer.0.into_inner().unwrap(),
+      vec![
+        "miss /package.json",
+        "start /package.json",
+        "end /package.json",
+        "hit /package.json",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_load_from_path_field_parsers_bypass_cache() {
+    let sys = sys_traits::impls::InMemorySys::default();
+    let path = PathBuf::from("/package.json");
+    sys.fs_insert(&path, r#"{ "name": "pkg", "tool": "config" }"#);
+    let cache = TestCache::default();
+    let mut parsers = PackageJsonFieldParsers::default();
+    parsers.register("tool", |value| value.as_str().map(String::from));
+    let load = |maybe_field_parsers| {
+      PackageJson::load_from_path_with_options(
+        &sys,
+        Some(&cache),
+        &path,
+        PackageJsonLoadOptions {
+          maybe_field_parsers,
+          ..Default::default()
+        },
+      )
+      .unwrap()
+    };
+    assert_eq!(load(None).extension::<String>("tool"), None);
+    assert_eq!(
+      load(Some(&parsers)).extension::<String>("tool"),
+      Some(&"config".to_string())
+    );
+    assert_eq!(cache.0.lock().unwrap().len(), 1);
+    assert_eq!(load(None).extension::<String>("tool"), None);
+  }
+
+  #[test]
+  fn test_load_from_path_cache_get_or_try_insert_with() {
+    /// Holds the lock while loading so each path is only parsed once.
+    #[derive(Debug, Default)]
+    struct LockingCache(Mutex<IndexMap<PathBuf, PackageJsonRc>>, Mutex<usize>);
+
+    impl PackageJsonCache for LockingCache {
+      fn get(&self, _path: &Path) -> Option<PackageJsonRc> {
+        unreachable!()
+      }
+
+      fn set(&self, _path: PathBuf, _package_json: PackageJsonRc) {
+        unreachable!()
+      }
+
+      fn get_or_try_insert_with(
+        &self,
+        path: &Path,
+        f: Box<
+          dyn FnOnce() -> Result<PackageJsonRc, PackageJsonLoadError> + '_,
+        >,
+      ) -> Result<PackageJsonRc, PackageJsonLoadError> {
+        let mut items = self.0.lock().unwrap();
+        if let Some(item) = items.get(path) {
+          return Ok(item.clone());
+        }
+        *self.1.lock().unwrap() += 1;
+        let item = f()?;
+        items.insert(path.to_path_buf(), item.clone());
+        Ok(item)
+      }
+    }
+
+    let sys = sys_traits::impls::InMemorySys::default();
+    sys.fs_insert("/package.json", r#"{ "name": "pkg" }"#);
+    let cache = LockingCache::default();
+    for _ in 0..2 {
+      let pkg_json = PackageJson::load_from_path(
+        &sys,
+        Some(&cache),
+        Path::new("/package.json"),
+      )
+      .unwrap();
+      assert_eq!(pkg_json.name.as_deref(), Some("pkg"));
+    }
+    assert!(PackageJson::load_from_path(
+      &sys,
+      Some(&cache),
+      Path::new("/missing")
+    )
+    .is_err());
+    assert_eq!(*cache.1.lock().unwrap(), 2);
+    assert_eq!(cache.0.lock().unwrap().len(), 1);
+  }
+
+  #[test]
+  fn test_load_from_path_string_pool() {
+    let sys = sys_traits::impls::InMemorySys::default();
+    let text =
+      r#"{ "dependencies": { "@scope/a-long-package-name": "^1.2.3" } }"#;
+    sys.fs_insert("/a/package.json", text);
+    sys.fs_insert("/b/package.json", text);
+    let pool = crate::sync::new_rc(PackageJsonStringPool::default());
+    let options = PackageJsonLoadOptions {
+      maybe_string_pool: Some(&pool),
+      ..Default::default()
+    };
+    let names = ["/a/package.json", "/b/package.json"].map(|path| {
+      let pkg_json = PackageJson::load_from_path_with_options(
+        &sys,
+        None,
+        Path::new(path),
+        options,
+      )
+      .unwrap();
+      let deps = pkg_json.resolve_local_package_json_deps();
+      let (alias, value) = deps.dependencies.get_index(0).unwrap();
+      let PackageJsonDepValue::Req(req) = value.as_ref().unwrap() else {
+        unreachable!();
+      };
+      assert_eq!(req.version_req.to_string(), "^1.2.3");
+      (alias.clone(), req.name.clone())
+    });
+    assert_eq!(names[0].0.as_ptr(), names[1].0.as_ptr());
+    assert_eq!(names[0].1.as_ptr(), names[1].1.as_ptr());
+  }
+
+  #[test]
+  fn test_load_case_insensitive_cache_keys() {
+    let sys = sys_traits::impls::InMemorySys::default();
+    sys.fs_insert("/Proj/package.json", r#"{ "name": "pkg" }"#);
+    let cache = TestCache::default();
+    let options = PackageJsonLoadOptions {
+      case_insensitive_cache_keys: true,
+      ..Default::default()
+    };
+    let pkg_json = PackageJson::load_from_path_with_options(
+      &sys,
+      Some(&cache),
+      Path::new("/Proj/package.json"),
+      options,
+    )
+    .unwrap();
+    assert_eq!(&*pkg_json.path, Path::new("/Proj/package.json"));
+    // only found through the cache
+    let cached = PackageJson::load_from_path_with_options(
+      &sys,
+      Some(&cache),
+      Path::new("/proj/package.json"),
+      options,
+    )
+    .unwrap();
+    assert_eq!(cached.name.as_deref(), Some("pkg"));
+    assert_eq!(
+      cache.0.lock().unwrap().keys().collect::<Vec<_>>(),
+      vec![Path::new("/proj/package.json")]
+    );
+  }
+
+  #[test]
+  fn test_load_lenient_dep_schemes() {
+    let sys = sys_traits::impls::InMemorySys::default();
+    sys.fs_insert(
+      "/package.json",
+      r#"{ "dependencies": {
+        "a": "git+ssh://git@github.com/user/a.git",
+        "b": "link:../b",
+        "c": "npm:other@1",
+        "d": "^1.0.0"
+      } }"#,
+    );
+    let pkg_json = PackageJson::load_from_path_with_options(
+      &sys,
+      None,
+      Path::new("/package.json"),
+      PackageJsonLoadOptions {
+        lenient_dep_schemes: true,
+        ..Default::default()
+      },
+    )
+    .unwrap();
+    let deps = pkg_json.resolve_local_package_json_deps();
+    assert_eq!(
+      deps
+        .dependencies
+        .values()
+        .map(|value| value.as_ref().unwrap().to_string())
+        .collect::<Vec<_>>(),
+      vec![
+        "git+ssh://git@github.com/user/a.git",
+        "link:../b",
+        "npm:other@1",
+        "npm:d@^1.0.0",
+      ]
+    );
+    assert_eq!(
+      deps.dependencies.get("b").unwrap(),
+      &Ok(PackageJsonDepValue::Raw {
+        scheme: "link".to_string(),
+        value: "link:../b".to_string(),
+      })
+    );
+  }
+
+  #[test]
+  fn test_load_fields() {
+    let sys = sys_traits::impls::InMemorySys::default();
+    let path = PathBuf::from("/package.json");
+    sys.fs_insert(
+      &path,
+      r#"{
+        "name": "pkg",
+        "type": "module",
+        "main": "./main.js",
+        "dependencies": { "a": "^1" },
+        "scripts": { "test": "deno test" }
+      }"#,
+    );
+    let pkg_json = PackageJson::load_from_path_with_options(
+      &sys,
+      None,
+      &path,
+      PackageJsonLoadOptions {
+        fields: PackageJsonFieldSet::NAME | PackageJsonFieldSet::MAIN,
+        ..Default::default()
+      },
+    )
+    .unwrap();
+    assert_eq!(pkg_json.name.as_deref(), Some("pkg"));
+    assert_eq!(pkg_json.raw_main(), Some("./main.js"));
+    assert_eq!(pkg_json.typ, "none");
+    assert!(pkg_json.dependencies.is_none());
+    assert!(pkg_json.scripts.is_none());
+  }
+
+  #[test]
+  fn test_load_from_path_field_set_bypasses_cache() {
+    let sys = sys_traits::impls::InMemorySys::default();
+    let path = PathBuf::from("/package.json");
+    sys.fs_insert(
+      &path,
+      r#"{ "type": "module", "dependencies": { "a": "^1" } }"#,
+    );
+    let cache = TestCache::default();
+    let load = |fields| {
+      PackageJson::load_from_path_with_options(
+        &sys,
+        Some(&cache),
+        &path,
+        PackageJsonLoadOptions {
+          fields,
+          ..Default::default()
+        },
+      )
+      .unwrap()
+    };
+    let pkg_json = load(PackageJsonFieldSet::TYPE);
+    assert_eq!(pkg_json.typ, "module");
+    assert!(pkg_json.dependencies.is_none());
+    assert!(cache.0.lock().unwrap().is_empty());
+    let pkg_json = load(PackageJsonFieldSet::ALL);
+    assert_eq!(pkg_json.dependencies.as_ref().unwrap().len(), 1);
+    assert_eq!(cache.0.lock().unwrap().len(), 1);
+  }
+
+  #[test]
+  fn test_load_from_path_limits_bypass_cache() {
+    let sys = sys_traits::impls::InMemorySys::default();
+    let path = PathBuf::from("/package.json");
+    sys.fs_insert(&path, r#"{ "name": "a-long-package-name" }"#);
+    let cache = TestCache::default();
+    let limits = PackageJsonLimits {
+      max_string_len: 4,
+      ..Default::default()
+    };
+    let load = |maybe_limits| {
+      PackageJson::load_from_path_with_options(
+        &sys,
+        Some(&cache),
+        &path,
+        PackageJsonLoadOptions {
+          maybe_limits,
+          ..Default::default()
+        },
+      )
+    };
+    assert!(load(None).is_ok());
+    assert_eq!(cache.0.lock().unwrap().len(), 1);
+    let err = load(Some(&limits)).unwrap_err();
+    assert!(matches!(
+      err.as_kind(),
+      PackageJsonLoadErrorKind::LimitExceeded { .. }
+    ));
+  }
+
+  #[test]
+  fn test_load_from_path_lenient_dep_schemes_bypass_cache() {
+    let sys = sys_traits::impls::InMemorySys::default();
+    let path = PathBuf::from("/package.json");
+    sys.fs_insert(&path, r#"{ "dependencies": { "b": "link:../b" } }"#);
+    let cache = TestCache::default();
+    let is_lenient = |lenient_dep_schemes| {
+      let pkg_json = PackageJson::load_from_path_with_options(
+        &sys,
+        Some(&cache),
+        &path,
+        PackageJsonLoadOptions {
+          lenient_dep_schemes,
+          ..Default::default()
+        },
+      )
+      .unwrap();
+      let deps = pkg_json.resolve_local_package_json_deps();
+      deps.dependencies.get("b").unwrap().is_ok()
+    };
+    assert!(!is_lenient(false));
+    assert!(is_lenient(true));
+    assert!(!is_lenient(false));
+    assert_eq!(cache.0.lock().unwrap().len(), 1);
+  }
+
+  #[test]
+  fn test_load_from_dir() {
+    let sys = sys_traits::impls::InMemorySys::default();
+    sys.fs_insert("/pkg/package.json", r#"{ "name": "pkg" }"#);
+    let pkg_json = PackageJson::load_from_dir(&sys, None, Path::new("/pkg"))
+      .unwrap()
+      .unwrap();
+    assert_eq!(&*pkg_json.path, Path::new("/pkg/package.json"));
+    assert!(PackageJson::load_from_dir(&sys, None, Path::new("/other"))
+      .unwrap()
+      .is_none());
+  }
+
+  #[test]
+  fn test_load_from_canonicalized_path() {
+    let sys = sys_traits::impls::InMemorySys::default();
+    sys.fs_insert("/store/pkg/package.json", r#"{ "name": "pkg" }"#);
+    sys.fs_create_dir_all("/a/node_modules").unwrap();
+    sys.fs_create_dir_all("/b/node_modules").unwrap();
+    sys
+      .fs_symlink_dir("/store/pkg", "/a/node_modules/pkg")
+      .unwrap();
+    sys
+      .fs_symlink_dir("/store/pkg", "/b/node_modules/pkg")
+      .unwrap();
+    let cache = TestCache::default();
+    let pkg_jsons = ["/a", "/b"].map(|dir| {
+      PackageJson::load_from_canonicalized_path(
+        &sys,
+        Some(&cache),
+        &Path::new(dir).join("node_modules/pkg/package.json"),
+        Default::default(),
+      )
+      .unwrap()
+    });
+    #[allow(clippy::disallowed_types)]
+    let is_same = crate::sync::MaybeArc::ptr_eq(&pkg_jsons[0], &pkg_jsons[1]);
+    assert!(is_same);
+    assert_eq!(pkg_jsons[0].dir_path(), Path::new("/store/pkg"));
+    assert_eq!(cache.0.lock().unwrap().len(), 1);
+  }
+
+  #[test]
+  fn test_load_from_path_with_policy() {
+    let sys = sys_traits::impls::InMemorySys::default();
+    sys.fs_insert("/store/pkg/package.json", r#"{ "name": "pkg" }"#);
+    sys.fs_create_dir_all("/proj/node_modules").unwrap();
+    sys
+      .fs_symlink_dir("/store/pkg", "/proj/node_modules/pkg")
+      .unwrap();
+    sys.env_set_current_dir("/proj").unwrap();
+    let load = |policy| {
+      PackageJson::load_from_path_with_policy(
+        &sys,
+        None,
+        Path::new("./node_modules/pkg/../pkg/package.json"),
+        policy,
+        Default::default(),
+      )
+      .unwrap()
+      .path
+      .to_path_buf()
+    };
+    assert_eq!(
+      load(PackageJsonPathPolicy::AsGiven),
+      PathBuf::from("./node_modules/pkg/../pkg/package.json")
+    );
+    assert_eq!(
+      load(PackageJsonPathPolicy::Absolute),
+      PathBuf::from("/proj/node_modules/pkg/package.json")
+    );
+    assert_eq!(
+      load(PackageJsonPathPolicy::Canonical),
+      PathBuf::from("/store/pkg/package.json")
+    );
+  }
+
+  #[cfg(feature = "schemars")]
+  #[test]
+  fn json_schema() {
+    let schema =
+      serde_json::to_value(schemars::schema_for!(PackageJson)).unwrap();
+    let properties = schema["properties"].as_object().unwrap();
+    assert!(properties.contains_key("devDependencies"));
+    assert!(properties.contains_key("bundleDependencies"));
+    assert!(!properties.contains_key("path"));
+    assert!(!properties.contains_key("resolvedDeps"));
+    assert!(!properties.contains_key("stringPool"));
+  }
+
   #[test]
   fn test_deserialize_serialize() {
     let json_value = serde_json::json!({
       "name": "test",
       "version": "1",
+      "private": true,
       "exports": {
         ".": "./main.js",
       },
       "bin": "./main.js",
       "types": "./types.d.ts",
+      "browser": { "./main.js": "./browser.js" },
       "imports": {
         "#test": "./main.js",
       },
@@ -714,7 +3224,37 @@ mod test {
       "scripts": {
         "test": "echo \"Error: no test specified\" && exit 1",
       },
-      "workspaces": ["asdf", "asdf2"]
+      "workspaces": ["asdf", "asdf2"],
+      "bundleDependencies": ["name"],
+      "optionalDependencies": {
+        "optional": "^2",
+      },
+      "repository": {
+        "type": "git",
+        "url": "git+https://github.com/denoland/deno.git",
+      },
+      "bugs": "https://github.com/denoland/deno/issues",
+      "author": "the Deno authors",
+      "jsnext:main": "./jsnext.js",
+      "esnext": "./esnext.js",
+      "unpkg": "./dist/index.umd.js",
+      "jsdelivr": "./dist/index.min.js",
+      "browserslist": {
+        "production": [">0.2%", "not dead"],
+        "development": ["last 1 chrome version"]
+      },
+      "os": ["linux", "darwin"],
+      "cpu": ["!ia32"],
+      "packageManager": "pnpm@9.1.0+sha512.abcdef",
+      "engines": { "node": ">=18" },
+      "peerDependencies": { "react": "^18" },
+      "peerDependenciesMeta": { "react": { "optional": true } },
+      "pnpm": {
+        "packageExtensions": {
+          "react-redux@1": { "peerDependencies": { "react-dom": "*" } }
+        }
+      },
+      "overrides": { "foo": "1.0.0", "bar": { ".": "$bar" } },
     });
     let package_json = PackageJson::load_from_value(
       PathBuf::from("/package.json"),
@@ -722,5 +3262,12 @@ mod test {
     );
     let serialized_value = serde_json::to_value(&package_json).unwrap();
     assert_eq!(serialized_value, json_value);
+
+    let mut deserialized: PackageJson =
+      serde_json::from_value(serialized_value).unwrap();
+    assert_eq!(&*deserialized.path, Path::new(""));
+    deserialized.path = Path::new("/package.json").into();
+    assert_eq!(deserialized.main(NodeModuleKind::Esm), Some("./module.js"));
+    assert_eq!(serde_json::to_value(&deserialized).unwrap(), json_value);
   }
 }
@@ -0,0 +1,175 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use deno_error::JsError;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::diagnostics::json_pointer;
+
+/// Limits applied to a package.json before it's loaded, which protects
+/// against pathological manifests from untrusted sources (ex. packages in
+/// a node_modules folder).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackageJsonLimits {
+  /// Maximum nesting depth of the "exports" and "imports" values, where
+  /// the field itself is depth 1.
+  pub max_depth: usize,
+  /// Maximum number of entries in any object or array.
+  pub max_entries: usize,
+  /// Maximum length in bytes of any string or object key.
+  pub max_string_len: usize,
+}
+
+impl Default for PackageJsonLimits {
+  fn default() -> Self {
+    Self {
+      max_depth: 32,
+      max_entries: 10_000,
+      max_string_len: 64 * 1024,
+    }
+  }
+}
+
+#[derive(Debug, Error, Clone, JsError, PartialEq, Eq)]
+#[class(generic)]
+pub enum PackageJsonLimitError {
+  #[error("'{field}' exceeds the maximum nesting depth of {max}.")]
+  DepthExceeded { field: &'static str, max: usize },
+  #[error("'{pointer}' has {count} entries, exceeding the maximum of {max}.")]
+  TooManyEntries {
+    pointer: String,
+    count: usize,
+    max: usize,
+  },
+  #[error(
+    "'{pointer}' has a string of length {len}, exceeding the maximum of {max}."
+  )]
+  StringTooLong {
+    pointer: String,
+    len: usize,
+    max: usize,
+  },
+}
+
+impl PackageJsonLimits {
+  /// Checks the provided package.json value against the limits.
+  pub fn check(&self, value: &Value) -> Result<(), PackageJsonLimitError> {
+    if let Value::Object(map) = value {
+      for field in ["exports", "imports"] {
+        if let Some(value) = map.get(field) {
+          if depth(value) > self.max_depth {
+            return Err(PackageJsonLimitError::DepthExceeded {
+              field,
+              max: self.max_depth,
+            });
+          }
+        }
+      }
+    }
+    self.check_sizes(value, &mut Vec::new())
+  }
+
+  fn check_sizes(
+    &self,
+    value: &Value,
+    path: &mut Vec<String>,
+  ) -> Result<(), PackageJsonLimitError> {
+    let check_len = |path: &[String], len: usize| {
+      if len > self.max_string_len {
+        Err(PackageJsonLimitError::StringTooLong {
+          pointer: json_pointer(path),
+          len,
+          max: self.max_string_len,
+        })
+      } else {
+        Ok(())
+      }
+    };
+    let check_count = |path: &[String], count: usize| {
+      if count > self.max_entries {
+        Err(PackageJsonLimitError::TooManyEntries {
+          pointer: json_pointer(path),
+          count,
+          max: self.max_entries,
+        })
+      } else {
+        Ok(())
+      }
+    };
+    match value {
+      Value::String(text) => check_len(path, text.len()),
+      Value::Array(items) => {
+        check_count(path, items.len())?;
+        for (index, item) in items.iter().enumerate() {
+          path.push(index.to_string());
+          self.check_sizes(item, path)?;
+          path.pop();
+        }
+        Ok(())
+      }
+      Value::Object(map) => {
+        check_count(path, map.len())?;
+        for (key, value) in map {
+          path.push(key.clone());
+          check_len(path, key.len())?;
+          self.check_sizes(value, path)?;
+          path.pop();
+        }
+        Ok(())
+      }
+      Value::Null | Value::Bool(_) | Value::Number(_) => Ok(()),
+    }
+  }
+}
+
+fn depth(value: &Value) -> usize {
+  1 + match value {
+    Value::Array(items) => items.iter().map(depth).max().unwrap_or(0),
+    Value::Object(map) => map.values().map(depth).max().unwrap_or(0),
+    _ => 0,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn checks_limits() {
+    let limits = PackageJsonLimits {
+      max_depth: 3,
+      max_entries: 2,
+      max_string_len: 7,
+    };
+    assert_eq!(
+      limits.check(&json!({ "exports": { ".": { "node": "./a" } } })),
+      Ok(())
+    );
+    assert_eq!(
+      limits.check(&json!({ "exports": { ".": { "node": ["./a"] } } })),
+      Err(PackageJsonLimitError::DepthExceeded {
+        field: "exports",
+        max: 3,
+      })
+    );
+    assert_eq!(
+      limits.check(&json!({ "bin": ["a", "b", "c"] })),
+      Err(PackageJsonLimitError::TooManyEntries {
+        pointer: "/bin".to_string(),
+        count: 3,
+        max: 2,
+      })
+    );
+    assert_eq!(
+      limits.check(&json!({ "imports": { "#a/b": "./longer" } })),
+      Err(PackageJsonLimitError::StringTooLong {
+        pointer: "/imports/#a~1b".to_string(),
+        len: 8,
+        max: 7,
+      })
+    );
+  }
+}
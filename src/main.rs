@@ -0,0 +1,77 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+//! Prints how a package.json is understood by this crate as JSON, which
+//! is useful for debugging resolution differences.
+//!
+//! Usage: `deno_package_json [path/to/package.json]`
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use deno_package_json::PackageJson;
+use deno_package_json::PackageJsonDepsMap;
+use deno_package_json::PackageJsonDiagnosticSeverity;
+use deno_package_json::PackageJsonPathPolicy;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use sys_traits::impls::RealSys;
+
+fn main() -> ExitCode {
+  let path = std::env::args_os()
+    .nth(1)
+    .map(PathBuf::from)
+    .unwrap_or_else(|| PathBuf::from("package.json"));
+  let package_json = match PackageJson::load_from_path_with_policy(
+    &RealSys,
+    None,
+    &path,
+    PackageJsonPathPolicy::Absolute,
+    Default::default(),
+  ) {
+    Ok(package_json) => package_json,
+    Err(err) => {
+      eprintln!("error: {err}");
+      return ExitCode::FAILURE;
+    }
+  };
+  let deps = package_json.resolve_local_package_json_deps();
+  let diagnostics = package_json
+    .diagnostics()
+    .into_iter()
+    .map(|diagnostic| {
+      json!({
+        "code": diagnostic.code(),
+        "severity": match diagnostic.severity() {
+          PackageJsonDiagnosticSeverity::Warning => "warning",
+          PackageJsonDiagnosticSeverity::Error => "error",
+        },
+        "pointer": diagnostic.pointer,
+        "message": diagnostic.message(),
+      })
+    })
+    .collect::<Vec<_>>();
+  let output = json!({
+    "path": package_json.path,
+    "packageJson": &*package_json,
+    "resolvedDependencies": {
+      "dependencies": deps_to_json(&deps.dependencies),
+      "devDependencies": deps_to_json(&deps.dev_dependencies),
+    },
+    "diagnostics": diagnostics,
+  });
+  println!("{}", serde_json::to_string_pretty(&output).unwrap());
+  ExitCode::SUCCESS
+}
+
+fn deps_to_json(deps: &PackageJsonDepsMap) -> Value {
+  let mut map = Map::with_capacity(deps.len());
+  for (alias, entry) in deps {
+    let value = match entry {
+      Ok(value) => json!(value.to_string()),
+      Err(err) => json!({ "error": err.to_string() }),
+    };
+    map.insert(alias.to_string(), value);
+  }
+  Value::Object(map)
+}
@@ -0,0 +1,94 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::PackageJson;
+use crate::PackageJsonCache;
+use crate::PackageJsonLoadError;
+use crate::PackageJsonLoadErrorKind;
+use crate::PackageJsonLoadOptions;
+use crate::PackageJsonRc;
+
+/// Files smaller than this are read into memory, which is faster than
+/// mapping them.
+const MMAP_MIN_LEN: u64 = 1024 * 1024;
+
+impl PackageJson {
+  /// Loads the package.json from the real file system, memory mapping
+  /// large files so they're parsed without copying them into a buffer.
+  ///
+  /// Unlike `load_from_path`, this doesn't accept a sys implementation
+  /// because only files on disk can be mapped.
+  pub fn load_from_path_mmap(
+    maybe_cache: Option<&dyn PackageJsonCache>,
+    path: &Path,
+    options: PackageJsonLoadOptions,
+  ) -> Result<PackageJsonRc, PackageJsonLoadError> {
+    Self::load_with_cache(maybe_cache, path, options, |path| {
+      let to_load_error = |err| {
+        PackageJsonLoadErrorKind::Io {
+          path: path.to_path_buf(),
+          source: err,
+        }
+        .into_box()
+      };
+      let mut file = File::open(path).map_err(to_load_error)?;
+      let len = file.metadata().map_err(to_load_error)?.len();
+      if len < MMAP_MIN_LEN {
+        let mut bytes = Vec::with_capacity(len as usize);
+        file.read_to_end(&mut bytes).map_err(to_load_error)?;
+        return Self::load_from_text(
+          path,
+          &String::from_utf8_lossy(&bytes),
+          options,
+        );
+      }
+      // SAFETY: the mapping is only read while parsing. Like any reader,
+      // the result is unspecified when the file is modified meanwhile.
+      let mmap = unsafe { memmap2::Mmap::map(&file) }.map_err(to_load_error)?;
+      Self::load_from_text(path, &String::from_utf8_lossy(&mmap), options)
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use pretty_assertions::assert_eq;
+  use sys_traits::impls::RealSys;
+  use sys_traits::FsCreateDirAll;
+  use sys_traits::FsRemoveDirAll;
+  use sys_traits::FsWrite;
+
+  use super::*;
+
+  #[test]
+  fn load_from_path_mmap() {
+    let dir = std::env::temp_dir()
+      .join(format!("deno_package_json_mmap_{}", std::process::id()));
+    let sys = RealSys;
+    sys.fs_create_dir_all(&dir).unwrap();
+    let small_path = dir.join("small.json");
+    sys.fs_write(&small_path, r#"{ "name": "small" }"#).unwrap();
+    let large_path = dir.join("large.json");
+    let description = "a".repeat(MMAP_MIN_LEN as usize);
+    sys
+      .fs_write(
+        &large_path,
+        format!(r#"{{ "name": "large", "description": "{description}" }}"#),
+      )
+      .unwrap();
+
+    let load = |path: &Path| {
+      PackageJson::load_from_path_mmap(None, path, Default::default())
+    };
+    assert_eq!(load(&small_path).unwrap().name.as_deref(), Some("small"));
+    assert_eq!(load(&large_path).unwrap().name.as_deref(), Some("large"));
+    assert!(matches!(
+      load(&dir.join("missing.json")).unwrap_err().as_kind(),
+      PackageJsonLoadErrorKind::Io { .. }
+    ));
+    sys.fs_remove_dir_all(&dir).unwrap();
+  }
+}
@@ -0,0 +1,180 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::path::Path;
+
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::NodeModuleKind;
+use crate::PackageJson;
+
+impl PackageJson {
+  /// Gets if a file of this package is ESM or CommonJS.
+  ///
+  /// `.mjs` and `.mts` files are always ESM and `.cjs` and `.cts` files
+  /// are always CommonJS. Other files follow the "type" field. Without a
+  /// "type" field, files that are only exported through the "import" or
+  /// "module" conditions are considered ESM, since they wouldn't load
+  /// otherwise, and everything else is CommonJS like in Node.
+  pub fn module_kind_for(&self, file_path: &Path) -> NodeModuleKind {
+    match file_path.extension().and_then(|ext| ext.to_str()) {
+      Some("mjs" | "mts") => return NodeModuleKind::Esm,
+      Some("cjs" | "cts") => return NodeModuleKind::Cjs,
+      _ => {}
+    }
+    match self.typ.as_str() {
+      "module" => NodeModuleKind::Esm,
+      "commonjs" => NodeModuleKind::Cjs,
+      _ if self.is_only_imported(file_path) => NodeModuleKind::Esm,
+      _ => NodeModuleKind::Cjs,
+    }
+  }
+
+  /// Gets if the "exports" targets matching the file are all under an
+  /// "import" or "module" condition.
+  fn is_only_imported(&self, file_path: &Path) -> bool {
+    let (Some(exports), Ok(dir_path)) = (&self.exports, self.try_dir_path())
+    else {
+      return false;
+    };
+    let file_path = deno_path_util::normalize_path(file_path);
+    let Ok(relative_path) = file_path.strip_prefix(dir_path) else {
+      return false;
+    };
+    let mut relative_specifier = String::from(".");
+    for component in relative_path.components() {
+      let Some(component) = component.as_os_str().to_str() else {
+        return false;
+      };
+      relative_specifier.push('/');
+      relative_specifier.push_str(component);
+    }
+    let mut hints = ExportHints::default();
+    // subpath keys (ex. "./utils") don't change the condition, so the
+    // top level is handled the same as conditions
+    collect_object_hints(&relative_specifier, exports, None, &mut hints);
+    hints.imported && !hints.other
+  }
+}
+
+#[derive(Default)]
+struct ExportHints {
+  /// A matching target is under an "import" or "module" condition.
+  imported: bool,
+  /// A matching target is reachable without those conditions.
+  other: bool,
+}
+
+fn collect_hints(
+  relative_path: &str,
+  target: &Value,
+  condition: Option<NodeModuleKind>,
+  hints: &mut ExportHints,
+) {
+  match target {
+    Value::String(target) => {
+      if target_matches(target, relative_path) {
+        match condition {
+          Some(NodeModuleKind::Esm) => hints.imported = true,
+          _ => hints.other = true,
+        }
+      }
+    }
+    Value::Array(targets) => {
+      for target in targets {
+        collect_hints(relative_path, target, condition, hints);
+      }
+    }
+    Value::Object(conditions) => {
+      collect_object_hints(relative_path, conditions, condition, hints);
+    }
+    _ => {}
+  }
+}
+
+fn collect_object_hints(
+  relative_path: &str,
+  conditions: &Map<String, Value>,
+  condition: Option<NodeModuleKind>,
+  hints: &mut ExportHints,
+) {
+  for (key, target) in conditions {
+    let condition = match key.as_str() {
+      "import" | "module" => Some(NodeModuleKind::Esm),
+      "require" => Some(NodeModuleKind::Cjs),
+      _ => condition,
+    };
+    collect_hints(relative_path, target, condition, hints);
+  }
+}
+
+/// Gets if an "exports" target (ex. `./dist/*.js`) matches the path of
+/// the file relative to the package (ex. `./dist/index.js`).
+pub(crate) fn target_matches(target: &str, relative_path: &str) -> bool {
+  match target.split_once('*') {
+    Some((prefix, suffix)) => {
+      relative_path.len() >= prefix.len() + suffix.len()
+        && relative_path.starts_with(prefix)
+        && relative_path.ends_with(suffix)
+    }
+    None => target == relative_path,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use super::*;
+
+  #[test]
+  fn module_kind_for() {
+    let package_json = |value| {
+      PackageJson::load_from_value(PathBuf::from("/pkg/package.json"), value)
+    };
+    let kind = |package_json: &PackageJson, path: &str| {
+      package_json.module_kind_for(Path::new(path))
+    };
+    let module = package_json(serde_json::json!({ "type": "module" }));
+    assert_eq!(kind(&module, "/pkg/index.js"), NodeModuleKind::Esm);
+    assert_eq!(kind(&module, "/pkg/index.cjs"), NodeModuleKind::Cjs);
+    let commonjs = package_json(serde_json::json!({
+      "type": "commonjs",
+      "exports": { "import": "./index.js" },
+    }));
+    assert_eq!(kind(&commonjs, "/pkg/index.js"), NodeModuleKind::Cjs);
+    assert_eq!(kind(&commonjs, "/pkg/index.mts"), NodeModuleKind::Esm);
+
+    let untyped = package_json(serde_json::json!({
+      "exports": {
+        ".": {
+          "import": "./dist/esm/index.js",
+          "require": "./dist/cjs/index.js",
+        },
+        "./features/*": { "module": "./dist/esm/features/*.js" },
+        "./both": {
+          "import": "./dist/both.js",
+          "default": "./dist/both.js",
+        },
+      },
+    }));
+    assert_eq!(
+      kind(&untyped, "/pkg/dist/esm/index.js"),
+      NodeModuleKind::Esm
+    );
+    assert_eq!(
+      kind(&untyped, "/pkg/dist/cjs/index.js"),
+      NodeModuleKind::Cjs
+    );
+    assert_eq!(
+      kind(&untyped, "/pkg/./dist/esm/features/a.js"),
+      NodeModuleKind::Esm
+    );
+    assert_eq!(kind(&untyped, "/pkg/dist/both.js"), NodeModuleKind::Cjs);
+    assert_eq!(kind(&untyped, "/other/index.js"), NodeModuleKind::Cjs);
+    let sugar = package_json(serde_json::json!({
+      "exports": { "import": "./index.js", "require": "./index.cjs" },
+    }));
+    assert_eq!(kind(&sugar, "/pkg/index.js"), NodeModuleKind::Esm);
+  }
+}
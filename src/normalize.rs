@@ -0,0 +1,316 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::path::Path;
+
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::PackageJson;
+
+impl PackageJson {
+  /// Creates a package.json from a version object of the npm registry's
+  /// package information (ex. `versions["1.0.0"]` of
+  /// `https://registry.npmjs.org/<name>`).
+  ///
+  /// Registry data has no location on disk, so the directory the package
+  /// would be found in (ex. in node_modules) must be provided. The registry
+  /// always serves `bin` as a map, so a string `bin` is converted to that
+  /// form to keep both sources of data consistent.
+  pub fn from_npm_registry_version_info(
+    package_dir: &Path,
+    version_info: Value,
+  ) -> PackageJson {
+    let mut package_json = PackageJson::load_from_value(
+      package_dir.join("package.json"),
+      version_info,
+    );
+    package_json.normalize_bin();
+    package_json
+  }
+
+  /// Normalizes the package.json into the shape the npm registry serves,
+  /// similar to the normalize-package-data package.
+  ///
+  /// - `repository` shorthands (ex. `user/repo`, `github:user/repo`) are
+  ///   expanded to `{ "type": "git", "url": "git+https://..." }`.
+  /// - `bugs` is expanded to an object and defaults to the GitHub issues
+  ///   page of the repository.
+  /// - `author` strings (`Name <email> (url)`) are parsed to an object.
+  /// - A string `bin` is converted to a map keyed by the unscoped name.
+  /// - `optionalDependencies` are merged into `dependencies`.
+  pub fn normalize(&mut self) {
+    if let Some(Value::String(repository)) = &self.repository {
+      let mut obj = Map::new();
+      obj.insert("type".to_string(), "git".into());
+      obj.insert("url".to_string(), expand_repository_url(repository).into());
+      self.repository = Some(Value::Object(obj));
+    }
+
+    self.bugs = match self.bugs.take() {
+      Some(Value::String(bugs)) => {
+        let mut obj = Map::new();
+        let key = if bugs.contains('@') && !bugs.contains("://") {
+          "email"
+        } else {
+          "url"
+        };
+        obj.insert(key.to_string(), bugs.into());
+        Some(Value::Object(obj))
+      }
+      Some(bugs) => Some(bugs),
+      None => self.github_repo_url().map(|url| {
+        let mut obj = Map::new();
+        obj.insert("url".to_string(), format!("{}/issues", url).into());
+        Value::Object(obj)
+      }),
+    };
+
+    if let Some(Value::String(author)) = &self.author {
+      self.author = Some(Value::Object(parse_person(author)));
+    }
+
+    self.normalize_bin();
+
+    if let Some(optional_deps) = &self.optional_dependencies {
+      let deps = crate::sync::make_mut(
+        self.dependencies.get_or_insert_with(Default::default),
+      );
+      for (key, value) in optional_deps.iter() {
+        deps.insert(key.clone(), value.clone());
+      }
+      self.resolved_deps = Default::default();
+    }
+  }
+
+  /// Removes the entries of `dependencies` that are also in
+  /// `optionalDependencies`, like npm does when reading a package.json,
+  /// so that the optional specifier is the only one used.
+  ///
+  /// Unlike `normalize`, this keeps the dependencies in separate fields.
+  pub fn dedupe_optional_dependencies(&mut self) {
+    let (Some(optional_deps), Some(deps)) =
+      (&self.optional_dependencies, &mut self.dependencies)
+    else {
+      return;
+    };
+    if !optional_deps.keys().any(|key| deps.contains_key(key)) {
+      return;
+    }
+    let deps = crate::sync::make_mut(deps);
+    deps.retain(|key, _| !optional_deps.contains_key(key));
+    self.resolved_deps = Default::default();
+  }
+
+  fn normalize_bin(&mut self) {
+    if let (Some(Value::String(bin)), Some(name)) = (&self.bin, &self.name) {
+      let bin_name = name.rsplit_once('/').map_or(name.as_str(), |(_, n)| n);
+      let mut obj = Map::new();
+      obj.insert(bin_name.to_string(), bin.clone().into());
+      self.bin = Some(Value::Object(obj));
+    }
+  }
+
+  /// Gets the https URL of the repository when it's hosted on GitHub.
+  fn github_repo_url(&self) -> Option<String> {
+    let url = match self.repository.as_ref()? {
+      Value::String(url) => expand_repository_url(url),
+      Value::Object(obj) => obj.get("url")?.as_str()?.to_string(),
+      _ => return None,
+    };
+    let rest = url
+      .strip_prefix("git+https://github.com/")
+      .or_else(|| url.strip_prefix("https://github.com/"))
+      .or_else(|| url.strip_prefix("git://github.com/"))
+      .or_else(|| url.strip_prefix("git+ssh://git@github.com/"))?;
+    let rest = rest.strip_suffix(".git").unwrap_or(rest);
+    Some(format!("https://github.com/{}", rest))
+  }
+}
+
+fn expand_repository_url(repository: &str) -> String {
+  const HOSTS: [(&str, &str); 3] = [
+    ("github:", "github.com"),
+    ("gitlab:", "gitlab.com"),
+    ("bitbucket:", "bitbucket.org"),
+  ];
+  for (prefix, host) in HOSTS {
+    if let Some(path) = repository.strip_prefix(prefix) {
+      return format!("git+https://{}/{}.git", host, path);
+    }
+  }
+  if let Some(id) = repository.strip_prefix("gist:") {
+    return format!("git+https://gist.github.com/{}.git", id);
+  }
+  let is_shorthand = !repository.contains(':')
+    && repository.split('/').count() == 2
+    && !repository.starts_with('.');
+  if is_shorthand {
+    format!("git+https://github.com/{}.git", repository)
+  } else {
+    repository.to_string()
+  }
+}
+
+/// Parses `Name <email> (url)`, where the email and url are optional.
+fn parse_person(text: &str) -> Map<String, Value> {
+  fn between(text: &str, start: char, end: char) -> Option<&str> {
+    let start_index = text.find(start)?;
+    let rest = &text[start_index + 1..];
+    let end_index = rest.find(end)?;
+    Some(rest[..end_index].trim()).filter(|s| !s.is_empty())
+  }
+
+  let mut obj = Map::new();
+  let name_end = text.find(['<', '(']).unwrap_or(text.len());
+  let name = text[..name_end].trim();
+  if !name.is_empty() {
+    obj.insert("name".to_string(), name.into());
+  }
+  if let Some(email) = between(text, '<', '>') {
+    obj.insert("email".to_string(), email.into());
+  }
+  if let Some(url) = between(text, '(', ')') {
+    obj.insert("url".to_string(), url.into());
+  }
+  obj
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn normalizes() {
+    let mut package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      json!({
+        "name": "@scope/pkg",
+        "bin": "./cli.js",
+        "repository": "denoland/deno",
+        "author": "Barney Rubble <b@rubble.com> (http://barnyrubble.tumblr.com/)",
+        "dependencies": { "a": "^1" },
+        "optionalDependencies": { "b": "^2" },
+      }),
+    );
+    package_json.normalize();
+    assert_eq!(
+      package_json.repository,
+      Some(json!({
+        "type": "git",
+        "url": "git+https://github.com/denoland/deno.git",
+      }))
+    );
+    assert_eq!(
+      package_json.bugs,
+      Some(json!({ "url": "https://github.com/denoland/deno/issues" }))
+    );
+    assert_eq!(
+      package_json.author,
+      Some(json!({
+        "name": "Barney Rubble",
+        "email": "b@rubble.com",
+        "url": "http://barnyrubble.tumblr.com/",
+      }))
+    );
+    assert_eq!(package_json.bin, Some(json!({ "pkg": "./cli.js" })));
+    assert_eq!(
+      package_json
+        .dependencies
+        .as_ref()
+        .unwrap()
+        .keys()
+        .collect::<Vec<_>>(),
+      vec!["a", "b"]
+    );
+    assert!(package_json
+      .resolve_local_package_json_deps()
+      .get("b")
+      .is_some());
+  }
+
+  #[test]
+  fn dedupes_optional_dependencies() {
+    let mut package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      json!({
+        "dependencies": { "a": "^1.0.0", "b": "^1.0.0", "c": "^1.0.0" },
+        "optionalDependencies": { "b": "^2.0.0" },
+      }),
+    );
+    assert!(package_json
+      .resolve_local_package_json_deps()
+      .has_dependency("b"));
+    package_json.dedupe_optional_dependencies();
+    assert_eq!(
+      package_json
+        .dependencies
+        .as_deref()
+        .unwrap()
+        .keys()
+        .collect::<Vec<_>>(),
+      vec!["a", "c"]
+    );
+    assert!(!package_json
+      .resolve_local_package_json_deps()
+      .has_dependency("b"));
+    assert_eq!(
+      package_json.optional_dependencies.as_deref().unwrap()["b"],
+      "^2.0.0"
+    );
+  }
+
+  #[test]
+  fn from_npm_registry_version_info() {
+    let package_json = PackageJson::from_npm_registry_version_info(
+      Path::new("/node_modules/pkg"),
+      json!({
+        "name": "pkg",
+        "version": "1.0.0",
+        "bin": "bin/cli.js",
+        "dependencies": { "a": "^1" },
+        "dist": {
+          "tarball": "https://registry.npmjs.org/pkg/-/pkg-1.0.0.tgz",
+        },
+      }),
+    );
+    assert_eq!(
+      &*package_json.path,
+      Path::new("/node_modules/pkg/package.json")
+    );
+    assert_eq!(package_json.version.as_deref(), Some("1.0.0"));
+    assert_eq!(package_json.bin, Some(json!({ "pkg": "bin/cli.js" })));
+    assert!(package_json
+      .resolve_local_package_json_deps()
+      .get("a")
+      .is_some());
+  }
+
+  #[test]
+  fn expands_repository_urls() {
+    assert_eq!(
+      expand_repository_url("gitlab:user/repo"),
+      "git+https://gitlab.com/user/repo.git"
+    );
+    assert_eq!(
+      expand_repository_url("https://example.com/repo.git"),
+      "https://example.com/repo.git"
+    );
+  }
+
+  #[test]
+  fn parses_people() {
+    assert_eq!(
+      Value::Object(parse_person("Only Name")),
+      json!({ "name": "Only Name" })
+    );
+    assert_eq!(
+      Value::Object(parse_person("<only@email.com>")),
+      json!({ "email": "only@email.com" })
+    );
+  }
+}
@@ -0,0 +1,288 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use deno_error::JsError;
+use deno_semver::package::PackageNv;
+use deno_semver::package::PackageReq;
+use deno_semver::VersionReq;
+use serde_json::Map;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::PackageJson;
+
+/// A parsed entry of the "overrides" field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageJsonOverride {
+  /// Name of the package to override.
+  pub name: String,
+  /// Version range the package must match for the override to apply
+  /// (ex. `^1` for a `foo@^1` key).
+  pub version_req: Option<String>,
+  /// Specifier to use for the package, which is `None` when the entry
+  /// only overrides the dependencies of the package.
+  pub specifier: Option<String>,
+  /// Overrides that only apply within the dependencies of the package.
+  pub children: Vec<PackageJsonOverride>,
+}
+
+impl PackageJsonOverride {
+  /// Gets the effective specifier of a dependency from parsed overrides.
+  ///
+  /// `ancestors` are the packages the dependency was reached through,
+  /// starting at a direct dependency of the root and ending at the
+  /// dependency's parent. Like npm, the overrides nested under the
+  /// closest matching ancestor take precedence, an ancestor matches when
+  /// its version satisfies the range of the key, and the dependency
+  /// matches when its requirement intersects the range of the key.
+  ///
+  /// Returns `None` when the dependency isn't overridden.
+  pub fn resolve<'a>(
+    overrides: &'a [PackageJsonOverride],
+    dep: &PackageReq,
+    ancestors: &[PackageNv],
+  ) -> Option<&'a str> {
+    let mut rule_sets = vec![overrides];
+    for ancestor in ancestors {
+      let rule = rule_sets.iter().rev().find_map(|rules| {
+        rules.iter().find(|rule| {
+          rule.matches_key(&ancestor.name, |req| req.matches(&ancestor.version))
+        })
+      });
+      if let Some(rule) = rule {
+        if !rule.children.is_empty() {
+          rule_sets.push(&rule.children);
+        }
+      }
+    }
+    let rule = rule_sets.iter().rev().find_map(|rules| {
+      rules.iter().find(|rule| {
+        rule.matches_key(&dep.name, |req| req.intersects(&dep.version_req))
+      })
+    })?;
+    rule.specifier.as_deref()
+  }
+
+  /// Gets if the key is for the package and its range, if any, passes
+  /// the check. Invalid ranges never match.
+  fn matches_key(
+    &self,
+    name: &str,
+    check_range: impl FnOnce(&VersionReq) -> bool,
+  ) -> bool {
+    self.name == name
+      && match &self.version_req {
+        Some(req) => {
+          VersionReq::parse_from_npm(req).is_ok_and(|req| check_range(&req))
+        }
+        None => true,
+      }
+  }
+}
+
+#[derive(Debug, Error, JsError)]
+#[class(generic)]
+pub enum PackageJsonOverridesError {
+  #[error("Unable to resolve reference ${reference} in override \"{key}\". It must be a direct dependency of the package.")]
+  UnresolvedReference { key: String, reference: String },
+}
+
+impl PackageJson {
+  /// Parses the "overrides" field.
+  ///
+  /// Like npm, values starting with `$` (ex. `"foo": "$foo"`) reference
+  /// the requirement of the package's own dependency on that name.
+  pub fn overrides(
+    &self,
+  ) -> Result<Vec<PackageJsonOverride>, PackageJsonOverridesError> {
+    match &self.overrides {
+      Some(overrides) => parse_overrides(self, overrides),
+      None => Ok(Vec::new()),
+    }
+  }
+
+  /// Gets the effective specifier of a dependency after applying the
+  /// "overrides" field. See `PackageJsonOverride::resolve`, which avoids
+  /// parsing the overrides for each dependency.
+  pub fn resolve_override(
+    &self,
+    dep: &PackageReq,
+    ancestors: &[PackageNv],
+  ) -> Result<Option<String>, PackageJsonOverridesError> {
+    let overrides = self.overrides()?;
+    Ok(
+      PackageJsonOverride::resolve(&overrides, dep, ancestors)
+        .map(|specifier| specifier.to_string()),
+    )
+  }
+
+  /// Gets the requirement of a direct dependency referenced by `$name`,
+  /// checking the dependency fields in the same order as npm.
+  fn referenced_specifier(&self, name: &str) -> Option<&str> {
+    [
+      &self.dev_dependencies,
+      &self.optional_dependencies,
+      &self.dependencies,
+      &self.peer_dependencies,
+    ]
+    .into_iter()
+    .find_map(|deps| deps.as_ref()?.get(name))
+    .map(|specifier| specifier.as_str())
+  }
+}
+
+fn parse_overrides(
+  package_json: &PackageJson,
+  overrides: &Map<String, Value>,
+) -> Result<Vec<PackageJsonOverride>, PackageJsonOverridesError> {
+  let mut result = Vec::with_capacity(overrides.len());
+  for (key, value) in overrides {
+    if key == "." {
+      continue;
+    }
+    let (specifier, children) = match value {
+      Value::String(specifier) => (Some(specifier.as_str()), Vec::new()),
+      Value::Object(children) => (
+        children.get(".").and_then(|v| v.as_str()),
+        parse_overrides(package_json, children)?,
+      ),
+      _ => continue,
+    };
+    let specifier = match specifier {
+      Some(specifier) => match specifier.strip_prefix('$') {
+        Some(reference) => Some(
+          package_json
+            .referenced_specifier(reference)
+            .ok_or_else(|| PackageJsonOverridesError::UnresolvedReference {
+              key: key.clone(),
+              reference: reference.to_string(),
+            })?
+            .to_string(),
+        ),
+        None => Some(specifier.to_string()),
+      },
+      None => None,
+    };
+    let (name, version_req) = crate::split_package_selector(key);
+    result.push(PackageJsonOverride {
+      name: name.to_string(),
+      version_req: version_req.map(|req| req.to_string()),
+      specifier,
+      children,
+    });
+  }
+  Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn parses_overrides() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      json!({
+        "dependencies": { "bar": "^2.0.0" },
+        "devDependencies": { "baz": "^3.0.0" },
+        "overrides": {
+          "foo": "1.0.0",
+          "bar": "$bar",
+          "qux@^1": {
+            ".": "1.2.0",
+            "baz": "$baz",
+          },
+        },
+      }),
+    );
+    assert_eq!(
+      package_json.overrides().unwrap(),
+      vec![
+        PackageJsonOverride {
+          name: "foo".to_string(),
+          version_req: None,
+          specifier: Some("1.0.0".to_string()),
+          children: Vec::new(),
+        },
+        PackageJsonOverride {
+          name: "bar".to_string(),
+          version_req: None,
+          specifier: Some("^2.0.0".to_string()),
+          children: Vec::new(),
+        },
+        PackageJsonOverride {
+          name: "qux".to_string(),
+          version_req: Some("^1".to_string()),
+          specifier: Some("1.2.0".to_string()),
+          children: vec![PackageJsonOverride {
+            name: "baz".to_string(),
+            version_req: None,
+            specifier: Some("^3.0.0".to_string()),
+            children: Vec::new(),
+          }],
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn unresolved_override_reference() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      json!({ "overrides": { "@scope/foo": { ".": "$foo" } } }),
+    );
+    assert_eq!(
+      package_json.overrides().unwrap_err().to_string(),
+      "Unable to resolve reference $foo in override \"@scope/foo\". It must be a direct dependency of the package."
+    );
+  }
+
+  #[test]
+  fn resolves_overrides() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      json!({
+        "overrides": {
+          "foo": "1.0.0",
+          "bar@^1": "1.5.0",
+          "baz": { "foo": "2.0.0" },
+          "qux@2": {
+            "baz": { "foo": "3.0.0" },
+          },
+        },
+      }),
+    );
+    let resolve = |dep: &str, ancestors: &[&str]| {
+      package_json
+        .resolve_override(
+          &PackageReq::from_str(dep).unwrap(),
+          &ancestors
+            .iter()
+            .map(|nv| PackageNv::from_str(nv).unwrap())
+            .collect::<Vec<_>>(),
+        )
+        .unwrap()
+    };
+    assert_eq!(resolve("foo@^0.1", &[]).as_deref(), Some("1.0.0"));
+    assert_eq!(resolve("foo@*", &["a@1.0.0"]).as_deref(), Some("1.0.0"));
+    assert_eq!(resolve("bar@^1.2", &[]).as_deref(), Some("1.5.0"));
+    assert_eq!(resolve("bar@^2", &[]), None);
+    assert_eq!(
+      resolve("foo@*", &["baz@1.0.0", "a@1.0.0"]).as_deref(),
+      Some("2.0.0")
+    );
+    assert_eq!(
+      resolve("foo@*", &["qux@2.1.0", "baz@1.0.0"]).as_deref(),
+      Some("3.0.0")
+    );
+    assert_eq!(
+      resolve("foo@*", &["qux@1.0.0", "baz@1.0.0"]).as_deref(),
+      Some("2.0.0")
+    );
+    assert_eq!(resolve("other@*", &["baz@1.0.0"]), None);
+  }
+}
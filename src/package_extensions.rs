@@ -0,0 +1,199 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use deno_semver::Version;
+use deno_semver::VersionReq;
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::PackageJson;
+use crate::PackageJsonStringMapRc;
+
+/// The pnpm specific settings of the "pnpm" field.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PackageJsonPnpmConfig {
+  /// Fields to add to the manifests of dependencies, keyed by a package
+  /// name with an optional version range (ex. `react-redux@1`).
+  #[serde(skip_serializing_if = "IndexMap::is_empty")]
+  pub package_extensions: IndexMap<String, PackageJsonPackageExtension>,
+}
+
+/// An entry of "pnpm.packageExtensions".
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PackageJsonPackageExtension {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub dependencies: Option<IndexMap<String, String>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub optional_dependencies: Option<IndexMap<String, String>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub peer_dependencies: Option<IndexMap<String, String>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub peer_dependencies_meta: Option<Map<String, Value>>,
+}
+
+pub(crate) fn parse_pnpm_config(value: Value) -> Option<PackageJsonPnpmConfig> {
+  let mut pnpm = crate::map_object(value)?;
+  let package_extensions = pnpm
+    .remove("packageExtensions")
+    .and_then(crate::map_object)
+    .unwrap_or_default()
+    .into_iter()
+    .filter_map(|(selector, extension)| {
+      let mut extension = crate::map_object(extension)?;
+      let mut string_map =
+        |key: &str| extension.remove(key).and_then(crate::parse_string_map);
+      let extension = PackageJsonPackageExtension {
+        dependencies: string_map("dependencies"),
+        optional_dependencies: string_map("optionalDependencies"),
+        peer_dependencies: string_map("peerDependencies"),
+        peer_dependencies_meta: extension
+          .remove("peerDependenciesMeta")
+          .and_then(crate::map_object),
+      };
+      Some((selector, extension))
+    })
+    .collect();
+  Some(PackageJsonPnpmConfig { package_extensions })
+}
+
+impl PackageJson {
+  /// Adds the fields of the matching "pnpm.packageExtensions" entries to
+  /// the manifest of a dependency, as pnpm does while resolving.
+  ///
+  /// Like pnpm, entries already in the manifest take precedence over the
+  /// ones from the extensions. Returns if the manifest was changed.
+  pub fn apply_package_extensions(&self, target: &mut PackageJson) -> bool {
+    let Some(pnpm) = &self.pnpm else {
+      return false;
+    };
+    let mut changed = false;
+    for (selector, extension) in &pnpm.package_extensions {
+      if !selector_matches(selector, target) {
+        continue;
+      }
+      for (extension_deps, target_deps) in [
+        (&extension.dependencies, &mut target.dependencies),
+        (
+          &extension.optional_dependencies,
+          &mut target.optional_dependencies,
+        ),
+        (&extension.peer_dependencies, &mut target.peer_dependencies),
+      ] {
+        let Some(extension_deps) = extension_deps else {
+          continue;
+        };
+        let target_deps = crate::sync::make_mut(
+          target_deps.get_or_insert_with(PackageJsonStringMapRc::default),
+        );
+        for (name, specifier) in extension_deps {
+          if !target_deps.contains_key(name) {
+            target_deps.insert(name.clone(), specifier.clone());
+            changed = true;
+          }
+        }
+      }
+      if let Some(extension_meta) = &extension.peer_dependencies_meta {
+        let target_meta =
+          target.peer_dependencies_meta.get_or_insert_with(Map::new);
+        for (name, meta) in extension_meta {
+          if !target_meta.contains_key(name) {
+            target_meta.insert(name.clone(), meta.clone());
+            changed = true;
+          }
+        }
+      }
+    }
+    if changed {
+      target.resolved_deps = Default::default();
+    }
+    changed
+  }
+}
+
+/// Matches a package extension key (ex. `@scope/name@^1`) against the
+/// name and version of a manifest.
+fn selector_matches(selector: &str, package_json: &PackageJson) -> bool {
+  let (name, range) = crate::split_package_selector(selector);
+  if package_json.name.as_deref() != Some(name) {
+    return false;
+  }
+  let Some(range) = range else {
+    return true;
+  };
+  let Some(version) = package_json
+    .version
+    .as_deref()
+    .and_then(|version| Version::parse_from_npm(version).ok())
+  else {
+    return false;
+  };
+  VersionReq::parse_from_npm(range).is_ok_and(|req| req.matches(&version))
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn applies_package_extensions() {
+    let root = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      json!({
+        "pnpm": {
+          "packageExtensions": {
+            "react-redux@1": {
+              "peerDependencies": { "react-dom": "*" },
+              "peerDependenciesMeta": { "react-dom": { "optional": true } },
+            },
+            "@scope/pkg": {
+              "dependencies": { "a": "^2.0.0", "b": "^1.0.0" },
+            },
+          }
+        }
+      }),
+    );
+    let mut target = PackageJson::load_from_value(
+      PathBuf::from("/node_modules/react-redux/package.json"),
+      json!({ "name": "react-redux", "version": "1.2.0" }),
+    );
+    assert!(root.apply_package_extensions(&mut target));
+    assert_eq!(
+      target.peer_dependencies.as_deref().unwrap()["react-dom"],
+      "*"
+    );
+    assert_eq!(
+      target.peer_dependencies_meta.as_ref().unwrap()["react-dom"],
+      json!({ "optional": true })
+    );
+    assert!(!root.apply_package_extensions(&mut target));
+
+    let mut target = PackageJson::load_from_value(
+      PathBuf::from("/node_modules/react-redux/package.json"),
+      json!({ "name": "react-redux", "version": "2.0.0" }),
+    );
+    assert!(!root.apply_package_extensions(&mut target));
+
+    let mut target = PackageJson::load_from_value(
+      PathBuf::from("/node_modules/@scope/pkg/package.json"),
+      json!({
+        "name": "@scope/pkg",
+        "version": "1.0.0",
+        "dependencies": { "a": "^1.0.0" },
+      }),
+    );
+    assert!(root.apply_package_extensions(&mut target));
+    let deps = target.resolve_local_package_json_deps();
+    assert_eq!(deps.dependency_req("a").unwrap().to_string(), "a@^1.0.0");
+    assert_eq!(deps.dependency_req("b").unwrap().to_string(), "b@^1.0.0");
+  }
+}
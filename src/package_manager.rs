@@ -0,0 +1,267 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::fmt;
+
+use deno_error::JsError;
+use deno_semver::Version;
+use serde::Serialize;
+use thiserror::Error;
+
+/// The "packageManager" field (ex. `pnpm@9.1.0+sha512.abc...`) used by
+/// corepack to pin the package manager of a project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageJsonPackageManager {
+  pub name: String,
+  pub version: String,
+  pub hash: Option<PackageJsonPackageManagerHash>,
+}
+
+/// Integrity hash of a pinned package manager (ex. `sha512.abc...`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageJsonPackageManagerHash {
+  pub algorithm: String,
+  /// Hex encoded digest.
+  pub digest: String,
+}
+
+impl PackageJsonPackageManagerHash {
+  /// Parses a hash in corepack's `<algorithm>.<hex digest>` form.
+  ///
+  /// Only the sha family of algorithms is recognized so that semver build
+  /// metadata (ex. `+build.1`) isn't mistaken for a hash.
+  pub fn parse(text: &str) -> Option<Self> {
+    let (algorithm, digest) = text.split_once('.')?;
+    let is_sha = algorithm.len() > 3
+      && algorithm
+        .get(..3)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("sha"))
+      && algorithm[3..].chars().all(|c| c.is_ascii_digit());
+    if !is_sha
+      || digest.is_empty()
+      || !digest.chars().all(|c| c.is_ascii_hexdigit())
+    {
+      return None;
+    }
+    Some(Self {
+      algorithm: algorithm.to_string(),
+      digest: digest.to_string(),
+    })
+  }
+}
+
+impl fmt::Display for PackageJsonPackageManagerHash {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}.{}", self.algorithm, self.digest)
+  }
+}
+
+#[derive(Debug, Error, JsError)]
+#[class(generic)]
+pub enum PackageJsonPackageManagerMismatch {
+  #[error("The project uses {expected}, but {found} was used.")]
+  Name { expected: String, found: String },
+  #[error("The project uses {name}@{expected}, but {name}@{found} was used.")]
+  Version {
+    name: String,
+    expected: String,
+    found: String,
+  },
+  #[error("The integrity of {name}@{version} doesn't match. Expected {expected}, but got {found}.")]
+  Hash {
+    name: String,
+    version: String,
+    expected: String,
+    found: String,
+  },
+  #[error("Could not verify the integrity of {name}@{version} because the hash is not using {algorithm}.")]
+  HashAlgorithm {
+    name: String,
+    version: String,
+    algorithm: String,
+  },
+  #[error(
+    "Invalid package manager hash \"{0}\". Expected <algorithm>.<hex digest>."
+  )]
+  InvalidHash(String),
+}
+
+impl PackageJsonPackageManager {
+  /// Parses the field in corepack's `<name>@<version>[+<hash>]` form.
+  pub fn parse(text: &str) -> Option<Self> {
+    let text = text.trim();
+    // skip the "@" of scoped names
+    let at_index = text.get(1..)?.find('@')? + 1;
+    let name = &text[..at_index];
+    let mut version = &text[at_index + 1..];
+    let mut hash = None;
+    if let Some((before, after)) = version.rsplit_once('+') {
+      // "+" is also used for semver build metadata
+      if let Some(parsed) = PackageJsonPackageManagerHash::parse(after) {
+        version = before;
+        hash = Some(parsed);
+      }
+    }
+    if name.is_empty() || version.is_empty() {
+      return None;
+    }
+    Some(Self {
+      name: name.to_string(),
+      version: version.to_string(),
+      hash,
+    })
+  }
+
+  /// Checks that a package manager matches the pinned one, like corepack
+  /// does before running it.
+  ///
+  /// `hash` is the integrity of the package manager in the same
+  /// `<algorithm>.<hex digest>` form and is only compared when both it
+  /// and the pinned hash are known.
+  pub fn satisfied_by(
+    &self,
+    name: &str,
+    version: &str,
+    hash: Option<&str>,
+  ) -> Result<(), PackageJsonPackageManagerMismatch> {
+    if self.name != name {
+      return Err(PackageJsonPackageManagerMismatch::Name {
+        expected: self.name.clone(),
+        found: name.to_string(),
+      });
+    }
+    let is_same_version = match (
+      Version::parse_standard(&self.version),
+      Version::parse_standard(version),
+    ) {
+      (Ok(expected), Ok(found)) => expected == found,
+      _ => self.version == version,
+    };
+    if !is_same_version {
+      return Err(PackageJsonPackageManagerMismatch::Version {
+        name: self.name.clone(),
+        expected: self.version.clone(),
+        found: version.to_string(),
+      });
+    }
+    let (Some(expected), Some(found)) = (&self.hash, hash) else {
+      return Ok(());
+    };
+    let found =
+      PackageJsonPackageManagerHash::parse(found).ok_or_else(|| {
+        PackageJsonPackageManagerMismatch::InvalidHash(found.to_string())
+      })?;
+    if !expected.algorithm.eq_ignore_ascii_case(&found.algorithm) {
+      return Err(PackageJsonPackageManagerMismatch::HashAlgorithm {
+        name: self.name.clone(),
+        version: self.version.clone(),
+        algorithm: expected.algorithm.clone(),
+      });
+    }
+    if !expected.digest.eq_ignore_ascii_case(&found.digest) {
+      return Err(PackageJsonPackageManagerMismatch::Hash {
+        name: self.name.clone(),
+        version: self.version.clone(),
+        expected: expected.to_string(),
+        found: found.to_string(),
+      });
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Display for PackageJsonPackageManager {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}@{}", self.name, self.version)?;
+    if let Some(hash) = &self.hash {
+      write!(f, "+{}", hash)?;
+    }
+    Ok(())
+  }
+}
+
+impl Serialize for PackageJsonPackageManager {
+  fn serialize<S: serde::Serializer>(
+    &self,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for PackageJsonPackageManager {
+  fn schema_name() -> std::borrow::Cow<'static, str> {
+    "PackageJsonPackageManager".into()
+  }
+
+  fn json_schema(
+    generator: &mut schemars::SchemaGenerator,
+  ) -> schemars::Schema {
+    generator.subschema_for::<String>()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn parse() {
+    let package_manager =
+      PackageJsonPackageManager::parse("pnpm@9.1.0+sha512.ABCdef01").unwrap();
+    assert_eq!(package_manager.name, "pnpm");
+    assert_eq!(package_manager.version, "9.1.0");
+    assert_eq!(
+      package_manager.hash,
+      Some(PackageJsonPackageManagerHash {
+        algorithm: "sha512".to_string(),
+        digest: "ABCdef01".to_string(),
+      })
+    );
+    assert_eq!(package_manager.to_string(), "pnpm@9.1.0+sha512.ABCdef01");
+    let package_manager =
+      PackageJsonPackageManager::parse("yarn@4.0.0+build.1").unwrap();
+    assert_eq!(package_manager.version, "4.0.0+build.1");
+    assert_eq!(package_manager.hash, None);
+    assert_eq!(PackageJsonPackageManager::parse("pnpm"), None);
+    assert_eq!(PackageJsonPackageManager::parse("pnpm@"), None);
+  }
+
+  #[test]
+  fn satisfied_by() {
+    let package_manager =
+      PackageJsonPackageManager::parse("pnpm@9.1.0+sha512.abcdef").unwrap();
+    assert!(package_manager.satisfied_by("pnpm", "9.1.0", None).is_ok());
+    assert!(package_manager
+      .satisfied_by("pnpm", "9.1.0", Some("SHA512.ABCDEF"))
+      .is_ok());
+    assert_eq!(
+      package_manager
+        .satisfied_by("npm", "9.1.0", None)
+        .unwrap_err()
+        .to_string(),
+      "The project uses pnpm, but npm was used."
+    );
+    assert_eq!(
+      package_manager
+        .satisfied_by("pnpm", "9.2.0", None)
+        .unwrap_err()
+        .to_string(),
+      "The project uses pnpm@9.1.0, but pnpm@9.2.0 was used."
+    );
+    assert!(matches!(
+      package_manager.satisfied_by("pnpm", "9.1.0", Some("sha512.012345")),
+      Err(PackageJsonPackageManagerMismatch::Hash { .. })
+    ));
+    assert!(matches!(
+      package_manager.satisfied_by("pnpm", "9.1.0", Some("sha1.abcdef")),
+      Err(PackageJsonPackageManagerMismatch::HashAlgorithm { .. })
+    ));
+    assert!(matches!(
+      package_manager.satisfied_by("pnpm", "9.1.0", Some("abcdef")),
+      Err(PackageJsonPackageManagerMismatch::InvalidHash(_))
+    ));
+  }
+}
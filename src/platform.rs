@@ -0,0 +1,210 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use crate::PackageJson;
+
+/// npm's "os" names (`process.platform`) and the matching Rust
+/// `target_os` values.
+const OS_NAMES: &[(&str, &str)] = &[
+  ("aix", "aix"),
+  ("android", "android"),
+  ("darwin", "macos"),
+  ("freebsd", "freebsd"),
+  ("linux", "linux"),
+  ("netbsd", "netbsd"),
+  ("openbsd", "openbsd"),
+  ("sunos", "solaris"),
+  ("sunos", "illumos"),
+  ("win32", "windows"),
+];
+
+/// npm's "cpu" names (`process.arch`) and the matching Rust
+/// `target_arch` values.
+const CPU_NAMES: &[(&str, &str)] = &[
+  ("arm", "arm"),
+  ("arm64", "aarch64"),
+  ("ia32", "x86"),
+  ("loong64", "loongarch64"),
+  ("mips", "mips"),
+  ("ppc", "powerpc"),
+  ("ppc64", "powerpc64"),
+  ("riscv64", "riscv64"),
+  ("s390x", "s390x"),
+  ("wasm32", "wasm32"),
+  ("x64", "x86_64"),
+];
+
+/// Converts an npm "os" name (ex. `"win32"`) to a Rust `target_os` value
+/// (ex. `"windows"`).
+pub fn npm_os_to_rust(os: &str) -> Option<&'static str> {
+  OS_NAMES
+    .iter()
+    .find(|(npm, _)| *npm == os)
+    .map(|(_, rust)| *rust)
+}
+
+/// Converts a Rust `target_os` value (ex. `std::env::consts::OS`) to an
+/// npm "os" name.
+pub fn npm_os_from_rust(os: &str) -> Option<&'static str> {
+  OS_NAMES
+    .iter()
+    .find(|(_, rust)| *rust == os)
+    .map(|(npm, _)| *npm)
+}
+
+/// Converts an npm "cpu" name (ex. `"x64"`) to a Rust `target_arch` value
+/// (ex. `"x86_64"`).
+pub fn npm_cpu_to_rust(cpu: &str) -> Option<&'static str> {
+  CPU_NAMES
+    .iter()
+    .find(|(npm, _)| *npm == cpu)
+    .map(|(_, rust)| *rust)
+}
+
+/// Converts a Rust `target_arch` value (ex. `std::env::consts::ARCH`) to
+/// an npm "cpu" name.
+pub fn npm_cpu_from_rust(cpu: &str) -> Option<&'static str> {
+  CPU_NAMES
+    .iter()
+    .find(|(_, rust)| *rust == cpu)
+    .map(|(npm, _)| *npm)
+}
+
+/// Gets the npm "os" and "cpu" names of a Rust target triple (ex.
+/// `x86_64-pc-windows-msvc` is `("win32", "x64")`).
+///
+/// The os is `None` for targets without an operating system, like
+/// `wasm32-unknown-unknown`.
+pub fn npm_platform_from_target_triple(
+  triple: &str,
+) -> Option<(Option<&'static str>, &'static str)> {
+  let mut components = triple.split('-');
+  let arch = match components.next()? {
+    "i386" | "i586" | "i686" => "x86",
+    arch if arch.starts_with("armv") || arch.starts_with("thumbv") => "arm",
+    arch => arch,
+  };
+  let cpu = npm_cpu_from_rust(arch)?;
+  let components = components.collect::<Vec<_>>();
+  // android triples also contain "linux" (ex. aarch64-linux-android)
+  let os = if components.contains(&"android") {
+    Some("android")
+  } else {
+    components.iter().find_map(|os| match *os {
+      // apple triples use "darwin" instead of the target_os
+      "darwin" => Some("darwin"),
+      os => npm_os_from_rust(os),
+    })
+  };
+  Some((os, cpu))
+}
+
+impl PackageJson {
+  /// Gets if the package supports the operating system and CPU
+  /// architecture, using npm's names (ex. `"win32"` and `"x64"`).
+  ///
+  /// Like npm, entries starting with `!` exclude a platform and a missing
+  /// field supports all of them.
+  pub fn supports_platform(&self, os: &str, cpu: &str) -> bool {
+    self
+      .os
+      .as_deref()
+      .map_or(true, |list| matches_list(list, os))
+      && self
+        .cpu
+        .as_deref()
+        .map_or(true, |list| matches_list(list, cpu))
+  }
+}
+
+/// Matches a value against an "os" or "cpu" list the same way as npm's
+/// npm-install-checks.
+fn matches_list(list: &[String], value: &str) -> bool {
+  if let [entry] = list {
+    if entry == "any" {
+      return true;
+    }
+  }
+  let mut negated = 0;
+  let mut matched = false;
+  for entry in list {
+    match entry.strip_prefix('!') {
+      Some(entry) => {
+        if entry == value {
+          return false;
+        }
+        negated += 1;
+      }
+      None => matched |= entry == value,
+    }
+  }
+  // a list of only exclusions allows everything else
+  matched || negated == list.len()
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use super::*;
+
+  #[test]
+  fn rust_target_names() {
+    assert_eq!(npm_os_to_rust("win32"), Some("windows"));
+    assert_eq!(npm_os_from_rust("macos"), Some("darwin"));
+    assert_eq!(npm_os_from_rust("illumos"), Some("sunos"));
+    assert_eq!(npm_cpu_to_rust("x64"), Some("x86_64"));
+    assert_eq!(npm_cpu_from_rust("aarch64"), Some("arm64"));
+    assert_eq!(npm_cpu_to_rust("sparc"), None);
+    assert_eq!(
+      npm_platform_from_target_triple("x86_64-pc-windows-msvc"),
+      Some((Some("win32"), "x64"))
+    );
+    assert_eq!(
+      npm_platform_from_target_triple("aarch64-apple-darwin"),
+      Some((Some("darwin"), "arm64"))
+    );
+    assert_eq!(
+      npm_platform_from_target_triple("aarch64-linux-android"),
+      Some((Some("android"), "arm64"))
+    );
+    assert_eq!(
+      npm_platform_from_target_triple("i686-unknown-linux-gnu"),
+      Some((Some("linux"), "ia32"))
+    );
+    assert_eq!(
+      npm_platform_from_target_triple("armv7-unknown-linux-gnueabihf"),
+      Some((Some("linux"), "arm"))
+    );
+    assert_eq!(
+      npm_platform_from_target_triple("wasm32-unknown-unknown"),
+      Some((None, "wasm32"))
+    );
+  }
+
+  #[test]
+  fn supports_platform() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "os": ["!win32"],
+        "cpu": ["x64", "arm64"],
+      }),
+    );
+    assert!(package_json.supports_platform("linux", "x64"));
+    assert!(package_json.supports_platform("darwin", "arm64"));
+    assert!(!package_json.supports_platform("win32", "x64"));
+    assert!(!package_json.supports_platform("linux", "ia32"));
+
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({ "os": "linux", "cpu": ["x64", "!x64"] }),
+    );
+    assert!(!package_json.supports_platform("linux", "x64"));
+    assert!(!package_json.supports_platform("darwin", "arm64"));
+    assert!(PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({})
+    )
+    .supports_platform("win32", "ia32"));
+  }
+}
@@ -0,0 +1,282 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use deno_semver::Version;
+use serde_json::Value;
+
+use crate::diagnostics::json_pointer;
+use crate::PackageJson;
+use crate::PackageJsonDiagnostic;
+use crate::PackageJsonDiagnosticKind;
+
+impl PackageJson {
+  /// Checks that the package.json can be published to a registry.
+  ///
+  /// This only looks at the package.json itself, so it doesn't check that
+  /// the files referenced by "exports", "main", or "bin" exist.
+  pub fn validate_for_publish(&self) -> Vec<PackageJsonDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut push = |pointer: String, kind| {
+      diagnostics.push(PackageJsonDiagnostic { pointer, kind })
+    };
+
+    match &self.name {
+      None => push(
+        String::new(),
+        PackageJsonDiagnosticKind::MissingField { field: "name" },
+      ),
+      Some(name) => {
+        if let Err(reason) = validate_package_name(name) {
+          push(
+            "/name".to_string(),
+            PackageJsonDiagnosticKind::InvalidName {
+              name: name.clone(),
+              reason,
+            },
+          );
+        }
+      }
+    }
+    match &self.version {
+      None => push(
+        String::new(),
+        PackageJsonDiagnosticKind::MissingField { field: "version" },
+      ),
+      Some(version) => {
+        if Version::parse_standard(version).is_err() {
+          push(
+            "/version".to_string(),
+            PackageJsonDiagnosticKind::InvalidVersion {
+              version: version.clone(),
+            },
+          );
+        }
+      }
+    }
+    if self.private == Some(true) {
+      push(
+        "/private".to_string(),
+        PackageJsonDiagnosticKind::PrivatePackage,
+      );
+    }
+
+    if let Some(exports) = &self.exports {
+      for (key, target) in exports.iter() {
+        let mut path = vec!["exports".to_string(), key.clone()];
+        collect_invalid_export_targets(&mut path, target, &mut push);
+      }
+    }
+    if let Some(main) = &self.main {
+      if !is_relative_within_package(main) {
+        push(
+          "/main".to_string(),
+          PackageJsonDiagnosticKind::InvalidTarget {
+            target: main.clone(),
+          },
+        );
+      }
+    }
+    match &self.bin {
+      Some(Value::String(target)) => {
+        if !is_relative_within_package(target) {
+          push(
+            "/bin".to_string(),
+            PackageJsonDiagnosticKind::InvalidTarget {
+              target: target.clone(),
+            },
+          );
+        }
+      }
+      Some(Value::Object(bins)) => {
+        for (name, target) in bins {
+          if let Some(target) = target.as_str() {
+            if !is_relative_within_package(target) {
+              push(
+                json_pointer(&["bin", name]),
+                PackageJsonDiagnosticKind::InvalidTarget {
+                  target: target.to_string(),
+                },
+              );
+            }
+          }
+        }
+      }
+      _ => {}
+    }
+
+    // dev dependencies aren't installed by consumers of the package
+    for (field, deps) in [
+      ("dependencies", &self.dependencies),
+      ("optionalDependencies", &self.optional_dependencies),
+    ] {
+      for (name, specifier) in deps.iter().flat_map(|deps| deps.iter()) {
+        if ["workspace:", "file:", "link:"]
+          .iter()
+          .any(|prefix| specifier.starts_with(prefix))
+        {
+          push(
+            json_pointer(&[field, name]),
+            PackageJsonDiagnosticKind::LocalDependency {
+              name: name.clone(),
+              specifier: specifier.clone(),
+            },
+          );
+        }
+      }
+    }
+
+    diagnostics
+  }
+}
+
+fn collect_invalid_export_targets(
+  path: &mut Vec<String>,
+  target: &Value,
+  push: &mut impl FnMut(String, PackageJsonDiagnosticKind),
+) {
+  match target {
+    Value::String(target) => {
+      if !target.starts_with("./") || !is_relative_within_package(target) {
+        push(
+          json_pointer(path),
+          PackageJsonDiagnosticKind::InvalidTarget {
+            target: target.clone(),
+          },
+        );
+      }
+    }
+    Value::Object(conditions) => {
+      for (condition, target) in conditions {
+        path.push(condition.clone());
+        collect_invalid_export_targets(path, target, push);
+        path.pop();
+      }
+    }
+    Value::Array(targets) => {
+      for (index, target) in targets.iter().enumerate() {
+        path.push(index.to_string());
+        collect_invalid_export_targets(path, target, push);
+        path.pop();
+      }
+    }
+    _ => {}
+  }
+}
+
+/// Gets if the path is relative and doesn't leave the package directory.
+fn is_relative_within_package(path: &str) -> bool {
+  if path.is_empty() || path.starts_with(['/', '\\']) || path.contains(':') {
+    return false;
+  }
+  let mut depth = 0usize;
+  for segment in path.split(['/', '\\']) {
+    match segment {
+      "" | "." => {}
+      ".." => match depth.checked_sub(1) {
+        Some(new_depth) => depth = new_depth,
+        None => return false,
+      },
+      _ => depth += 1,
+    }
+  }
+  true
+}
+
+/// Validates a package name the way npm does for new packages.
+fn validate_package_name(name: &str) -> Result<(), &'static str> {
+  if name.is_empty() {
+    return Err("name can't be empty");
+  }
+  if name.len() > 214 {
+    return Err("name can't be longer than 214 characters");
+  }
+  if name.trim() != name {
+    return Err("name can't have leading or trailing spaces");
+  }
+  if name.starts_with(['.', '_']) {
+    return Err("name can't start with a period or underscore");
+  }
+  if name.chars().any(|c| c.is_ascii_uppercase()) {
+    return Err("name can't contain capital letters");
+  }
+  let parts = match name.strip_prefix('@') {
+    Some(scoped) => match scoped.split_once('/') {
+      Some((scope, package)) if !scope.is_empty() && !package.is_empty() => {
+        vec![scope, package]
+      }
+      _ => return Err("scoped name must be in the form @scope/name"),
+    },
+    None => vec![name],
+  };
+  let is_url_safe = |c: char| {
+    c.is_ascii_alphanumeric()
+      || matches!(c, '-' | '_' | '.' | '~' | '!' | '*' | '\'' | '(' | ')')
+  };
+  if parts.iter().any(|part| !part.chars().all(is_url_safe)) {
+    return Err("name can only contain URL-friendly characters");
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn validate(value: Value) -> Vec<(&'static str, String)> {
+    PackageJson::load_from_value(PathBuf::from("/package.json"), value)
+      .validate_for_publish()
+      .into_iter()
+      .map(|d| (d.code(), d.pointer))
+      .collect()
+  }
+
+  #[test]
+  fn valid_package() {
+    assert_eq!(
+      validate(serde_json::json!({
+        "name": "@scope/pkg",
+        "version": "1.0.0-beta.1",
+        "main": "lib/index.js",
+        "exports": { ".": { "import": "./index.mjs", "default": null } },
+        "bin": { "pkg": "./bin/../cli.js" },
+        "dependencies": { "a": "^1" },
+        "devDependencies": { "b": "workspace:*" },
+      })),
+      vec![]
+    );
+  }
+
+  #[test]
+  fn invalid_package() {
+    assert_eq!(
+      validate(serde_json::json!({
+        "name": "My-Package",
+        "private": true,
+        "main": "/abs/index.js",
+        "exports": { ".": ["index.js", { "node": "./../outside.js" }] },
+        "bin": { "a": "../a.js" },
+        "dependencies": { "a": "workspace:^", "b": "file:../b" },
+        "optionalDependencies": { "c": "link:../c" },
+      })),
+      vec![
+        ("invalid-name", "/name".to_string()),
+        ("missing-field", "".to_string()),
+        ("private-package", "/private".to_string()),
+        ("invalid-target", "/exports/./0".to_string()),
+        ("invalid-target", "/exports/./1/node".to_string()),
+        ("invalid-target", "/main".to_string()),
+        ("invalid-target", "/bin/a".to_string()),
+        ("local-dependency", "/dependencies/a".to_string()),
+        ("local-dependency", "/dependencies/b".to_string()),
+        ("local-dependency", "/optionalDependencies/c".to_string()),
+      ]
+    );
+    assert_eq!(
+      validate(serde_json::json!({ "name": "pkg", "version": "1.0" })),
+      vec![("invalid-version", "/version".to_string())]
+    );
+  }
+}
@@ -0,0 +1,433 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::path::PathBuf;
+
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::diagnostics::json_pointer;
+use crate::PackageJson;
+use crate::PackageJsonDiagnostic;
+use crate::PackageJsonDiagnosticKind;
+
+const MAX_DEPTH: usize = 128;
+
+impl PackageJson {
+  /// Loads the package.json from a string, recovering as much as possible
+  /// from JSON syntax errors (ex. trailing commas, comments or unquoted
+  /// keys) instead of failing.
+  ///
+  /// Returns the recovered package.json along with the syntax errors and
+  /// any values that had to be dropped (ex. `"exports"` mixing subpath and
+  /// condition keys), which is useful for editors where the file is often
+  /// mid-edit.
+  pub fn load_from_string_recovering(
+    path: PathBuf,
+    source: &str,
+  ) -> (PackageJson, Vec<PackageJsonDiagnostic>) {
+    if source.trim().is_empty() {
+      return (PackageJson::empty(path), Vec::new());
+    }
+    if let Ok(value) = crate::parse_json_value(source) {
+      return recovered(path, value, Vec::new());
+    }
+
+    let mut parser = RecoveringParser {
+      source,
+      pos: 0,
+      path: Vec::new(),
+      diagnostics: Vec::new(),
+    };
+    let value = parser.parse_value(0).unwrap_or(Value::Null);
+    parser.skip_trivia();
+    if parser.pos < source.len() {
+      parser.error(parser.pos, "Unexpected content after the end");
+    }
+    recovered(path, value, parser.diagnostics)
+  }
+}
+
+fn recovered(
+  path: PathBuf,
+  value: Value,
+  mut diagnostics: Vec<PackageJsonDiagnostic>,
+) -> (PackageJson, Vec<PackageJsonDiagnostic>) {
+  let package_json = PackageJson::load_from_value(path, value);
+  diagnostics.extend(package_json.mixed_exports_keys_diagnostic());
+  (package_json, diagnostics)
+}
+
+struct RecoveringParser<'a> {
+  source: &'a str,
+  pos: usize,
+  path: Vec<String>,
+  diagnostics: Vec<PackageJsonDiagnostic>,
+}
+
+impl<'a> RecoveringParser<'a> {
+  fn peek(&self) -> Option<u8> {
+    self.source.as_bytes().get(self.pos).copied()
+  }
+
+  fn rest(&self) -> &'a str {
+    &self.source[self.pos..]
+  }
+
+  fn error(&mut self, offset: usize, message: &'static str) {
+    self.diagnostics.push(PackageJsonDiagnostic {
+      pointer: json_pointer(&self.path),
+      kind: PackageJsonDiagnosticKind::SyntaxError { offset, message },
+    });
+  }
+
+  fn skip_char(&mut self) {
+    let len = self.rest().chars().next().map_or(0, char::len_utf8);
+    self.pos += len;
+  }
+
+  fn skip_trivia(&mut self) {
+    loop {
+      let rest = self.rest();
+      let trimmed = rest.trim_start();
+      self.pos += rest.len() - trimmed.len();
+      if trimmed.starts_with("//") {
+        self.error(self.pos, "Comments aren't allowed");
+        self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+      } else if let Some(comment) = trimmed.strip_prefix("/*") {
+        self.error(self.pos, "Comments aren't allowed");
+        self.pos += comment.find("*/").map_or(trimmed.len(), |i| i + 4);
+      } else {
+        break;
+      }
+    }
+  }
+
+  /// Takes an unquoted word (ex. `true` or an unquoted key).
+  fn take_word(&mut self) -> &'a str {
+    let rest = self.rest();
+    let len = rest
+      .find(|c: char| c.is_whitespace() || "{}[],:\"'/".contains(c))
+      .unwrap_or(rest.len());
+    self.pos += len;
+    &rest[..len]
+  }
+
+  fn parse_value(&mut self, depth: usize) -> Option<Value> {
+    self.skip_trivia();
+    let start = self.pos;
+    match self.peek() {
+      None => {
+        self.error(start, "Expected a value");
+        None
+      }
+      Some(b'{' | b'[') if depth >= MAX_DEPTH => {
+        self.error(start, "Exceeded the maximum nesting depth");
+        self.pos = self.source.len();
+        None
+      }
+      Some(b'{') => Some(Value::Object(self.parse_object(depth))),
+      Some(b'[') => Some(Value::Array(self.parse_array(depth))),
+      Some(b'"' | b'\'') => Some(Value::String(self.parse_string())),
+      Some(b'}' | b']' | b',' | b':') => {
+        self.error(start, "Expected a value");
+        None
+      }
+      Some(_) => match self.take_word() {
+        "" => {
+          self.error(start, "Unexpected character");
+          self.skip_char();
+          None
+        }
+        "true" => Some(Value::Bool(true)),
+        "false" => Some(Value::Bool(false)),
+        "null" => Some(Value::Null),
+        word if word.starts_with(|c: char| c == '-' || c.is_ascii_digit()) => {
+          match serde_json::from_str::<Value>(word) {
+            Ok(value @ Value::Number(_)) => Some(value),
+            _ => {
+              self.error(start, "Invalid number");
+              None
+            }
+          }
+        }
+        word => {
+          self.error(start, "Missing quotes around string");
+          Some(Value::String(word.to_string()))
+        }
+      },
+    }
+  }
+
+  fn parse_string(&mut self) -> String {
+    let start = self.pos;
+    let quote = self.source.as_bytes()[start] as char;
+    if quote == '\'' {
+      self.error(start, "Strings must use double quotes");
+    }
+    let content_start = start + 1;
+    let mut chars = self.source[content_start..].char_indices();
+    let mut content_end = None;
+    while let Some((i, c)) = chars.next() {
+      match c {
+        '\\' => {
+          chars.next();
+        }
+        '\n' => break,
+        c if c == quote => {
+          content_end = Some(content_start + i);
+          break;
+        }
+        _ => {}
+      }
+    }
+    let content = match content_end {
+      Some(end) => {
+        self.pos = end + 1;
+        &self.source[content_start..end]
+      }
+      None => {
+        self.error(start, "Unterminated string");
+        let len = self.source[content_start..]
+          .find('\n')
+          .unwrap_or(self.source.len() - content_start);
+        self.pos = content_start + len;
+        self.source[content_start..self.pos].trim_end()
+      }
+    };
+    let content = if quote == '\'' {
+      content.replace("\\'", "'").replace('"', "\\\"")
+    } else {
+      content.to_string()
+    };
+    match serde_json::from_str(&format!("\"{}\"", content)) {
+      Ok(text) => text,
+      Err(_) => {
+        self.error(start, "Invalid string");
+        content
+      }
+    }
+  }
+
+  fn parse_object(&mut self, depth: usize) -> Map<String, Value> {
+    let start = self.pos;
+    self.pos += 1;
+    let mut map = Map::new();
+    loop {
+      self.skip_trivia();
+      let key_start = self.pos;
+      let key = match self.peek() {
+        None => {
+          self.error(start, "Unterminated object");
+          break;
+        }
+        Some(b'}') => {
+          self.pos += 1;
+          break;
+        }
+        Some(b']') => {
+          self.error(key_start, "Expected '}'");
+          break;
+        }
+        Some(b',') => {
+          self.error(key_start, "Unexpected comma");
+          self.pos += 1;
+          continue;
+        }
+        Some(b'"' | b'\'') => self.parse_string(),
+        Some(_) => match self.take_word() {
+          "" => {
+            self.error(key_start, "Expected a property name");
+            self.skip_char();
+            continue;
+          }
+          word => {
+            self.error(key_start, "Missing quotes around property name");
+            word.to_string()
+          }
+        },
+      };
+      self.skip_trivia();
+      if self.peek() == Some(b':') {
+        self.pos += 1;
+      } else {
+        self.error(self.pos, "Expected a colon");
+      }
+      self.path.push(key);
+      let value = self.parse_value(depth + 1);
+      let key = self.path.pop().unwrap();
+      if let Some(value) = value {
+        map.insert(key, value);
+      }
+      self.parse_separator(b'}');
+    }
+    map
+  }
+
+  fn parse_array(&mut self, depth: usize) -> Vec<Value> {
+    let start = self.pos;
+    self.pos += 1;
+    let mut items = Vec::new();
+    loop {
+      self.skip_trivia();
+      let item_start = self.pos;
+      match self.peek() {
+        None => {
+          self.error(start, "Unterminated array");
+          break;
+        }
+        Some(b']') => {
+          self.pos += 1;
+          break;
+        }
+        Some(b'}') => {
+          self.error(item_start, "Expected ']'");
+          break;
+        }
+        Some(b',') => {
+          self.error(item_start, "Unexpected comma");
+          self.pos += 1;
+          continue;
+        }
+        Some(_) => {}
+      }
+      self.path.push(items.len().to_string());
+      let value = self.parse_value(depth + 1);
+      self.path.pop();
+      match value {
+        Some(value) => items.push(value),
+        // ensure progress
+        None if self.pos == item_start => self.skip_char(),
+        None => {}
+      }
+      self.parse_separator(b']');
+    }
+    items
+  }
+
+  fn parse_separator(&mut self, close: u8) {
+    self.skip_trivia();
+    match self.peek() {
+      Some(b',') => {
+        let comma_start = self.pos;
+        self.pos += 1;
+        self.skip_trivia();
+        if self.peek() == Some(close) {
+          self.error(comma_start, "Trailing commas aren't allowed");
+        }
+      }
+      Some(c) if c == close => {}
+      None => {}
+      Some(_) => self.error(self.pos, "Expected a comma"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn recover(source: &str) -> (PackageJson, Vec<(String, &'static str)>) {
+    let (package_json, diagnostics) = PackageJson::load_from_string_recovering(
+      PathBuf::from("/package.json"),
+      source,
+    );
+    let errors = diagnostics
+      .into_iter()
+      .map(|d| match d.kind {
+        PackageJsonDiagnosticKind::SyntaxError { message, .. } => {
+          (d.pointer, message)
+        }
+        kind => (d.pointer, kind.code()),
+      })
+      .collect();
+    (package_json, errors)
+  }
+
+  #[test]
+  fn recovers_from_syntax_errors() {
+    let (package_json, errors) = recover(
+      r#"{
+  // comment
+  name: "pkg",
+  "version": '1.0.0',
+  "dependencies": {
+    "a": "^1.0.0",
+    "b":
+  },
+  "workspaces": ["packages/*",],
+  "main": "./main.js"
+  "types": "./main.d.ts
+"#,
+    );
+    assert_eq!(package_json.name.as_deref(), Some("pkg"));
+    assert_eq!(package_json.version.as_deref(), Some("1.0.0"));
+    assert_eq!(
+      package_json
+        .dependencies
+        .as_ref()
+        .unwrap()
+        .keys()
+        .collect::<Vec<_>>(),
+      vec!["a"]
+    );
+    assert_eq!(
+      package_json.workspaces.as_deref(),
+      Some(&["packages/*".to_string()][..])
+    );
+    assert_eq!(
+      package_json.main(crate::NodeModuleKind::Cjs),
+      Some("./main.js")
+    );
+    assert_eq!(package_json.types.as_deref(), Some("./main.d.ts"));
+    assert_eq!(
+      errors,
+      vec![
+        ("".to_string(), "Comments aren't allowed"),
+        ("".to_string(), "Missing quotes around property name"),
+        ("/version".to_string(), "Strings must use double quotes"),
+        ("/dependencies/b".to_string(), "Expected a value"),
+        ("/workspaces".to_string(), "Trailing commas aren't allowed"),
+        ("".to_string(), "Expected a comma"),
+        ("/types".to_string(), "Unterminated string"),
+        ("".to_string(), "Unterminated object"),
+      ]
+    );
+  }
+
+  #[test]
+  fn recovering_valid_source() {
+    let (package_json, errors) = recover(r#"{ "name": "pkg" }"#);
+    assert_eq!(package_json.name.as_deref(), Some("pkg"));
+    assert!(errors.is_empty());
+
+    let (package_json, errors) = recover("{ \"name\": \"pkg\" } }");
+    assert_eq!(package_json.name.as_deref(), Some("pkg"));
+    assert_eq!(
+      errors,
+      vec![("".to_string(), "Unexpected content after the end")]
+    );
+  }
+
+  #[test]
+  fn recovering_mixed_exports_keys() {
+    let source = r#"{ "exports": { ".": "./a.js", "import": "./b.js" } }"#;
+    let (package_json, errors) = recover(source);
+    assert_eq!(package_json.exports, None);
+    assert_eq!(errors, vec![("/exports".to_string(), "mixed-exports-keys")]);
+
+    let (package_json, errors) =
+      recover(&format!("{} // comment", source.replace(" }", ", }")));
+    assert_eq!(package_json.exports, None);
+    assert_eq!(
+      errors,
+      vec![
+        ("/exports".to_string(), "Trailing commas aren't allowed"),
+        ("".to_string(), "Trailing commas aren't allowed"),
+        ("".to_string(), "Comments aren't allowed"),
+        ("/exports".to_string(), "mixed-exports-keys"),
+      ]
+    );
+  }
+}
@@ -0,0 +1,947 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use boxed_error::Boxed;
+use deno_error::builtin_classes;
+use deno_error::JsError;
+use deno_error::JsErrorClass;
+use deno_semver::Version;
+use serde_json::Value;
+
+use crate::exports;
+use crate::exports::ResolvedTarget;
+use crate::exports::TargetError;
+use crate::PackageJson;
+use crate::PackageJsonValueMapRc;
+
+#[derive(Debug, Clone, JsError, PartialEq, Eq, Boxed)]
+pub struct PackageJsonResolveError(pub Box<PackageJsonResolveErrorKind>);
+
+/// An error resolving the "exports" or "imports", with Node's error codes
+/// and messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageJsonResolveErrorKind {
+  PathNotExported {
+    package_json_path: PathBuf,
+    subpath: String,
+  },
+  ImportNotDefined {
+    package_json_path: PathBuf,
+    specifier: String,
+  },
+  InvalidTarget {
+    package_json_path: PathBuf,
+    /// The matched key (ex. `./features/*`).
+    key: String,
+    /// The target as JSON.
+    target: String,
+    is_imports: bool,
+  },
+  InvalidModuleSpecifier {
+    package_json_path: PathBuf,
+    request: String,
+    key: String,
+    is_imports: bool,
+  },
+}
+
+impl PackageJsonResolveErrorKind {
+  /// Node's error code (ex. `ERR_PACKAGE_PATH_NOT_EXPORTED`).
+  pub fn code(&self) -> &'static str {
+    match self {
+      Self::PathNotExported { .. } => "ERR_PACKAGE_PATH_NOT_EXPORTED",
+      Self::ImportNotDefined { .. } => "ERR_PACKAGE_IMPORT_NOT_DEFINED",
+      Self::InvalidTarget { .. } => "ERR_INVALID_PACKAGE_TARGET",
+      Self::InvalidModuleSpecifier { .. } => "ERR_INVALID_MODULE_SPECIFIER",
+    }
+  }
+
+  /// Formats the message the way Node does, including the module the
+  /// resolution was done from when provided.
+  pub fn to_node_message(&self, maybe_referrer: Option<&str>) -> String {
+    let imported_from = maybe_referrer
+      .map(|referrer| format!(" imported from {}", referrer))
+      .unwrap_or_default();
+    match self {
+      Self::PathNotExported {
+        package_json_path,
+        subpath,
+      } => {
+        if subpath == "." {
+          format!(
+            "No \"exports\" main defined in {}{}",
+            package_json_path.display(),
+            imported_from
+          )
+        } else {
+          format!(
+            "Package subpath '{}' is not defined by \"exports\" in {}{}",
+            subpath,
+            package_json_path.display(),
+            imported_from
+          )
+        }
+      }
+      Self::ImportNotDefined {
+        package_json_path,
+        specifier,
+      } => format!(
+        "Package import specifier \"{}\" is not defined in package {}{}",
+        specifier,
+        package_json_path.display(),
+        imported_from
+      ),
+      Self::InvalidTarget {
+        package_json_path,
+        key,
+        target,
+        is_imports,
+      } => {
+        let is_relative_error = !is_imports
+          && target.starts_with('"')
+          && target != "\"\""
+          && !target.starts_with("\"./");
+        let message = if key == "." && !is_imports {
+          format!(
+            "Invalid \"exports\" main target {} defined in the package config {}{}",
+            target,
+            package_json_path.display(),
+            imported_from
+          )
+        } else {
+          format!(
+            "Invalid \"{}\" target {} defined for '{}' in the package config {}{}",
+            field_name(*is_imports),
+            target,
+            key,
+            package_json_path.display(),
+            imported_from
+          )
+        };
+        if is_relative_error {
+          format!("{}; targets must start with \"./\"", message)
+        } else {
+          message
+        }
+      }
+      Self::InvalidModuleSpecifier {
+        package_json_path,
+        request,
+        key,
+        is_imports,
+      } => format!(
+        "Invalid module \"{}\" request is not a valid match in pattern \"{}\" for the \"{}\" resolution of {}{}",
+        request,
+        key,
+        field_name(*is_imports),
+        package_json_path.display(),
+        imported_from
+      ),
+    }
+  }
+}
+
+impl std::fmt::Display for PackageJsonResolveErrorKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.to_node_message(None))
+  }
+}
+
+impl std::error::Error for PackageJsonResolveErrorKind {}
+
+impl JsErrorClass for PackageJsonResolveErrorKind {
+  fn get_class(&self) -> &'static str {
+    match self {
+      Self::PathNotExported { .. } | Self::InvalidTarget { .. } => {
+        builtin_classes::GENERIC_ERROR
+      }
+      Self::ImportNotDefined { .. } | Self::InvalidModuleSpecifier { .. } => {
+        builtin_classes::TYPE_ERROR
+      }
+    }
+  }
+
+  fn get_message(&self) -> Cow<'static, str> {
+    self.to_string().into()
+  }
+
+  fn get_additional_properties(
+    &self,
+  ) -> Option<Vec<(Cow<'static, str>, Cow<'static, str>)>> {
+    Some(vec![("code".into(), self.code().into())])
+  }
+}
+
+type ExportResolution = Result<String, PackageJsonResolveError>;
+
+/// Memoized "exports" resolutions keyed by the subpath and conditions.
+#[derive(Debug, Default)]
+pub(crate) struct ExportResolutionCache(Mutex<ExportResolutionCacheInner>);
+
+#[derive(Debug, Default)]
+struct ExportResolutionCacheInner {
+  /// The "exports" the resolutions are for. Holding a reference makes
+  /// editing the field with `make_mut` clone it, which invalidates the
+  /// resolutions.
+  exports: Option<PackageJsonValueMapRc>,
+  resolutions: HashMap<String, ExportResolution>,
+}
+
+/// Clones start with an empty cache.
+impl Clone for ExportResolutionCache {
+  fn clone(&self) -> Self {
+    Self::default()
+  }
+}
+
+impl ExportResolutionCache {
+  fn get_or_insert_with(
+    &self,
+    exports: &PackageJsonValueMapRc,
+    subpath: &str,
+    conditions: &[&str],
+    types_version: Option<&Version>,
+    resolve: impl FnOnce() -> ExportResolution,
+  ) -> ExportResolution {
+    let mut key = subpath.to_string();
+    for condition in conditions {
+      key.push('\0');
+      key.push_str(condition);
+    }
+    if let Some(version) = types_version {
+      key.push_str("\0types@");
+      key.push_str(&version.to_string());
+    }
+    {
+      let mut inner = self.0.lock().unwrap();
+      #[allow(clippy::disallowed_types)]
+      let is_same_exports = inner
+        .exports
+        .as_ref()
+        .is_some_and(|cached| crate::sync::MaybeArc::ptr_eq(cached, exports));
+      if !is_same_exports {
+        inner.exports = Some(exports.clone());
+        inner.resolutions.clear();
+      } else if let Some(resolution) = inner.resolutions.get(&key) {
+        return resolution.clone();
+      }
+    }
+    let resolution = resolve();
+    let mut inner = self.0.lock().unwrap();
+    #[allow(clippy::disallowed_types)]
+    if inner
+      .exports
+      .as_ref()
+      .is_some_and(|cached| crate::sync::MaybeArc::ptr_eq(cached, exports))
+    {
+      inner.resolutions.insert(key, resolution.clone());
+    }
+    resolution
+  }
+}
+
+fn field_name(is_imports: bool) -> &'static str {
+  if is_imports {
+    "imports"
+  } else {
+    "exports"
+  }
+}
+
+/// The environment being resolved for, which selects between the
+/// "development" and "production" conditions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageJsonEnv {
+  Development,
+  Production,
+}
+
+impl PackageJsonEnv {
+  pub fn condition(self) -> &'static str {
+    match self {
+      PackageJsonEnv::Development => "development",
+      PackageJsonEnv::Production => "production",
+    }
+  }
+}
+
+/// Options shared by the resolution methods of `PackageJson`.
+#[derive(Debug, Clone, Copy)]
+pub struct PackageJsonResolutionOptions<'a> {
+  /// Fields to check in order for the package entrypoint when there are
+  /// no "exports" (ex. `["browser", "module", "main"]`). Supports "main",
+  /// "module", "jsnext:main", "esnext", "browser", and "types".
+  pub main_fields: &'a [&'a str],
+  /// Conditions to match in "exports", besides "default".
+  pub conditions: &'a [&'a str],
+  /// Whether to apply the file replacements of an object "browser" field.
+  pub browser: bool,
+  /// Environment condition to match in addition to `conditions`.
+  pub env: Option<PackageJsonEnv>,
+  /// Version of TypeScript to match versioned types conditions (ex.
+  /// `types@>=5.2`) against. They never match without it.
+  pub types_version: Option<&'a Version>,
+  /// Whether the "module" field is the entrypoint for the "import"
+  /// condition regardless of the "type" field, like bundlers do.
+  /// Otherwise it's only used for "import" in ES module packages.
+  pub module_for_import: bool,
+}
+
+impl Default for PackageJsonResolutionOptions<'_> {
+  fn default() -> Self {
+    Self {
+      main_fields: &["main"],
+      conditions: crate::DEFAULT_NODE_CONDITIONS,
+      browser: false,
+      env: None,
+      types_version: None,
+      module_for_import: false,
+    }
+  }
+}
+
+impl PackageJsonResolutionOptions<'static> {
+  /// Resolution the way Node.js does it.
+  pub fn node(env: Option<PackageJsonEnv>) -> Self {
+    Self {
+      env,
+      ..Default::default()
+    }
+  }
+
+  /// Resolution the way Deno does it.
+  pub fn deno(env: Option<PackageJsonEnv>) -> Self {
+    Self {
+      conditions: crate::DENO_CONDITIONS,
+      env,
+      ..Default::default()
+    }
+  }
+
+  /// Resolution the way bundlers targeting browsers do it.
+  pub fn browser(env: Option<PackageJsonEnv>) -> Self {
+    Self {
+      main_fields: &["browser", "module", "main"],
+      conditions: crate::BROWSER_CONDITIONS,
+      browser: true,
+      env,
+      types_version: None,
+      module_for_import: true,
+    }
+  }
+}
+
+impl PackageJsonResolutionOptions<'_> {
+  /// The conditions to match, including the environment's condition.
+  fn all_conditions(&self) -> Cow<[&str]> {
+    match self.env {
+      Some(env) => {
+        let mut conditions = self.conditions.to_vec();
+        conditions.push(env.condition());
+        Cow::Owned(conditions)
+      }
+      None => Cow::Borrowed(self.conditions),
+    }
+  }
+}
+
+impl PackageJson {
+  /// Resolves the package entrypoint from the configured main fields.
+  ///
+  /// This doesn't take "exports" into account. Use `resolve_export(".")`
+  /// for that.
+  pub fn resolve_main(
+    &self,
+    options: &PackageJsonResolutionOptions,
+  ) -> Option<&str> {
+    let main = options.main_fields.iter().find_map(|field| {
+      let value = match *field {
+        "main" => self.main.as_deref(),
+        "module" => self.module.as_deref(),
+        "jsnext:main" => self.jsnext_main.as_deref(),
+        "esnext" => self.esnext.as_deref(),
+        "types" => self.types.as_deref(),
+        "browser" => self.browser.as_ref().and_then(|b| b.as_str()),
+        _ => None,
+      };
+      value.map(|v| v.trim()).filter(|v| !v.is_empty())
+    })?;
+    if options.browser {
+      self.browser_replacement(main).unwrap_or(Some(main))
+    } else {
+      Some(main)
+    }
+  }
+
+  /// Gets the entrypoint for the configured conditions. See
+  /// `main_with_conditions`.
+  pub fn main_with_options(
+    &self,
+    options: &PackageJsonResolutionOptions,
+  ) -> Option<&str> {
+    crate::select_main(
+      &self.typ,
+      self.main.as_deref(),
+      self.module.as_deref(),
+      &options.all_conditions(),
+      options.module_for_import,
+    )
+  }
+
+  /// Resolves a subpath (ex. `.` or `./feature`) of the "exports" for the
+  /// configured conditions, returning the target relative to the package.
+  ///
+  /// Arrays of targets are resolved with Node's fallback semantics, where
+  /// invalid targets are skipped, but other errors are returned right
+  /// away.
+  ///
+  /// Returns `None` when there are no "exports" or when the target is
+  /// replaced with an empty module by the "browser" field.
+  ///
+  /// Results are memoized on the package.json, since the same subpaths
+  /// tend to be resolved many times.
+  pub fn resolve_export(
+    &self,
+    subpath: &str,
+    options: &PackageJsonResolutionOptions,
+  ) -> Result<Option<Cow<str>>, PackageJsonResolveError> {
+    let Some(exports) = self.exports.as_ref() else {
+      return Ok(None);
+    };
+    let conditions = options.all_conditions();
+    let target = self.export_resolutions.get_or_insert_with(
+      exports,
+      subpath,
+      &conditions,
+      options.types_version,
+      || {
+        let target = self.resolve_subpath(
+          exports,
+          subpath,
+          &conditions,
+          options.types_version,
+          false,
+        )?;
+        target.map(Cow::into_owned).ok_or_else(|| {
+          PackageJsonResolveErrorKind::PathNotExported {
+            package_json_path: self.path.to_path_buf(),
+            subpath: subpath.to_string(),
+          }
+          .into_box()
+        })
+      },
+    )?;
+    if options.browser {
+      if let Some(replacement) = self.browser_replacement(&target) {
+        return Ok(replacement.map(Cow::Borrowed));
+      }
+    }
+    Ok(Some(Cow::Owned(target)))
+  }
+
+  /// Resolves a specifier (ex. `#internal`) of the "imports" for the
+  /// configured conditions.
+  ///
+  /// The target is either relative to the package (ex. `./internal.js`)
+  /// or a bare specifier of a dependency (ex. `dep/feature`). Arrays of
+  /// targets are resolved with Node's fallback semantics.
+  pub fn resolve_import(
+    &self,
+    specifier: &str,
+    options: &PackageJsonResolutionOptions,
+  ) -> Result<Cow<str>, PackageJsonResolveError> {
+    let target = match self.imports.as_ref() {
+      Some(imports) => {
+        let conditions = options.all_conditions();
+        self.resolve_subpath(
+          imports,
+          specifier,
+          &conditions,
+          options.types_version,
+          true,
+        )?
+      }
+      None => None,
+    };
+    target.ok_or_else(|| {
+      PackageJsonResolveErrorKind::ImportNotDefined {
+        package_json_path: self.path.to_path_buf(),
+        specifier: specifier.to_string(),
+      }
+      .into_box()
+    })
+  }
+
+  /// Resolves a subpath of the "exports" or "imports", returning `None`
+  /// when it isn't defined.
+  fn resolve_subpath<'a>(
+    &self,
+    map: &'a serde_json::Map<String, Value>,
+    subpath: &str,
+    conditions: &[&str],
+    types_version: Option<&Version>,
+    is_imports: bool,
+  ) -> Result<Option<Cow<'a, str>>, PackageJsonResolveError> {
+    let Some(subpath_match) = exports::match_subpath(map, subpath) else {
+      return Ok(None);
+    };
+    let resolved = exports::resolve_target(
+      subpath_match.target,
+      subpath_match.pattern_match,
+      conditions,
+      types_version,
+      is_imports,
+    )
+    .map_err(|err| {
+      match err {
+        TargetError::InvalidTarget { target } => {
+          PackageJsonResolveErrorKind::InvalidTarget {
+            package_json_path: self.path.to_path_buf(),
+            key: subpath_match.key.to_string(),
+            target,
+            is_imports,
+          }
+        }
+        TargetError::InvalidSubpath => {
+          PackageJsonResolveErrorKind::InvalidModuleSpecifier {
+            package_json_path: self.path.to_path_buf(),
+            request: subpath.to_string(),
+            key: subpath_match.key.to_string(),
+            is_imports,
+          }
+        }
+      }
+      .into_box()
+    })?;
+    match resolved {
+      ResolvedTarget::Path(target) => Ok(Some(target)),
+      ResolvedTarget::Null | ResolvedTarget::NoMatch => Ok(None),
+    }
+  }
+
+  /// Resolves the declaration file of the package entrypoint the way
+  /// TypeScript does, preferring a "types" condition in the "exports" over
+  /// the "types" field.
+  ///
+  /// Versioned types conditions (ex. `types@>=5.2`) are matched in order
+  /// against the configured `types_version`.
+  pub fn resolve_types(
+    &self,
+    options: &PackageJsonResolutionOptions,
+  ) -> Option<Cow<str>> {
+    if self.exports.is_some() {
+      let mut conditions = vec!["types"];
+      conditions.extend(options.all_conditions().iter());
+      return self
+        .resolve_export(
+          ".",
+          &PackageJsonResolutionOptions {
+            conditions: &conditions,
+            env: None,
+            ..*options
+          },
+        )
+        .ok()
+        .flatten();
+    }
+    self
+      .types
+      .as_deref()
+      .map(|types| types.trim())
+      .filter(|types| !types.is_empty())
+      .map(Cow::Borrowed)
+  }
+
+  /// Looks up a file in an object "browser" field, where `Some(None)`
+  /// means the file is replaced with an empty module (`false`).
+  fn browser_replacement(&self, path: &str) -> Option<Option<&str>> {
+    let Some(Value::Object(browser)) = &self.browser else {
+      return None;
+    };
+    let stripped = path.strip_prefix("./").unwrap_or(path);
+    let value = browser
+      .get(path)
+      .or_else(|| browser.get(stripped))
+      .or_else(|| browser.get(&format!("./{}", stripped)))?;
+    match value {
+      Value::String(replacement) => Some(Some(replacement)),
+      Value::Bool(false) => Some(None),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn package_json(value: serde_json::Value) -> PackageJson {
+    PackageJson::load_from_value(PathBuf::from("/package.json"), value)
+  }
+
+  #[test]
+  fn resolves_main() {
+    let package_json = package_json(serde_json::json!({
+      "main": "./main.js",
+      "module": "./module.js",
+      "browser": { "./module.js": "./browser.js", "./main.js": false },
+    }));
+    let options = PackageJsonResolutionOptions::default();
+    assert_eq!(package_json.resolve_main(&options), Some("./main.js"));
+    let options = PackageJsonResolutionOptions {
+      main_fields: &["browser", "module", "main"],
+      browser: true,
+      ..Default::default()
+    };
+    assert_eq!(package_json.resolve_main(&options), Some("./browser.js"));
+    let options = PackageJsonResolutionOptions {
+      main_fields: &["main"],
+      browser: true,
+      ..Default::default()
+    };
+    assert_eq!(package_json.resolve_main(&options), None);
+  }
+
+  #[test]
+  fn resolves_legacy_esm_main_fields() {
+    let package_json = package_json(serde_json::json!({
+      "main": "./main.js",
+      "jsnext:main": "./jsnext.js",
+      "esnext": { "main": "./esnext.js" },
+    }));
+    let options = PackageJsonResolutionOptions {
+      main_fields: &["esnext", "jsnext:main", "main"],
+      ..Default::default()
+    };
+    assert_eq!(package_json.resolve_main(&options), Some("./esnext.js"));
+    let options = PackageJsonResolutionOptions {
+      main_fields: &["module", "jsnext:main", "main"],
+      ..Default::default()
+    };
+    assert_eq!(package_json.resolve_main(&options), Some("./jsnext.js"));
+  }
+
+  #[test]
+  fn main_with_options() {
+    let package_json = package_json(serde_json::json!({
+      "type": "commonjs",
+      "main": "./main.js",
+      "module": "./module.js",
+    }));
+    let node = PackageJsonResolutionOptions::default();
+    assert_eq!(package_json.main_with_options(&node), Some("./main.js"));
+    let options = PackageJsonResolutionOptions {
+      module_for_import: true,
+      ..Default::default()
+    };
+    assert_eq!(
+      package_json.main_with_options(&options),
+      Some("./module.js")
+    );
+    let options = PackageJsonResolutionOptions {
+      conditions: crate::DEFAULT_NODE_REQUIRE_CONDITIONS,
+      module_for_import: true,
+      ..Default::default()
+    };
+    assert_eq!(package_json.main_with_options(&options), Some("./main.js"));
+  }
+
+  #[test]
+  fn resolves_exports_and_types() {
+    let package_json = package_json(serde_json::json!({
+      "types": "./legacy.d.ts",
+      "exports": {
+        ".": {
+          "types": "./index.d.ts",
+          "browser": "./browser.js",
+          "import": "./index.mjs",
+          "default": "./index.js",
+        },
+        "./features/*": {
+          "require": null,
+          "production": "./dist/*.prod.js",
+          "default": ["./dist/*.js"],
+        },
+      },
+    }));
+    let options = PackageJsonResolutionOptions::default();
+    assert_eq!(
+      package_json
+        .resolve_export(".", &options)
+        .unwrap()
+        .as_deref(),
+      Some("./index.mjs")
+    );
+    assert_eq!(
+      package_json
+        .resolve_export(
+          ".",
+          &PackageJsonResolutionOptions {
+            conditions: crate::BROWSER_CONDITIONS,
+            ..Default::default()
+          }
+        )
+        .unwrap()
+        .as_deref(),
+      Some("./browser.js")
+    );
+    assert_eq!(
+      package_json
+        .resolve_export("./features/a", &options)
+        .unwrap()
+        .as_deref(),
+      Some("./dist/a.js")
+    );
+    assert!(package_json.resolve_export("./other", &options).is_err());
+    let production = PackageJsonResolutionOptions::browser(Some(
+      crate::PackageJsonEnv::Production,
+    ));
+    assert_eq!(
+      package_json
+        .resolve_export("./features/a", &production)
+        .unwrap()
+        .as_deref(),
+      Some("./dist/a.prod.js")
+    );
+    assert_eq!(
+      package_json.resolve_types(&options).as_deref(),
+      Some("./index.d.ts")
+    );
+  }
+
+  #[test]
+  fn resolves_versioned_types() {
+    let package_json = package_json(serde_json::json!({
+      "exports": {
+        ".": {
+          "types@>=5.2": "./ts5.2/index.d.ts",
+          "types@>=4.8": "./ts4.8/index.d.ts",
+          "types": "./index.d.ts",
+          "default": "./index.js",
+        },
+      },
+    }));
+    let resolve = |version: Option<&str>| {
+      let version = version.map(|v| Version::parse_standard(v).unwrap());
+      package_json
+        .resolve_types(&PackageJsonResolutionOptions {
+          types_version: version.as_ref(),
+          ..Default::default()
+        })
+        .map(Cow::into_owned)
+    };
+    assert_eq!(
+      resolve(Some("5.4.2")).as_deref(),
+      Some("./ts5.2/index.d.ts")
+    );
+    assert_eq!(
+      resolve(Some("5.0.0")).as_deref(),
+      Some("./ts4.8/index.d.ts")
+    );
+    assert_eq!(resolve(Some("4.0.0")).as_deref(), Some("./index.d.ts"));
+    assert_eq!(resolve(None).as_deref(), Some("./index.d.ts"));
+    // only matched along with the "types" condition
+    let version = Version::parse_standard("5.4.2").unwrap();
+    let options = PackageJsonResolutionOptions {
+      types_version: Some(&version),
+      ..Default::default()
+    };
+    assert_eq!(
+      package_json
+        .resolve_export(".", &options)
+        .unwrap()
+        .as_deref(),
+      Some("./index.js")
+    );
+  }
+
+  #[test]
+  fn resolves_export_fallbacks() {
+    let package_json = package_json(serde_json::json!({
+      "exports": {
+        ".": ["invalid", { "browser": "./browser.js" }, "./index.js"],
+        "./only-invalid": ["invalid", "../outside.js"],
+        "./null-last": ["invalid", null],
+        "./null-first": [null, "invalid"],
+        "./nested": [{ "import": ["node_modules/x.js", "./nested.js"] }],
+        "./features/*": ["./dist/*.js"],
+        "./number": 1,
+      },
+    }));
+    let options = PackageJsonResolutionOptions::default();
+    let resolve = |subpath| {
+      package_json
+        .resolve_export(subpath, &options)
+        .map(|target| target.unwrap().into_owned())
+        .map_err(|err| err.to_string())
+    };
+    assert_eq!(resolve("."), Ok("./index.js".to_string()));
+    assert_eq!(
+      resolve("./only-invalid"),
+      Err("Invalid \"exports\" target \"../outside.js\" defined for './only-invalid' in the package config /package.json; targets must start with \"./\"".to_string())
+    );
+    assert_eq!(
+      resolve("./null-last"),
+      Err("Package subpath './null-last' is not defined by \"exports\" in /package.json".to_string())
+    );
+    assert_eq!(
+      resolve("./null-first"),
+      Err("Invalid \"exports\" target \"invalid\" defined for './null-first' in the package config /package.json; targets must start with \"./\"".to_string())
+    );
+    assert_eq!(resolve("./nested"), Ok("./nested.js".to_string()));
+    assert_eq!(resolve("./features/a"), Ok("./dist/a.js".to_string()));
+    // invalid subpaths aren't skipped
+    assert_eq!(
+      resolve("./features/a/../../secret"),
+      Err("Invalid module \"./features/a/../../secret\" request is not a valid match in pattern \"./features/*\" for the \"exports\" resolution of /package.json".to_string())
+    );
+    assert_eq!(
+      resolve("./number"),
+      Err("Invalid \"exports\" target 1 defined for './number' in the package config /package.json".to_string())
+    );
+  }
+
+  #[test]
+  fn validates_path_segments() {
+    let package_json = package_json(serde_json::json!({
+      "exports": {
+        "./empty": "./dist//index.js",
+        "./dot": "./a/./b.js",
+        "./encoded": "./a/%2E/b.js",
+        "./p/*": "./dist/*.js",
+      },
+    }));
+    let options = PackageJsonResolutionOptions::default();
+    let resolve = |subpath| {
+      package_json
+        .resolve_export(subpath, &options)
+        .map(|target| target.unwrap().into_owned())
+        .map_err(|err| err.code())
+    };
+    // empty segments are only deprecated
+    assert_eq!(resolve("./empty"), Ok("./dist//index.js".to_string()));
+    assert_eq!(resolve("./p/x//y"), Ok("./dist/x//y.js".to_string()));
+    assert_eq!(resolve("./dot"), Err("ERR_INVALID_PACKAGE_TARGET"));
+    assert_eq!(resolve("./encoded"), Err("ERR_INVALID_PACKAGE_TARGET"));
+    assert_eq!(resolve("./p/./x"), Err("ERR_INVALID_MODULE_SPECIFIER"));
+    assert_eq!(
+      resolve("./p/NODE_MODULES/x"),
+      Err("ERR_INVALID_MODULE_SPECIFIER")
+    );
+  }
+
+  #[test]
+  fn resolves_import_fallbacks() {
+    let package_json = package_json(serde_json::json!({
+      "imports": {
+        "#dep": ["node:invalid", "dep/feature"],
+        "#local/*": [{ "require": "./cjs/*.js" }, "./esm/*.js"],
+        "#url": "https://example.com/x.js",
+      },
+    }));
+    let options = PackageJsonResolutionOptions::default();
+    let resolve = |specifier| {
+      package_json
+        .resolve_import(specifier, &options)
+        .map(|target| target.into_owned())
+        .map_err(|err| err.to_string())
+    };
+    assert_eq!(resolve("#dep"), Ok("dep/feature".to_string()));
+    assert_eq!(resolve("#local/a"), Ok("./esm/a.js".to_string()));
+    assert_eq!(
+      resolve("#url"),
+      Err("Invalid \"imports\" target \"https://example.com/x.js\" defined for '#url' in the package config /package.json".to_string())
+    );
+    assert_eq!(
+      resolve("#other"),
+      Err("Package import specifier \"#other\" is not defined in package /package.json".to_string())
+    );
+  }
+
+  #[test]
+  fn memoizes_export_resolutions() {
+    let mut package_json = package_json(serde_json::json!({
+      "exports": {
+        "./a": { "import": "./a.mjs", "default": "./a.js" },
+      },
+    }));
+    let node = PackageJsonResolutionOptions::default();
+    let require = PackageJsonResolutionOptions {
+      conditions: crate::DEFAULT_NODE_REQUIRE_CONDITIONS,
+      ..Default::default()
+    };
+    for _ in 0..2 {
+      assert_eq!(
+        package_json
+          .resolve_export("./a", &node)
+          .unwrap()
+          .as_deref(),
+        Some("./a.mjs")
+      );
+      assert_eq!(
+        package_json
+          .resolve_export("./a", &require)
+          .unwrap()
+          .as_deref(),
+        Some("./a.js")
+      );
+      assert!(package_json.resolve_export("./b", &node).is_err());
+    }
+    assert_eq!(
+      package_json
+        .export_resolutions
+        .0
+        .lock()
+        .unwrap()
+        .resolutions
+        .len(),
+      3
+    );
+
+    // editing the exports invalidates the resolutions
+    let exports = crate::sync::make_mut(package_json.exports.as_mut().unwrap());
+    exports.insert("./b".to_string(), "./b.js".into());
+    assert_eq!(
+      package_json
+        .resolve_export("./b", &node)
+        .unwrap()
+        .as_deref(),
+      Some("./b.js")
+    );
+  }
+
+  #[test]
+  fn resolve_error_codes() {
+    let package_json = package_json(serde_json::json!({
+      "exports": { "./a": "a.js" },
+    }));
+    let options = PackageJsonResolutionOptions::default();
+    let err = package_json.resolve_export(".", &options).unwrap_err();
+    assert_eq!(err.code(), "ERR_PACKAGE_PATH_NOT_EXPORTED");
+    assert_eq!(
+      err.to_node_message(Some("/main.js")),
+      "No \"exports\" main defined in /package.json imported from /main.js"
+    );
+    let err = package_json.resolve_export("./a", &options).unwrap_err();
+    assert_eq!(err.get_class(), "Error");
+    assert_eq!(
+      err.get_additional_properties(),
+      Some(vec![("code".into(), "ERR_INVALID_PACKAGE_TARGET".into())])
+    );
+    assert_eq!(
+      err.to_node_message(Some("/main.js")),
+      "Invalid \"exports\" target \"a.js\" defined for './a' in the package config /package.json imported from /main.js; targets must start with \"./\""
+    );
+    let err = package_json.resolve_import("#a", &options).unwrap_err();
+    assert_eq!(err.code(), "ERR_PACKAGE_IMPORT_NOT_DEFINED");
+    assert_eq!(err.get_class(), "TypeError");
+  }
+}
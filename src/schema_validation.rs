@@ -0,0 +1,94 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use serde_json::Value;
+
+use crate::PackageJsonDiagnostic;
+use crate::PackageJsonDiagnosticKind;
+
+/// Validates package.json values against a condensed version of the
+/// SchemaStore package.json schema, which is vendored in this crate.
+///
+/// This catches mistakes the typed parse silently ignores, like a
+/// "scripts" entry that isn't a string. Create the validator once and
+/// reuse it because compiling the schema is relatively expensive.
+pub struct PackageJsonSchemaValidator {
+  validator: jsonschema::Validator,
+}
+
+impl std::fmt::Debug for PackageJsonSchemaValidator {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("PackageJsonSchemaValidator")
+      .finish_non_exhaustive()
+  }
+}
+
+impl Default for PackageJsonSchemaValidator {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl PackageJsonSchemaValidator {
+  pub fn new() -> Self {
+    let schema: Value =
+      serde_json::from_str(include_str!("package_json.schema.json")).unwrap();
+    Self {
+      validator: jsonschema::draft7::new(&schema).unwrap(),
+    }
+  }
+
+  /// Gets the schema violations of a package.json value.
+  pub fn validate(&self, value: &Value) -> Vec<PackageJsonDiagnostic> {
+    self
+      .validator
+      .iter_errors(value)
+      .map(|err| PackageJsonDiagnostic {
+        pointer: err.instance_path.to_string(),
+        kind: PackageJsonDiagnosticKind::SchemaViolation {
+          message: err.to_string(),
+        },
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn validates_against_schema() {
+    let validator = PackageJsonSchemaValidator::new();
+    assert_eq!(
+      validator.validate(&json!({
+        "name": "@scope/pkg",
+        "version": "1.0.0",
+        "type": "module",
+        "author": { "name": "Deno" },
+        "scripts": { "test": "deno test" },
+        "exports": { ".": "./mod.js" },
+      })),
+      vec![]
+    );
+    let diagnostics = validator.validate(&json!({
+      "name": "Invalid Name",
+      "type": "esm",
+      "scripts": { "test": 1 },
+    }));
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|d| (d.code(), d.pointer.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        ("schema-violation", "/name"),
+        ("schema-violation", "/type"),
+        ("schema-violation", "/scripts/test"),
+      ]
+    );
+    assert_eq!(diagnostics[2].message(), "1 is not of type \"string\"");
+  }
+}
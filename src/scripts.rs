@@ -0,0 +1,371 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use crate::PackageJson;
+
+/// A script that npm runs automatically as part of a command (ex.
+/// `prepare` on `npm install`), rather than only through `npm run`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackageJsonLifecycleEvent {
+  Preinstall,
+  Install,
+  Postinstall,
+  Preprepare,
+  Prepare,
+  Postprepare,
+  Prepublish,
+  PrepublishOnly,
+  Prepack,
+  Postpack,
+  Publish,
+  Postpublish,
+  Preversion,
+  Version,
+  Postversion,
+  Dependencies,
+}
+
+impl PackageJsonLifecycleEvent {
+  pub const ALL: [PackageJsonLifecycleEvent; 16] = [
+    Self::Preinstall,
+    Self::Install,
+    Self::Postinstall,
+    Self::Preprepare,
+    Self::Prepare,
+    Self::Postprepare,
+    Self::Prepublish,
+    Self::PrepublishOnly,
+    Self::Prepack,
+    Self::Postpack,
+    Self::Publish,
+    Self::Postpublish,
+    Self::Preversion,
+    Self::Version,
+    Self::Postversion,
+    Self::Dependencies,
+  ];
+
+  /// Gets the event for a script name (ex. `"prepublishOnly"`).
+  pub fn from_name(name: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|event| event.as_str() == name)
+  }
+
+  /// Gets the script name of the event.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Preinstall => "preinstall",
+      Self::Install => "install",
+      Self::Postinstall => "postinstall",
+      Self::Preprepare => "preprepare",
+      Self::Prepare => "prepare",
+      Self::Postprepare => "postprepare",
+      Self::Prepublish => "prepublish",
+      Self::PrepublishOnly => "prepublishOnly",
+      Self::Prepack => "prepack",
+      Self::Postpack => "postpack",
+      Self::Publish => "publish",
+      Self::Postpublish => "postpublish",
+      Self::Preversion => "preversion",
+      Self::Version => "version",
+      Self::Postversion => "postversion",
+      Self::Dependencies => "dependencies",
+    }
+  }
+
+  /// Gets if the event runs when the package is installed, including as
+  /// a dependency, which is when blocked lifecycle scripts matter.
+  pub fn runs_on_install(&self) -> bool {
+    matches!(
+      self,
+      Self::Preinstall | Self::Install | Self::Postinstall | Self::Prepare
+    )
+  }
+}
+
+impl std::fmt::Display for PackageJsonLifecycleEvent {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl PackageJson {
+  /// Gets the scripts that npm would run for `npm run <name>` in order,
+  /// as `(name, command)` pairs.
+  ///
+  /// This is the `pre<name>`, `<name>`, and `post<name>` scripts that
+  /// are defined. When `restart` isn't defined, the `stop` and `start`
+  /// sequences are used in its place like npm does. An empty vector is
+  /// returned when the script doesn't exist.
+  pub fn script_run_sequence(&self, name: &str) -> Vec<(&str, &str)> {
+    let mut sequence = Vec::new();
+    if self.script(name).is_some() {
+      self.push_script_with_hooks(name, &mut sequence);
+    } else if name == "restart"
+      && (self.script("stop").is_some() || self.script("start").is_some())
+    {
+      self.push_script(&format!("pre{}", name), &mut sequence);
+      self.push_script_with_hooks("stop", &mut sequence);
+      self.push_script_with_hooks("start", &mut sequence);
+      self.push_script(&format!("post{}", name), &mut sequence);
+    }
+    sequence
+  }
+
+  /// Gets the install lifecycle scripts (`preinstall`, `install`,
+  /// `postinstall`, and `prepare`) that are defined, in the order npm
+  /// runs them.
+  ///
+  /// These are what package managers execute on install, so this is
+  /// what should be reported when lifecycle scripts are blocked.
+  pub fn install_lifecycle_scripts(&self) -> Vec<(&str, &str)> {
+    let mut scripts = self.lifecycle_scripts();
+    scripts.retain(|(event, _)| event.runs_on_install());
+    // the events are declared in the order npm runs them
+    scripts.sort_by_key(|(event, _)| *event as usize);
+    scripts
+      .into_iter()
+      .map(|(event, command)| (event.as_str(), command))
+      .collect()
+  }
+
+  /// Gets the defined scripts that are lifecycle events, in declaration
+  /// order.
+  pub fn lifecycle_scripts(&self) -> Vec<(PackageJsonLifecycleEvent, &str)> {
+    self
+      .scripts
+      .iter()
+      .flat_map(|scripts| scripts.iter())
+      .filter_map(|(name, command)| {
+        let event = PackageJsonLifecycleEvent::from_name(name)?;
+        Some((event, command.as_str()))
+      })
+      .collect()
+  }
+
+  /// Gets the defined scripts that aren't lifecycle events, which are
+  /// only run by the user, in declaration order.
+  pub fn user_scripts(&self) -> Vec<(&str, &str)> {
+    self
+      .scripts
+      .iter()
+      .flat_map(|scripts| scripts.iter())
+      .filter(|(name, _)| PackageJsonLifecycleEvent::from_name(name).is_none())
+      .map(|(name, command)| (name.as_str(), command.as_str()))
+      .collect()
+  }
+
+  fn script(&self, name: &str) -> Option<(&str, &str)> {
+    self
+      .scripts
+      .as_ref()?
+      .get_key_value(name)
+      .map(|(name, command)| (name.as_str(), command.as_str()))
+  }
+
+  fn push_script<'a>(
+    &'a self,
+    name: &str,
+    sequence: &mut Vec<(&'a str, &'a str)>,
+  ) {
+    if let Some(script) = self.script(name) {
+      sequence.push(script);
+    }
+  }
+
+  fn push_script_with_hooks<'a>(
+    &'a self,
+    name: &str,
+    sequence: &mut Vec<(&'a str, &'a str)>,
+  ) {
+    self.push_script(&format!("pre{}", name), sequence);
+    self.push_script(name, sequence);
+    self.push_script(&format!("post{}", name), sequence);
+  }
+}
+
+/// Flags that make a script run in other packages or tolerate missing
+/// scripts, so references using them aren't checked.
+const UNCHECKED_FLAGS: &[&str] = &[
+  "--if-present",
+  "--prefix",
+  "--workspace",
+  "--workspaces",
+  "-w",
+  "-ws",
+  "--filter",
+  "-F",
+  "--recursive",
+  "-r",
+  "--dir",
+  "-C",
+  "--cwd",
+];
+
+/// Gets the names of the scripts that a script command runs through a
+/// package manager (ex. `build` for `npm run build && node .`).
+///
+/// This only understands `npm run`, `npm test` and `pnpm run`, which
+/// always run a package.json script. `yarn run` and `bun run` also run
+/// binaries and files, and `deno task` usually runs a task from
+/// deno.json, so those can't be checked.
+pub(crate) fn referenced_scripts(command: &str) -> Vec<&str> {
+  let mut names = Vec::new();
+  for segment in command.split(['&', '|', ';', '\n', '(', ')']) {
+    let mut tokens = segment
+      .split_whitespace()
+      .map(|token| token.trim_matches(['"', '\'']))
+      // skip environment variables (ex. `NODE_ENV=production npm test`)
+      .skip_while(|token| token.contains('=') || *token == "cross-env");
+    let Some(program) = tokens.next() else {
+      continue;
+    };
+    let args = tokens.collect::<Vec<_>>();
+    if args.iter().any(|arg| {
+      UNCHECKED_FLAGS.contains(arg)
+        || arg
+          .split_once('=')
+          .is_some_and(|(flag, _)| UNCHECKED_FLAGS.contains(&flag))
+    }) {
+      continue;
+    }
+    let mut args = args.into_iter().filter(|arg| !arg.starts_with('-'));
+    let command = args.next();
+    let name = match (program, command) {
+      ("npm", Some("run" | "run-script" | "rum" | "urn"))
+      | ("pnpm", Some("run")) => args.next(),
+      ("npm", Some("test" | "t" | "tst")) => Some("test"),
+      _ => None,
+    };
+    if let Some(name) = name {
+      names.push(name);
+    }
+  }
+  names
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use super::*;
+
+  #[test]
+  fn script_run_sequence() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "scripts": {
+          "prebuild": "echo prebuild",
+          "build": "echo build",
+          "postbuild": "echo postbuild",
+          "test": "echo test",
+          "start": "echo start",
+          "poststart": "echo poststart",
+          "prerestart": "echo prerestart",
+        }
+      }),
+    );
+    assert_eq!(
+      package_json.script_run_sequence("build"),
+      vec![
+        ("prebuild", "echo prebuild"),
+        ("build", "echo build"),
+        ("postbuild", "echo postbuild"),
+      ]
+    );
+    assert_eq!(
+      package_json.script_run_sequence("test"),
+      vec![("test", "echo test")]
+    );
+    assert_eq!(
+      package_json.script_run_sequence("restart"),
+      vec![
+        ("prerestart", "echo prerestart"),
+        ("start", "echo start"),
+        ("poststart", "echo poststart"),
+      ]
+    );
+    assert_eq!(
+      package_json.script_run_sequence("prerestart"),
+      vec![("prerestart", "echo prerestart")]
+    );
+    assert!(package_json.script_run_sequence("missing").is_empty());
+    assert!(package_json.install_lifecycle_scripts().is_empty());
+  }
+
+  #[test]
+  fn lifecycle_scripts() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "scripts": {
+          "build": "tsc",
+          "prepublishOnly": "npm test",
+          "prebuild": "rm -rf dist",
+          "version": "git add -A",
+          "postinstall": "node postinstall.js",
+        }
+      }),
+    );
+    assert_eq!(
+      package_json.lifecycle_scripts(),
+      vec![
+        (PackageJsonLifecycleEvent::PrepublishOnly, "npm test"),
+        (PackageJsonLifecycleEvent::Version, "git add -A"),
+        (
+          PackageJsonLifecycleEvent::Postinstall,
+          "node postinstall.js"
+        ),
+      ]
+    );
+    assert_eq!(
+      package_json.user_scripts(),
+      vec![("build", "tsc"), ("prebuild", "rm -rf dist")]
+    );
+    assert!(PackageJsonLifecycleEvent::Postinstall.runs_on_install());
+    assert!(!PackageJsonLifecycleEvent::Prepack.runs_on_install());
+    assert_eq!(PackageJsonLifecycleEvent::from_name("prepublishonly"), None);
+  }
+
+  #[test]
+  fn test_referenced_scripts() {
+    assert_eq!(
+      referenced_scripts("npm run build && npm test"),
+      vec!["build", "test"]
+    );
+    assert_eq!(
+      referenced_scripts("NODE_ENV=production npm run -s lint:js"),
+      vec!["lint:js"]
+    );
+    assert_eq!(referenced_scripts("pnpm run build"), vec!["build"]);
+    assert!(referenced_scripts("yarn tsc && npm install").is_empty());
+    assert!(referenced_scripts("yarn run tsc; yarn build:css").is_empty());
+    assert!(referenced_scripts("bun run src/index.ts").is_empty());
+    assert!(referenced_scripts("deno task check").is_empty());
+    assert!(referenced_scripts("npm run build --if-present").is_empty());
+    assert!(referenced_scripts("npm run build -w pkg").is_empty());
+    assert!(referenced_scripts("npm run --workspace=pkg build").is_empty());
+  }
+
+  #[test]
+  fn install_lifecycle_scripts() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "scripts": {
+          "prepare": "husky",
+          "build": "tsc",
+          "postinstall": "node postinstall.js",
+          "preinstall": "node preinstall.js",
+        }
+      }),
+    );
+    assert_eq!(
+      package_json.install_lifecycle_scripts(),
+      vec![
+        ("preinstall", "node preinstall.js"),
+        ("postinstall", "node postinstall.js"),
+        ("prepare", "husky"),
+      ]
+    );
+  }
+}
@@ -0,0 +1,393 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::path::PathBuf;
+
+use deno_error::JsError;
+use deno_semver::package::PackageReq;
+use deno_semver::StackString;
+use deno_semver::VersionReq;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::PackageJson;
+use crate::PackageJsonBrowserslist;
+use crate::PackageJsonBundleDependencies;
+use crate::PackageJsonDepValue;
+use crate::PackageJsonDepWorkspaceReq;
+use crate::PackageJsonDeps;
+use crate::PackageJsonDepsMap;
+use crate::PackageJsonPackageManager;
+use crate::PackageJsonStringMapRc;
+use crate::PackageJsonStringsRc;
+use crate::PackageJsonValueMapRc;
+
+/// Bumped whenever the layout of the snapshot changes.
+const SNAPSHOT_VERSION: u8 = 2;
+
+#[derive(Debug, Error, JsError)]
+#[class(generic)]
+pub enum PackageJsonSnapshotError {
+  #[error("Unsupported package.json snapshot version {found} (expected {SNAPSHOT_VERSION}).")]
+  UnsupportedVersion { found: u8 },
+  #[error("Failed encoding package.json snapshot.")]
+  Encode(#[source] rmp_serde::encode::Error),
+  #[error("Failed decoding package.json snapshot.")]
+  Decode(#[source] rmp_serde::decode::Error),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+  path: PathBuf,
+  exports: Option<PackageJsonValueMapRc>,
+  imports: Option<PackageJsonValueMapRc>,
+  bin: Option<Value>,
+  main: Option<String>,
+  module: Option<String>,
+  name: Option<String>,
+  version: Option<String>,
+  private: Option<bool>,
+  typ: String,
+  types: Option<String>,
+  browser: Option<Value>,
+  dependencies: Option<PackageJsonStringMapRc>,
+  dev_dependencies: Option<PackageJsonStringMapRc>,
+  optional_dependencies: Option<PackageJsonStringMapRc>,
+  scripts: Option<PackageJsonStringMapRc>,
+  workspaces: Option<PackageJsonStringsRc>,
+  bundle_dependencies: Option<SnapshotBundleDependencies>,
+  repository: Option<Value>,
+  bugs: Option<Value>,
+  author: Option<Value>,
+  jsnext_main: Option<String>,
+  esnext: Option<String>,
+  unpkg: Option<String>,
+  jsdelivr: Option<String>,
+  browserslist: Option<SnapshotBrowserslist>,
+  shadowed_types: Option<String>,
+  os: Option<Vec<String>>,
+  cpu: Option<Vec<String>>,
+  package_manager: Option<String>,
+  engines: Option<IndexMap<String, String>>,
+  peer_dependencies: Option<PackageJsonStringMapRc>,
+  peer_dependencies_meta: Option<Map<String, Value>>,
+  pnpm: Option<Value>,
+  overrides: Option<Map<String, Value>>,
+  url: Option<String>,
+  lenient_dep_schemes: bool,
+  is_empty_source: bool,
+  has_mixed_exports_keys: bool,
+  unknown_fields: Vec<String>,
+  present_fields: u64,
+  resolved_deps: Option<SnapshotDeps>,
+}
+
+#[derive(Serialize, Deserialize)]
+enum SnapshotBundleDependencies {
+  All,
+  Names(Vec<String>),
+}
+
+#[derive(Serialize, Deserialize)]
+enum SnapshotBrowserslist {
+  Queries(Vec<String>),
+  Envs(IndexMap<String, Vec<String>>),
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotDeps {
+  dependencies: Vec<(String, SnapshotDepValue)>,
+  dev_dependencies: Vec<(String, SnapshotDepValue)>,
+}
+
+#[derive(Serialize, Deserialize)]
+enum SnapshotDepValue {
+  Req(PackageReq),
+  WorkspaceTilde,
+  WorkspaceCaret,
+  WorkspaceVersionReq(VersionReq),
+  Raw { scheme: String, value: String },
+}
+
+impl PackageJson {
+  /// Serializes the package.json, including its resolved dependencies,
+  /// to a compact binary form that can be restored with
+  /// `PackageJson::from_snapshot` without parsing any JSON.
+  ///
+  /// Dependencies with errors aren't stored and are resolved again
+  /// after restoring.
+  pub fn to_snapshot(&self) -> Result<Vec<u8>, PackageJsonSnapshotError> {
+    let snapshot = Snapshot {
+      path: self.path.to_path_buf(),
+      exports: self.exports.clone(),
+      imports: self.imports.clone(),
+      bin: self.bin.clone(),
+      main: self.main.clone(),
+      module: self.module.clone(),
+      name: self.name.clone(),
+      version: self.version.clone(),
+      private: self.private,
+      typ: self.typ.clone(),
+      types: self.types.clone(),
+      browser: self.browser.clone(),
+      dependencies: self.dependencies.clone(),
+      dev_dependencies: self.dev_dependencies.clone(),
+      optional_dependencies: self.optional_dependencies.clone(),
+      scripts: self.scripts.clone(),
+      workspaces: self.workspaces.clone(),
+      bundle_dependencies: self.bundle_dependencies.as_ref().map(|deps| {
+        match deps {
+          PackageJsonBundleDependencies::All => SnapshotBundleDependencies::All,
+          PackageJsonBundleDependencies::Names(names) => {
+            SnapshotBundleDependencies::Names(names.clone())
+          }
+        }
+      }),
+      repository: self.repository.clone(),
+      bugs: self.bugs.clone(),
+      author: self.author.clone(),
+      jsnext_main: self.jsnext_main.clone(),
+      esnext: self.esnext.clone(),
+      unpkg: self.unpkg.clone(),
+      jsdelivr: self.jsdelivr.clone(),
+      browserslist: self.browserslist.as_ref().map(|browserslist| {
+        match browserslist {
+          PackageJsonBrowserslist::Queries(queries) => {
+            SnapshotBrowserslist::Queries(queries.clone())
+          }
+          PackageJsonBrowserslist::Envs(envs) => {
+            SnapshotBrowserslist::Envs(envs.clone())
+          }
+        }
+      }),
+      shadowed_types: self.shadowed_types.clone(),
+      os: self.os.clone(),
+      cpu: self.cpu.clone(),
+      package_manager: self.package_manager.as_ref().map(|p| p.to_string()),
+      engines: self.engines.clone(),
+      peer_dependencies: self.peer_dependencies.clone(),
+      peer_dependencies_meta: self.peer_dependencies_meta.clone(),
+      pnpm: self
+        .pnpm
+        .as_ref()
+        .and_then(|pnpm| serde_json::to_value(pnpm).ok()),
+      overrides: self.overrides.clone(),
+      #[cfg(feature = "url")]
+      url: self.url.as_ref().map(|url| url.to_string()),
+      #[cfg(not(feature = "url"))]
+      url: None,
+      lenient_dep_schemes: self.lenient_dep_schemes,
+      is_empty_source: self.is_empty_source,
+      has_mixed_exports_keys: self.has_mixed_exports_keys,
+      unknown_fields: self.unknown_fields.clone(),
+      present_fields: self.present_fields,
+      resolved_deps: snapshot_deps(self.resolve_local_package_json_deps()),
+    };
+    let mut bytes = vec![SNAPSHOT_VERSION];
+    rmp_serde::encode::write(&mut bytes, &snapshot)
+      .map_err(PackageJsonSnapshotError::Encode)?;
+    Ok(bytes)
+  }
+
+  /// Restores a package.json from the output of `to_snapshot`.
+  pub fn from_snapshot(
+    bytes: &[u8],
+  ) -> Result<PackageJson, PackageJsonSnapshotError> {
+    let found = bytes.first().copied().unwrap_or(0);
+    if found != SNAPSHOT_VERSION {
+      return Err(PackageJsonSnapshotError::UnsupportedVersion { found });
+    }
+    let snapshot: Snapshot = rmp_serde::from_slice(&bytes[1..])
+      .map_err(PackageJsonSnapshotError::Decode)?;
+    let resolved_deps = crate::PackageJsonDepsRcCell::default();
+    if let Some(deps) = snapshot.resolved_deps {
+      _ = resolved_deps.set(crate::sync::new_rc(PackageJsonDeps {
+        dependencies: restore_deps_map(deps.dependencies),
+        dev_dependencies: restore_deps_map(deps.dev_dependencies),
+      }));
+    }
+    Ok(PackageJson {
+      path: snapshot.path.into(),
+      exports: snapshot.exports,
+      imports: snapshot.imports,
+      bin: snapshot.bin,
+      main: snapshot.main,
+      module: snapshot.module,
+      name: snapshot.name,
+      version: snapshot.version,
+      private: snapshot.private,
+      typ: snapshot.typ,
+      types: snapshot.types,
+      browser: snapshot.browser,
+      dependencies: snapshot.dependencies,
+      dev_dependencies: snapshot.dev_dependencies,
+      optional_dependencies: snapshot.optional_dependencies,
+      scripts: snapshot.scripts,
+      workspaces: snapshot.workspaces,
+      bundle_dependencies: snapshot.bundle_dependencies.map(
+        |deps| match deps {
+          SnapshotBundleDependencies::All => PackageJsonBundleDependencies::All,
+          SnapshotBundleDependencies::Names(names) => {
+            PackageJsonBundleDependencies::Names(names)
+          }
+        },
+      ),
+      repository: snapshot.repository,
+      bugs: snapshot.bugs,
+      author: snapshot.author,
+      jsnext_main: snapshot.jsnext_main,
+      esnext: snapshot.esnext,
+      unpkg: snapshot.unpkg,
+      jsdelivr: snapshot.jsdelivr,
+      browserslist: snapshot.browserslist.map(
+        |browserslist| match browserslist {
+          SnapshotBrowserslist::Queries(queries) => {
+            PackageJsonBrowserslist::Queries(queries)
+          }
+          SnapshotBrowserslist::Envs(envs) => {
+            PackageJsonBrowserslist::Envs(envs)
+          }
+        },
+      ),
+      shadowed_types: snapshot.shadowed_types,
+      os: snapshot.os,
+      cpu: snapshot.cpu,
+      package_manager: snapshot
+        .package_manager
+        .and_then(|p| PackageJsonPackageManager::parse(&p)),
+      engines: snapshot.engines,
+      peer_dependencies: snapshot.peer_dependencies,
+      peer_dependencies_meta: snapshot.peer_dependencies_meta,
+      pnpm: snapshot
+        .pnpm
+        .and_then(crate::package_extensions::parse_pnpm_config),
+      overrides: snapshot.overrides,
+      #[cfg(feature = "url")]
+      url: snapshot.url.and_then(|url| url::Url::parse(&url).ok()),
+      resolved_deps,
+      export_resolutions: Default::default(),
+      string_pool: None,
+      lenient_dep_schemes: snapshot.lenient_dep_schemes,
+      is_empty_source: snapshot.is_empty_source,
+      has_mixed_exports_keys: snapshot.has_mixed_exports_keys,
+      unknown_fields: snapshot.unknown_fields,
+      extensions: Default::default(),
+      present_fields: snapshot.present_fields,
+    })
+  }
+}
+
+fn snapshot_deps(deps: &PackageJsonDeps) -> Option<SnapshotDeps> {
+  fn snapshot_map(
+    map: &PackageJsonDepsMap,
+  ) -> Option<Vec<(String, SnapshotDepValue)>> {
+    map
+      .iter()
+      .map(|(key, value)| {
+        let value = match value.as_ref().ok()? {
+          PackageJsonDepValue::Req(req) => SnapshotDepValue::Req(req.clone()),
+          PackageJsonDepValue::Workspace(PackageJsonDepWorkspaceReq::Tilde) => {
+            SnapshotDepValue::WorkspaceTilde
+          }
+          PackageJsonDepValue::Workspace(PackageJsonDepWorkspaceReq::Caret) => {
+            SnapshotDepValue::WorkspaceCaret
+          }
+          PackageJsonDepValue::Workspace(
+            PackageJsonDepWorkspaceReq::VersionReq(req),
+          ) => SnapshotDepValue::WorkspaceVersionReq(req.clone()),
+          PackageJsonDepValue::Raw { scheme, value } => SnapshotDepValue::Raw {
+            scheme: scheme.clone(),
+            value: value.clone(),
+          },
+        };
+        Some((key.to_string(), value))
+      })
+      .collect()
+  }
+
+  Some(SnapshotDeps {
+    dependencies: snapshot_map(&deps.dependencies)?,
+    dev_dependencies: snapshot_map(&deps.dev_dependencies)?,
+  })
+}
+
+fn restore_deps_map(
+  entries: Vec<(String, SnapshotDepValue)>,
+) -> PackageJsonDepsMap {
+  entries
+    .into_iter()
+    .map(|(key, value)| {
+      let value = match value {
+        SnapshotDepValue::Req(req) => PackageJsonDepValue::Req(req),
+        SnapshotDepValue::WorkspaceTilde => {
+          PackageJsonDepValue::Workspace(PackageJsonDepWorkspaceReq::Tilde)
+        }
+        SnapshotDepValue::WorkspaceCaret => {
+          PackageJsonDepValue::Workspace(PackageJsonDepWorkspaceReq::Caret)
+        }
+        SnapshotDepValue::WorkspaceVersionReq(req) => {
+          PackageJsonDepValue::Workspace(
+            PackageJsonDepWorkspaceReq::VersionReq(req),
+          )
+        }
+        SnapshotDepValue::Raw { scheme, value } => {
+          PackageJsonDepValue::Raw { scheme, value }
+        }
+      };
+      (StackString::from_string(key), Ok(value))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn round_trips() {
+    let package_json = PackageJson::load_from_string(
+      PathBuf::from("/pkg/package.json"),
+      r#"{
+        "name": "pkg",
+        "type": "module",
+        "exports": { ".": { "import": "./a.js", "default": "./b.js" } },
+        "dependencies": { "a": "^1.0.0", "b": "workspace:^" },
+        "devDependencies": { "c": "npm:d@2" },
+        "bundleDependencies": true
+      }"#,
+    )
+    .unwrap();
+    let bytes = package_json.to_snapshot().unwrap();
+    let restored = PackageJson::from_snapshot(&bytes).unwrap();
+    assert_eq!(
+      serde_json::to_value(&restored).unwrap(),
+      serde_json::to_value(&package_json).unwrap()
+    );
+    assert_eq!(restored.path, package_json.path);
+    assert!(restored.resolved_deps.get().is_some());
+    let deps = restored.resolve_local_package_json_deps();
+    assert_eq!(
+      deps.dependencies,
+      package_json.resolve_local_package_json_deps().dependencies
+    );
+    assert_eq!(
+      deps.dev_dependencies,
+      package_json
+        .resolve_local_package_json_deps()
+        .dev_dependencies
+    );
+  }
+
+  #[test]
+  fn unsupported_version() {
+    assert!(matches!(
+      PackageJson::from_snapshot(&[0]),
+      Err(PackageJsonSnapshotError::UnsupportedVersion { found: 0 })
+    ));
+  }
+}
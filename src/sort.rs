@@ -0,0 +1,206 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::text_edit::reorder_properties_edit;
+use crate::text_edit::source_properties;
+use crate::PackageJson;
+use crate::PackageJsonTextEdit;
+
+/// The conventional order of top level package.json fields (the order
+/// used by sort-package-json). Unlisted fields go at the end.
+pub const PACKAGE_JSON_FIELD_ORDER: &[&str] = &[
+  "$schema",
+  "name",
+  "displayName",
+  "version",
+  "private",
+  "description",
+  "categories",
+  "keywords",
+  "homepage",
+  "bugs",
+  "repository",
+  "funding",
+  "license",
+  "author",
+  "maintainers",
+  "contributors",
+  "publisher",
+  "sideEffects",
+  "type",
+  "imports",
+  "exports",
+  "main",
+  "svelte",
+  "umd:main",
+  "jsdelivr",
+  "unpkg",
+  "module",
+  "source",
+  "jsnext:main",
+  "browser",
+  "react-native",
+  "types",
+  "typesVersions",
+  "typings",
+  "style",
+  "example",
+  "examplestyle",
+  "assets",
+  "bin",
+  "man",
+  "directories",
+  "files",
+  "workspaces",
+  "binary",
+  "scripts",
+  "betterScripts",
+  "contributes",
+  "activationEvents",
+  "husky",
+  "simple-git-hooks",
+  "pre-commit",
+  "commitlint",
+  "lint-staged",
+  "nano-staged",
+  "config",
+  "nodemonConfig",
+  "browserify",
+  "babel",
+  "browserslist",
+  "xo",
+  "prettier",
+  "eslintConfig",
+  "eslintIgnore",
+  "npmpackagejsonlint",
+  "release",
+  "remarkConfig",
+  "stylelint",
+  "ava",
+  "jest",
+  "mocha",
+  "nyc",
+  "tap",
+  "oclif",
+  "resolutions",
+  "dependencies",
+  "devDependencies",
+  "dependenciesMeta",
+  "peerDependencies",
+  "peerDependenciesMeta",
+  "optionalDependencies",
+  "bundledDependencies",
+  "bundleDependencies",
+  "extensionPack",
+  "extensionDependencies",
+  "flat",
+  "packageManager",
+  "engines",
+  "engineStrict",
+  "volta",
+  "languageName",
+  "os",
+  "cpu",
+  "preferGlobal",
+  "publishConfig",
+  "icon",
+  "badges",
+  "galleryBanner",
+  "preview",
+  "markdown",
+  "pnpm",
+];
+
+fn field_rank(key: &str) -> usize {
+  PACKAGE_JSON_FIELD_ORDER
+    .iter()
+    .position(|field| *field == key)
+    .unwrap_or(PACKAGE_JSON_FIELD_ORDER.len())
+}
+
+impl PackageJson {
+  /// Sorts the top level fields of a package.json object in the
+  /// conventional order, keeping unlisted fields in their relative order
+  /// at the end.
+  pub fn sort_fields(package_json: &mut Map<String, Value>) {
+    let mut entries =
+      std::mem::take(package_json).into_iter().collect::<Vec<_>>();
+    entries.sort_by_key(|(key, _)| field_rank(key));
+    package_json.extend(entries);
+  }
+
+  /// Gets the edits that sort the top level fields of the package.json
+  /// source text like `sort_fields`, keeping the text of each field and
+  /// the existing formatting.
+  ///
+  /// Returns no edits when the fields are already sorted.
+  pub fn sort_fields_text_edits(
+    source: &str,
+  ) -> Result<Vec<PackageJsonTextEdit>, serde_json::Error> {
+    let properties = source_properties(source)?;
+    if !source.trim_start().starts_with('{') {
+      return Ok(Vec::new());
+    }
+    let mut sorted = properties.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|property| field_rank(&property.key));
+    Ok(
+      reorder_properties_edit(source, &properties, &sorted)
+        .into_iter()
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::apply_text_edits;
+
+  #[test]
+  fn sorts_fields() {
+    let mut value = serde_json::json!({
+      "dependencies": {},
+      "custom": 1,
+      "version": "1.0.0",
+      "other": 2,
+      "name": "pkg",
+    });
+    PackageJson::sort_fields(value.as_object_mut().unwrap());
+    assert_eq!(
+      value.as_object().unwrap().keys().collect::<Vec<_>>(),
+      vec!["name", "version", "dependencies", "custom", "other"]
+    );
+  }
+
+  #[test]
+  fn sorts_fields_text_edits() {
+    let source = r#"{
+  "scripts": { "test": "deno test" },
+  "version": "1.0.0",
+  "name": "pkg"
+}
+"#;
+    let edits = PackageJson::sort_fields_text_edits(source).unwrap();
+    let sorted = apply_text_edits(source, &edits);
+    assert_eq!(
+      sorted,
+      r#"{
+  "name": "pkg",
+  "version": "1.0.0",
+  "scripts": { "test": "deno test" }
+}
+"#
+    );
+    assert!(PackageJson::sort_fields_text_edits(&sorted)
+      .unwrap()
+      .is_empty());
+    assert!(PackageJson::sort_fields_text_edits("[]")
+      .unwrap()
+      .is_empty());
+    assert!(PackageJson::sort_fields_text_edits("{").is_err());
+  }
+}
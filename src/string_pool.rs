@@ -0,0 +1,72 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use deno_semver::npm::NpmVersionReqParseError;
+use deno_semver::StackString;
+use deno_semver::VersionReq;
+
+#[allow(clippy::disallowed_types)]
+pub type PackageJsonStringPoolRc = crate::sync::MaybeArc<PackageJsonStringPool>;
+
+/// A pool of strings that can be shared between many loaded package.json
+/// files in order to reduce memory usage in large workspaces.
+///
+/// Dependency names and parsed version requirements that are repeated
+/// across manifests will share a single allocation. Only these dependency
+/// strings are shared. Other repeated strings, such as script names, are
+/// stored as `String`s on each `PackageJson` and aren't pooled.
+#[derive(Debug, Default)]
+pub struct PackageJsonStringPool {
+  strings: Mutex<HashSet<StackString>>,
+  version_reqs: Mutex<HashMap<StackString, VersionReq>>,
+}
+
+impl PackageJsonStringPool {
+  /// Gets a shared copy of the provided text.
+  pub fn intern(&self, text: &str) -> StackString {
+    let mut strings = self.strings.lock().unwrap();
+    if let Some(value) = strings.get(text) {
+      return value.clone();
+    }
+    let value = StackString::from(text);
+    strings.insert(value.clone());
+    value
+  }
+
+  /// Parses the npm version requirement, re-using a previous result
+  /// for the same text.
+  pub(crate) fn parse_version_req(
+    &self,
+    text: &str,
+  ) -> Result<VersionReq, NpmVersionReqParseError> {
+    if let Some(version_req) = self.version_reqs.lock().unwrap().get(text) {
+      return Ok(version_req.clone());
+    }
+    let version_req = VersionReq::parse_from_npm(text)?;
+    self
+      .version_reqs
+      .lock()
+      .unwrap()
+      .insert(self.intern(text), version_req.clone());
+    Ok(version_req)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn interns_strings() {
+    let pool = PackageJsonStringPool::default();
+    let long_name = "@some-scope/some-very-long-package-name";
+    let a = pool.intern(long_name);
+    let b = pool.intern(long_name);
+    assert_eq!(a, b);
+    assert_eq!(a.as_ptr(), b.as_ptr());
+    assert_eq!(pool.strings.lock().unwrap().len(), 1);
+  }
+}
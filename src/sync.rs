@@ -5,6 +5,8 @@ pub use inner::*;
 #[cfg(feature = "sync")]
 mod inner {
   #![allow(clippy::disallowed_types)]
+  pub use std::marker::Send as MaybeSend;
+  pub use std::marker::Sync as MaybeSync;
   pub use std::sync::Arc as MaybeArc;
   pub use std::sync::OnceLock as MaybeOnceLock;
 }
@@ -13,6 +15,12 @@ mod inner {
 mod inner {
   pub use std::cell::OnceCell as MaybeOnceLock;
   pub use std::rc::Rc as MaybeArc;
+
+  pub trait MaybeSend {}
+  impl<T: ?Sized> MaybeSend for T {}
+
+  pub trait MaybeSync {}
+  impl<T: ?Sized> MaybeSync for T {}
 }
 
 // ok for constructing
@@ -20,3 +28,24 @@ mod inner {
 pub fn new_rc<T>(value: T) -> MaybeArc<T> {
   MaybeArc::new(value)
 }
+
+// ok for copy-on-write editing
+#[allow(clippy::disallowed_types)]
+pub fn make_mut<T: Clone>(rc: &mut MaybeArc<T>) -> &mut T {
+  MaybeArc::make_mut(rc)
+}
+
+#[cfg(feature = "sync")]
+#[allow(dead_code)]
+fn assert_send_sync() {
+  fn assert<T: Send + Sync>() {}
+  assert::<crate::PackageJsonRc>();
+  assert::<crate::PackageJsonDepsRc>();
+  assert::<crate::PackageJsonLoadError>();
+  assert::<crate::PackageJsonDepValueParseError>();
+  assert::<crate::PackageJsonResolveError>();
+  assert::<crate::PackageJsonDirPathError>();
+  assert::<crate::PackageJsonLoadOptions>();
+  assert::<crate::PackageJsonFieldParsers>();
+  assert::<&dyn crate::PackageJsonCache>();
+}
@@ -0,0 +1,195 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::ops::Range;
+
+use serde::Deserialize;
+use serde_json::value::RawValue;
+
+/// A replacement of a range of the package.json source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageJsonTextEdit {
+  /// Byte range in the source text.
+  pub range: Range<usize>,
+  pub new_text: String,
+}
+
+/// Applies non-overlapping text edits to the source text.
+pub fn apply_text_edits(source: &str, edits: &[PackageJsonTextEdit]) -> String {
+  let mut edits = edits.iter().collect::<Vec<_>>();
+  edits.sort_by_key(|edit| edit.range.start);
+  let mut text = String::with_capacity(source.len());
+  let mut last_end = 0;
+  for edit in edits {
+    text.push_str(&source[last_end..edit.range.start]);
+    text.push_str(&edit.new_text);
+    last_end = edit.range.end;
+  }
+  text.push_str(&source[last_end..]);
+  text
+}
+
+/// A top level property of a JSON object, or item of a JSON array, along
+/// with its location.
+pub(crate) struct SourceProperty {
+  /// Key of the property or index of the item.
+  pub key: String,
+  /// Range of the key, which is empty for array items.
+  pub key_range: Range<usize>,
+  pub value_range: Range<usize>,
+}
+
+impl SourceProperty {
+  /// Range from the start of the key to the end of the value.
+  pub fn range(&self) -> Range<usize> {
+    self.key_range.start..self.value_range.end
+  }
+}
+
+/// Gets the top level properties of the JSON object, or items of the JSON
+/// array, in the source text in the order they appear.
+///
+/// Returns an empty list for other values.
+pub(crate) fn source_properties(
+  source: &str,
+) -> Result<Vec<SourceProperty>, serde_json::Error> {
+  // validate the whole document upfront
+  let root: &RawValue = serde_json::from_str(source)?;
+  let is_object = root.get().starts_with('{');
+  if !is_object && !root.get().starts_with('[') {
+    return Ok(Vec::new());
+  }
+  let offset_of =
+    |text: &str| text.as_ptr() as usize - source.as_ptr() as usize;
+  // the document is valid, so separators can be skipped loosely
+  let skip_separators = |index: usize| {
+    source[index..]
+      .find(|c: char| !c.is_ascii_whitespace() && c != ',' && c != ':')
+      .map_or(source.len(), |offset| index + offset)
+  };
+  let parse_raw = |index: usize| {
+    let mut deserializer = serde_json::Deserializer::from_str(&source[index..]);
+    <&RawValue>::deserialize(&mut deserializer)
+  };
+  let mut properties = Vec::new();
+  let mut index = skip_separators(offset_of(root.get()) + 1);
+  loop {
+    let property = if is_object {
+      if !source[index..].starts_with('"') {
+        break;
+      }
+      let key = parse_raw(index)?;
+      let key_start = offset_of(key.get());
+      let value = parse_raw(skip_separators(key_start + key.get().len()))?;
+      let value_start = offset_of(value.get());
+      SourceProperty {
+        key: serde_json::from_str(key.get())?,
+        key_range: key_start..key_start + key.get().len(),
+        value_range: value_start..value_start + value.get().len(),
+      }
+    } else {
+      if source[index..].starts_with(']') {
+        break;
+      }
+      let value = parse_raw(index)?;
+      let value_start = offset_of(value.get());
+      SourceProperty {
+        key: properties.len().to_string(),
+        key_range: value_start..value_start,
+        value_range: value_start..value_start + value.get().len(),
+      }
+    };
+    index = skip_separators(property.value_range.end);
+    properties.push(property);
+  }
+  Ok(properties)
+}
+
+/// Gets the properties of the object, or items of the array, at the JSON
+/// pointer in the source text.
+pub(crate) fn source_properties_at(
+  source: &str,
+  pointer: &str,
+) -> Option<Vec<SourceProperty>> {
+  let mut properties = source_properties(source).ok()?;
+  for segment in pointer.split('/').skip(1) {
+    let segment = segment.replace("~1", "/").replace("~0", "~");
+    // the last duplicate key wins
+    let property = properties.into_iter().rev().find(|p| p.key == segment)?;
+    properties = source_properties_in(source, property.value_range).ok()?;
+  }
+  Some(properties)
+}
+
+/// Gets the properties of the object, or items of the array, in the range
+/// of the source text, with ranges in the whole source text.
+pub(crate) fn source_properties_in(
+  source: &str,
+  range: Range<usize>,
+) -> Result<Vec<SourceProperty>, serde_json::Error> {
+  let start = range.start;
+  let mut properties = source_properties(&source[range])?;
+  for property in &mut properties {
+    property.key_range =
+      property.key_range.start + start..property.key_range.end + start;
+    property.value_range =
+      property.value_range.start + start..property.value_range.end + start;
+  }
+  Ok(properties)
+}
+
+/// Removes the properties overridden by a later duplicate key, keeping
+/// the document order.
+pub(crate) fn last_occurrences(
+  properties: Vec<SourceProperty>,
+) -> Vec<SourceProperty> {
+  let is_last = (0..properties.len())
+    .map(|i| {
+      let key = &properties[i].key;
+      !properties[i + 1..].iter().any(|p| p.key == *key)
+    })
+    .collect::<Vec<_>>();
+  properties
+    .into_iter()
+    .zip(is_last)
+    .filter_map(|(property, is_last)| is_last.then_some(property))
+    .collect()
+}
+
+/// Gets the edit that removes the property along with the separator
+/// after it, so it must not be the last property.
+pub(crate) fn remove_property_edit(
+  properties: &[SourceProperty],
+  index: usize,
+) -> PackageJsonTextEdit {
+  PackageJsonTextEdit {
+    range: properties[index].range().start..properties[index + 1].range().start,
+    new_text: String::new(),
+  }
+}
+
+/// Gets the edit that reorders the properties in the source text, keeping
+/// the separator between the first two properties.
+pub(crate) fn reorder_properties_edit(
+  source: &str,
+  properties: &[SourceProperty],
+  order: &[&SourceProperty],
+) -> Option<PackageJsonTextEdit> {
+  let is_same_order = properties
+    .iter()
+    .zip(order)
+    .all(|(a, b)| a.key_range == b.key_range);
+  if is_same_order {
+    return None;
+  }
+  let separator =
+    &source[properties[0].range().end..properties[1].range().start];
+  let new_text = order
+    .iter()
+    .map(|property| &source[property.range()])
+    .collect::<Vec<_>>()
+    .join(separator);
+  Some(PackageJsonTextEdit {
+    range: properties[0].range().start..properties.last().unwrap().range().end,
+    new_text,
+  })
+}
@@ -0,0 +1,157 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use deno_semver::RangeBound;
+use deno_semver::StackString;
+use deno_semver::VersionBoundKind;
+use deno_semver::VersionReq;
+
+use crate::PackageJsonDepValue;
+use crate::PackageJsonDepWorkspaceReq;
+use crate::PackageJsonDeps;
+
+/// How strictly a dependency's version requirement pins the version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackageJsonVersionReqKind {
+  /// A single version (ex. `1.2.3` or `=1.2.3`).
+  Exact,
+  /// A caret range (ex. `^1.2.3`).
+  Caret,
+  /// A tilde range (ex. `~1.2.3`).
+  Tilde,
+  /// Any version (ex. `*`, `x`, or an empty string).
+  Wildcard,
+  /// A dist tag (ex. `latest`).
+  Tag,
+  /// Any other range (ex. `>=1 <3`, `1.x || 2.x`, or `1.2.3 - 1.4`).
+  Range,
+}
+
+impl PackageJsonVersionReqKind {
+  pub fn from_version_req(req: &VersionReq) -> Self {
+    let Some(range_set) = req.range() else {
+      return Self::Tag;
+    };
+    let [range] = range_set.0.as_ref() else {
+      return Self::Range;
+    };
+    match (&range.start, &range.end) {
+      (RangeBound::Unbounded, RangeBound::Unbounded) => return Self::Wildcard,
+      (RangeBound::Version(start), RangeBound::Version(end))
+        if start.kind == VersionBoundKind::Inclusive
+          && end.kind == VersionBoundKind::Inclusive
+          && start.version == end.version =>
+      {
+        return Self::Exact
+      }
+      _ => {}
+    }
+    // these are only distinguishable from equivalent ranges (ex. `1.x`)
+    // by how they're written
+    let text = req.version_text().trim();
+    if text.starts_with('^') && !text.contains([' ', '|']) {
+      Self::Caret
+    } else if text.starts_with('~') && !text.contains([' ', '|']) {
+      Self::Tilde
+    } else {
+      Self::Range
+    }
+  }
+
+  /// Gets if the requirement only allows a single version.
+  pub fn is_pinned(self) -> bool {
+    self == Self::Exact
+  }
+}
+
+impl PackageJsonDepValue {
+  /// Classifies the dependency's version requirement.
+  ///
+  /// Returns `None` for raw dependencies, which have no version
+  /// requirement.
+  pub fn version_req_kind(&self) -> Option<PackageJsonVersionReqKind> {
+    Some(match self {
+      PackageJsonDepValue::Req(req) => {
+        PackageJsonVersionReqKind::from_version_req(&req.version_req)
+      }
+      PackageJsonDepValue::Workspace(PackageJsonDepWorkspaceReq::Tilde) => {
+        PackageJsonVersionReqKind::Tilde
+      }
+      PackageJsonDepValue::Workspace(PackageJsonDepWorkspaceReq::Caret) => {
+        PackageJsonVersionReqKind::Caret
+      }
+      PackageJsonDepValue::Workspace(
+        PackageJsonDepWorkspaceReq::VersionReq(req),
+      ) => PackageJsonVersionReqKind::from_version_req(req),
+      PackageJsonDepValue::Raw { .. } => return None,
+    })
+  }
+}
+
+impl PackageJsonDeps {
+  /// Classifies the version requirement of each dependency and dev
+  /// dependency, skipping entries that failed to parse.
+  pub fn version_req_kinds(
+    &self,
+  ) -> impl Iterator<Item = (&StackString, PackageJsonVersionReqKind)> {
+    self
+      .valid()
+      .filter_map(|(_, alias, value)| Some((alias, value.version_req_kind()?)))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::PackageJson;
+
+  #[test]
+  fn classifies_dependencies() {
+    let package_json = PackageJson::load_from_value(
+      PathBuf::from("/package.json"),
+      serde_json::json!({
+        "dependencies": {
+          "exact": "1.2.3",
+          "equals": "=1.2.3",
+          "caret": "^1.2.3",
+          "tilde": "~1.2",
+          "star": "*",
+          "empty": "",
+          "tag": "latest",
+          "x-range": "1.x",
+          "or": "^1 || ^2",
+          "workspace": "workspace:^",
+          "invalid": "https://example.com",
+        },
+        "devDependencies": {
+          "alias": "npm:pkg@>=1 <2",
+        }
+      }),
+    );
+    let kinds = package_json
+      .resolve_local_package_json_deps()
+      .version_req_kinds()
+      .map(|(alias, kind)| (alias.as_str(), kind))
+      .collect::<Vec<_>>();
+    use PackageJsonVersionReqKind::*;
+    assert_eq!(
+      kinds,
+      vec![
+        ("exact", Exact),
+        ("equals", Exact),
+        ("caret", Caret),
+        ("tilde", Tilde),
+        ("star", Wildcard),
+        ("empty", Wildcard),
+        ("tag", Tag),
+        ("x-range", Range),
+        ("or", Range),
+        ("workspace", Caret),
+        ("alias", Range),
+      ]
+    );
+  }
+}
@@ -0,0 +1,215 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::ops::Range;
+
+use serde_json::Value;
+
+use crate::diagnostics::json_pointer;
+use crate::field_set::KNOWN_FIELDS;
+use crate::text_edit::last_occurrences;
+use crate::text_edit::source_properties_in;
+use crate::text_edit::SourceProperty;
+use crate::PackageJson;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PackageJsonWalkOptions {
+  /// Also visits the top level fields that aren't understood by this
+  /// crate (ex. "description" or tool configuration).
+  pub include_unknown_fields: bool,
+}
+
+/// A value found while walking a package.json.
+#[derive(Debug, Clone, Copy)]
+pub struct PackageJsonNode<'a> {
+  /// JSON pointer (RFC 6901) to the value (ex. `/exports/.~1utils`).
+  pub pointer: &'a str,
+  /// Key of the property or index of the array item.
+  pub key: &'a str,
+  /// Byte range of the key in the source text, which is empty for array
+  /// items.
+  pub key_range: &'a Range<usize>,
+  /// Byte range of the value in the source text.
+  pub value_range: &'a Range<usize>,
+  pub value: &'a Value,
+  /// Nesting level, which is 1 for top level fields.
+  pub depth: usize,
+}
+
+/// Receives the values of a package.json in document order.
+pub trait PackageJsonVisitor {
+  /// Called before the values nested in the node. Return `false` to skip
+  /// them.
+  fn enter(&mut self, node: &PackageJsonNode) -> bool {
+    let _ = node;
+    true
+  }
+
+  /// Called after the values nested in the node, unless they were
+  /// skipped.
+  fn exit(&mut self, node: &PackageJsonNode) {
+    let _ = node;
+  }
+}
+
+impl PackageJson {
+  /// Walks the fields of the package.json source text along with their
+  /// locations, descending into objects and arrays.
+  ///
+  /// Only the fields understood by this crate are visited unless
+  /// `include_unknown_fields` is set. Like the loaders, only the last of
+  /// duplicate keys is visited since it's the one with the parsed value.
+  pub fn walk(
+    source: &str,
+    options: PackageJsonWalkOptions,
+    visitor: &mut dyn PackageJsonVisitor,
+  ) -> Result<(), serde_json::Error> {
+    let root: Value = serde_json::from_str(source)?;
+    let Value::Object(map) = &root else {
+      return Ok(());
+    };
+    let properties = source_properties_in(source, 0..source.len())?;
+    for property in last_occurrences(properties) {
+      if !options.include_unknown_fields
+        && !KNOWN_FIELDS.contains(&property.key.as_str())
+      {
+        continue;
+      }
+      if let Some(value) = map.get(&property.key) {
+        let mut path = vec![property.key.clone()];
+        walk_property(source, &property, value, &mut path, visitor)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+fn walk_property(
+  source: &str,
+  property: &SourceProperty,
+  value: &Value,
+  path: &mut Vec<String>,
+  visitor: &mut dyn PackageJsonVisitor,
+) -> Result<(), serde_json::Error> {
+  let pointer = json_pointer(path);
+  let node = PackageJsonNode {
+    pointer: &pointer,
+    key: &property.key,
+    key_range: &property.key_range,
+    value_range: &property.value_range,
+    value,
+    depth: path.len(),
+  };
+  if !visitor.enter(&node) {
+    return Ok(());
+  }
+  if matches!(value, Value::Object(_) | Value::Array(_)) {
+    let children = source_properties_in(source, property.value_range.clone())?;
+    for child in last_occurrences(children) {
+      let child_value = match value {
+        Value::Object(map) => map.get(&child.key),
+        Value::Array(items) => {
+          child.key.parse::<usize>().ok().and_then(|i| items.get(i))
+        }
+        _ => None,
+      };
+      if let Some(child_value) = child_value {
+        path.push(child.key.clone());
+        walk_property(source, &child, child_value, path, visitor)?;
+        path.pop();
+      }
+    }
+  }
+  visitor.exit(&node);
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[derive(Default)]
+  struct Collector {
+    events: Vec<String>,
+  }
+
+  impl PackageJsonVisitor for Collector {
+    fn enter(&mut self, node: &PackageJsonNode) -> bool {
+      self.events.push(format!(
+        "{} {} {:?}",
+        node.depth, node.pointer, node.value_range
+      ));
+      node.pointer != "/scripts"
+    }
+
+    fn exit(&mut self, node: &PackageJsonNode) {
+      self.events.push(format!("exit {}", node.pointer));
+    }
+  }
+
+  #[test]
+  fn walks_fields() {
+    let source = r#"{
+  "name": "pkg",
+  "description": "text",
+  "exports": { "./a/b": ["./a.js", null] },
+  "scripts": { "test": "deno test" }
+}"#;
+    let mut collector = Collector::default();
+    PackageJson::walk(source, Default::default(), &mut collector).unwrap();
+    assert_eq!(
+      collector.events,
+      vec![
+        "1 /name 12..17",
+        "exit /name",
+        "1 /exports 57..86",
+        "2 /exports/.~1a~1b 68..84",
+        "3 /exports/.~1a~1b/0 69..77",
+        "exit /exports/.~1a~1b/0",
+        "3 /exports/.~1a~1b/1 79..83",
+        "exit /exports/.~1a~1b/1",
+        "exit /exports/.~1a~1b",
+        "exit /exports",
+        "1 /scripts 101..124",
+      ]
+    );
+    assert_eq!(&source[68..84], r#"["./a.js", null]"#);
+
+    let mut collector = Collector::default();
+    PackageJson::walk(
+      source,
+      PackageJsonWalkOptions {
+        include_unknown_fields: true,
+      },
+      &mut collector,
+    )
+    .unwrap();
+    assert_eq!(collector.events[2], "1 /description 36..42");
+    assert!(PackageJson::walk("{", Default::default(), &mut collector).is_err());
+  }
+
+  #[test]
+  fn walks_last_duplicate_key() {
+    let source = r#"{
+  "name": "a",
+  "exports": { ".": "./a.js", ".": "./b.js" },
+  "name": "b"
+}"#;
+    let mut collector = Collector::default();
+    PackageJson::walk(source, Default::default(), &mut collector).unwrap();
+    assert_eq!(
+      collector.events,
+      vec![
+        "1 /exports 30..62",
+        "2 /exports/. 52..60",
+        "exit /exports/.",
+        "exit /exports",
+        "1 /name 74..77",
+        "exit /name",
+      ]
+    );
+    assert_eq!(&source[52..60], r#""./b.js""#);
+    assert_eq!(&source[74..77], r#""b""#);
+  }
+}
@@ -0,0 +1,405 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use deno_error::JsError;
+use deno_semver::Version;
+use deno_semver::VersionReq;
+use thiserror::Error;
+
+use crate::diagnostics::json_pointer;
+use crate::text_edit::source_properties_at;
+use crate::PackageJsonDepValue;
+use crate::PackageJsonDepWorkspaceReq;
+use crate::PackageJsonRc;
+use crate::PackageJsonTextEdit;
+use crate::PackageJsonVersionReqKind;
+
+/// The loaded package.json files of the members of a workspace.
+#[derive(Debug, Clone, Default)]
+pub struct PackageJsonWorkspace {
+  members: Vec<PackageJsonRc>,
+}
+
+/// A dependency linked to a workspace member.
+#[derive(Debug, Clone)]
+pub struct PackageJsonWorkspaceDep<'a> {
+  pub member: &'a PackageJsonRc,
+  /// The parsed "version" of the member, if any.
+  pub version: Option<Version>,
+}
+
+/// A dependency specifier of a workspace member to update after bumping
+/// the version of another member.
+#[derive(Debug, Clone)]
+pub struct PackageJsonWorkspaceBumpEdit<'a> {
+  /// The member with the dependency.
+  pub member: &'a PackageJsonRc,
+  /// The dependency field (ex. `"devDependencies"`).
+  pub section: &'static str,
+  pub alias: String,
+  pub old_specifier: String,
+  pub new_specifier: String,
+}
+
+impl PackageJsonWorkspaceBumpEdit<'_> {
+  /// JSON pointer (RFC 6901) to the specifier (ex. `/dependencies/a`).
+  pub fn pointer(&self) -> String {
+    json_pointer(&[self.section, &self.alias])
+  }
+
+  /// Gets the edit of the specifier in the source text of the member's
+  /// package.json.
+  pub fn text_edit(&self, source: &str) -> Option<PackageJsonTextEdit> {
+    let properties =
+      source_properties_at(source, &json_pointer(&[self.section]))?;
+    let property = properties.iter().rev().find(|p| p.key == self.alias)?;
+    Some(PackageJsonTextEdit {
+      range: property.value_range.clone(),
+      new_text: serde_json::Value::from(self.new_specifier.as_str())
+        .to_string(),
+    })
+  }
+}
+
+#[derive(Debug, Error, JsError)]
+#[class(generic)]
+pub enum PackageJsonWorkspaceDepError {
+  #[error("Could not find a workspace member named \"{alias}\".")]
+  MemberNotFound { alias: String },
+  #[error("The workspace member \"{alias}\" is version {version}, which doesn't satisfy {req}.")]
+  VersionMismatch {
+    alias: String,
+    req: String,
+    version: String,
+  },
+  #[error("The workspace member \"{alias}\" has no valid version, which doesn't satisfy {req}.")]
+  MissingVersion { alias: String, req: String },
+}
+
+impl PackageJsonWorkspace {
+  pub fn new(members: Vec<PackageJsonRc>) -> Self {
+    Self { members }
+  }
+
+  pub fn members(&self) -> &[PackageJsonRc] {
+    &self.members
+  }
+
+  /// Gets the member with the provided name.
+  pub fn member(&self, name: &str) -> Option<&PackageJsonRc> {
+    self
+      .members
+      .iter()
+      .find(|member| member.name.as_deref() == Some(name))
+  }
+
+  /// Links a dependency to the workspace member it resolves to.
+  ///
+  /// `workspace:` dependencies must match a member, so a missing member
+  /// or an incompatible version is an error. Like npm, other
+  /// dependencies only link to a member with the same name when its
+  /// version satisfies the requirement, and otherwise `None` is returned
+  /// so they're installed from the registry.
+  pub fn resolve_workspace_dep(
+    &self,
+    alias: &str,
+    req: &PackageJsonDepValue,
+  ) -> Result<Option<PackageJsonWorkspaceDep<'_>>, PackageJsonWorkspaceDepError>
+  {
+    match req {
+      PackageJsonDepValue::Workspace(workspace_req) => {
+        let member = self.member(alias).ok_or_else(|| {
+          PackageJsonWorkspaceDepError::MemberNotFound {
+            alias: alias.to_string(),
+          }
+        })?;
+        let version = member_version(member);
+        if let PackageJsonDepWorkspaceReq::VersionReq(version_req) =
+          workspace_req
+        {
+          let matches = match &version {
+            Some(version) => version_req.matches(version),
+            // a member without a version can only satisfy "*"
+            None => version_req.version_text() == "*",
+          };
+          if !matches {
+            return Err(match &version {
+              Some(version) => PackageJsonWorkspaceDepError::VersionMismatch {
+                alias: alias.to_string(),
+                req: workspace_req.to_string(),
+                version: version.to_string(),
+              },
+              None => PackageJsonWorkspaceDepError::MissingVersion {
+                alias: alias.to_string(),
+                req: workspace_req.to_string(),
+              },
+            });
+          }
+        }
+        Ok(Some(PackageJsonWorkspaceDep { member, version }))
+      }
+      PackageJsonDepValue::Req(package_req) => {
+        let Some(member) = self.member(&package_req.name) else {
+          return Ok(None);
+        };
+        let Some(version) = member_version(member) else {
+          return Ok(None);
+        };
+        if !package_req.version_req.matches(&version) {
+          return Ok(None);
+        }
+        Ok(Some(PackageJsonWorkspaceDep {
+          member,
+          version: Some(version),
+        }))
+      }
+      PackageJsonDepValue::Raw { .. } => Ok(None),
+    }
+  }
+
+  /// Computes the dependency specifiers to update in the other members
+  /// after the member with the provided name is bumped to a new version.
+  ///
+  /// Like lerna, the style of each range is kept, so `^1.0.0` becomes
+  /// `^2.0.0` and `workspace:~1.0.0` becomes `workspace:~2.0.0`. Other
+  /// ranges are only replaced, with a caret range, when they no longer
+  /// match the new version. Wildcards, tags, and the `workspace:^`
+  /// shorthands are left as is.
+  pub fn bump_edits(
+    &self,
+    name: &str,
+    version: &Version,
+  ) -> Vec<PackageJsonWorkspaceBumpEdit<'_>> {
+    let mut edits = Vec::new();
+    for member in &self.members {
+      if member.name.as_deref() == Some(name) {
+        continue;
+      }
+      for (section, deps) in [
+        ("dependencies", member.dependencies.as_deref()),
+        ("devDependencies", member.dev_dependencies.as_deref()),
+        (
+          "optionalDependencies",
+          member.optional_dependencies.as_deref(),
+        ),
+        ("peerDependencies", member.peer_dependencies.as_deref()),
+      ] {
+        for (alias, specifier) in deps.into_iter().flatten() {
+          let Some(new_specifier) =
+            bump_specifier(alias, specifier.trim(), name, version)
+          else {
+            continue;
+          };
+          if new_specifier != *specifier {
+            edits.push(PackageJsonWorkspaceBumpEdit {
+              member,
+              section,
+              alias: alias.clone(),
+              old_specifier: specifier.clone(),
+              new_specifier,
+            });
+          }
+        }
+      }
+    }
+    edits
+  }
+}
+
+/// Gets the specifier of a dependency on the bumped package, or `None`
+/// when the dependency is on another package.
+fn bump_specifier(
+  alias: &str,
+  specifier: &str,
+  name: &str,
+  version: &Version,
+) -> Option<String> {
+  if let Some(range) = specifier.strip_prefix("workspace:") {
+    if alias != name {
+      return None;
+    }
+    let range = match range {
+      "~" | "^" => range.to_string(),
+      range => bump_range(range, version)?,
+    };
+    return Some(format!("workspace:{}", range));
+  }
+  let (dep_name, range) =
+    crate::parse_dep_entry_name_and_raw_version(alias, specifier);
+  if dep_name != name {
+    return None;
+  }
+  let range = bump_range(range, version)?;
+  Some(match specifier.strip_prefix("npm:") {
+    Some(_) => format!("npm:{}@{}", name, range),
+    None => range,
+  })
+}
+
+fn bump_range(range: &str, version: &Version) -> Option<String> {
+  let req = VersionReq::parse_from_npm(range).ok()?;
+  Some(match PackageJsonVersionReqKind::from_version_req(&req) {
+    PackageJsonVersionReqKind::Exact => match range.strip_prefix('=') {
+      Some(_) => format!("={}", version),
+      None => version.to_string(),
+    },
+    PackageJsonVersionReqKind::Caret => format!("^{}", version),
+    PackageJsonVersionReqKind::Tilde => format!("~{}", version),
+    PackageJsonVersionReqKind::Wildcard | PackageJsonVersionReqKind::Tag => {
+      range.to_string()
+    }
+    PackageJsonVersionReqKind::Range => {
+      if req.matches(version) {
+        range.to_string()
+      } else {
+        format!("^{}", version)
+      }
+    }
+  })
+}
+
+fn member_version(member: &PackageJsonRc) -> Option<Version> {
+  Version::parse_from_npm(member.version.as_deref()?).ok()
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+  use crate::PackageJson;
+
+  #[test]
+  fn resolve_workspace_dep() {
+    let member = |name: &str, value: serde_json::Value| -> PackageJsonRc {
+      crate::sync::new_rc(PackageJson::load_from_value(
+        PathBuf::from(format!("/{}/package.json", name)),
+        value,
+      ))
+    };
+    let workspace = PackageJsonWorkspace::new(vec![
+      member("a", json!({ "name": "a", "version": "1.2.0" })),
+      member("b", json!({ "name": "b" })),
+    ]);
+    let resolve = |alias: &str, specifier: &str| {
+      let root = PackageJson::load_from_value(
+        PathBuf::from("/package.json"),
+        json!({ "dependencies": { alias: specifier } }),
+      );
+      let deps = root.resolve_local_package_json_deps();
+      let req = deps.get(alias).unwrap().as_ref().unwrap();
+      workspace
+        .resolve_workspace_dep(alias, req)
+        .map(|dep| dep.map(|dep| (dep.member.name.clone(), dep.version)))
+        .map_err(|err| err.to_string())
+    };
+    let version = |text: &str| Some(Version::parse_standard(text).unwrap());
+    assert_eq!(
+      resolve("a", "workspace:^1.0.0"),
+      Ok(Some((Some("a".to_string()), version("1.2.0"))))
+    );
+    assert_eq!(
+      resolve("a", "workspace:~"),
+      Ok(Some((Some("a".to_string()), version("1.2.0"))))
+    );
+    assert_eq!(
+      resolve("b", "workspace:*"),
+      Ok(Some((Some("b".to_string()), None)))
+    );
+    assert_eq!(
+      resolve("a", "workspace:^2.0.0"),
+      Err("The workspace member \"a\" is version 1.2.0, which doesn't satisfy workspace:^2.0.0.".to_string())
+    );
+    assert_eq!(
+      resolve("b", "workspace:1.0.0"),
+      Err("The workspace member \"b\" has no valid version, which doesn't satisfy workspace:1.0.0.".to_string())
+    );
+    assert_eq!(
+      resolve("c", "workspace:*"),
+      Err("Could not find a workspace member named \"c\".".to_string())
+    );
+    assert_eq!(
+      resolve("alias", "npm:a@^1"),
+      Ok(Some((Some("a".to_string()), version("1.2.0"))))
+    );
+    assert_eq!(resolve("a", "^2"), Ok(None));
+    assert_eq!(resolve("b", "^1"), Ok(None));
+  }
+
+  #[test]
+  fn bump_edits() {
+    let source = r#"{
+  "name": "b",
+  "dependencies": {
+    "a": "^1.0.0",
+    "c": "^1.0.0"
+  },
+  "devDependencies": {
+    "a": "workspace:~1.0.0",
+    "alias": "npm:a@1.0.0",
+    "other": "npm:c@1.0.0"
+  },
+  "peerDependencies": {
+    "a": ">=1 <2"
+  }
+}"#;
+    let b = crate::sync::new_rc(
+      PackageJson::load_from_string(PathBuf::from("/b/package.json"), source)
+        .unwrap(),
+    );
+    let c = crate::sync::new_rc(PackageJson::load_from_value(
+      PathBuf::from("/c/package.json"),
+      json!({
+        "name": "c",
+        "dependencies": { "a": "workspace:^", "d": "workspace:*" },
+        "devDependencies": { "a": ">=1" },
+      }),
+    ));
+    let a = crate::sync::new_rc(PackageJson::load_from_value(
+      PathBuf::from("/a/package.json"),
+      json!({ "name": "a", "version": "1.0.0", "dependencies": { "a": "^1" } }),
+    ));
+    let workspace = PackageJsonWorkspace::new(vec![a, b, c]);
+    let edits =
+      workspace.bump_edits("a", &Version::parse_standard("2.0.0").unwrap());
+    assert_eq!(
+      edits
+        .iter()
+        .map(|edit| (edit.pointer(), edit.new_specifier.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        ("/dependencies/a".to_string(), "^2.0.0"),
+        ("/devDependencies/a".to_string(), "workspace:~2.0.0"),
+        ("/devDependencies/alias".to_string(), "npm:a@2.0.0"),
+        ("/peerDependencies/a".to_string(), "^2.0.0"),
+      ]
+    );
+    assert!(edits
+      .iter()
+      .all(|edit| edit.member.name.as_deref() == Some("b")));
+    let text_edits = edits
+      .iter()
+      .map(|edit| edit.text_edit(source).unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      crate::apply_text_edits(source, &text_edits),
+      r#"{
+  "name": "b",
+  "dependencies": {
+    "a": "^2.0.0",
+    "c": "^1.0.0"
+  },
+  "devDependencies": {
+    "a": "workspace:~2.0.0",
+    "alias": "npm:a@2.0.0",
+    "other": "npm:c@1.0.0"
+  },
+  "peerDependencies": {
+    "a": "^2.0.0"
+  }
+}"#
+    );
+  }
+}